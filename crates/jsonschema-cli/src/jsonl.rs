@@ -0,0 +1,209 @@
+//! Validation of JSON Lines input on a pool of worker threads.
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    mem,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, SyncSender},
+        Mutex,
+    },
+    thread,
+};
+
+use serde_json::json;
+
+use crate::{output_to_json, Output};
+
+/// Number of records sent to a worker at once.
+const BATCH_SIZE: usize = 256;
+
+/// Records as `(line number, content)` pairs together with the sequence number of the batch.
+type Batch = (usize, Vec<(usize, String)>);
+/// Reports for invalid records of a batch, `None` for valid ones.
+type Reports = (usize, Result<Vec<Option<String>>, serde_json::Error>);
+
+/// Counts of validated records.
+#[derive(Default)]
+pub(crate) struct Summary {
+    pub(crate) valid: usize,
+    pub(crate) invalid: usize,
+}
+
+/// Validate every non-empty line of `path`, or of stdin if it is `-`, as a separate instance.
+///
+/// Reports of invalid records are printed in the order of the input. Reading stops once
+/// `max_errors` invalid records are reported.
+pub(crate) fn validate(
+    validator: &jsonschema::Validator,
+    path: &Path,
+    output: Output,
+    jobs: usize,
+    max_errors: Option<usize>,
+) -> Result<Summary, Box<dyn std::error::Error>> {
+    let (name, reader): (_, Box<dyn BufRead + Send>) = if path == Path::new("-") {
+        ("<stdin>".into(), Box::new(BufReader::new(io::stdin())))
+    } else {
+        (
+            path.to_string_lossy(),
+            Box::new(BufReader::new(File::open(path)?)),
+        )
+    };
+    let jobs = jobs.max(1);
+    let stop = AtomicBool::new(false);
+    let (batches, received_batches) = mpsc::sync_channel::<Batch>(jobs * 2);
+    let received_batches = Mutex::new(received_batches);
+    let (reports, received_reports) = mpsc::channel::<Reports>();
+    let mut summary = Summary::default();
+
+    thread::scope(|scope| -> Result<Summary, Box<dyn std::error::Error>> {
+        let stop = &stop;
+        // The reader owns the sender, so workers stop once the input is exhausted
+        let reader = scope.spawn({
+            let name = &name;
+            move || read_batches(reader, name, &batches, stop)
+        });
+        for _ in 0..jobs {
+            let reports = reports.clone();
+            let (received_batches, name) = (&received_batches, &name);
+            scope.spawn(move || loop {
+                let received = received_batches
+                    .lock()
+                    .expect("Worker panicked while receiving")
+                    .recv();
+                let Ok((sequence, records)) = received else {
+                    break;
+                };
+                if stop.load(Ordering::Relaxed) {
+                    // Drain the remaining batches so the reader is not blocked
+                    continue;
+                }
+                let batch_reports = records
+                    .iter()
+                    .map(|(line, content)| check_record(validator, name, *line, content, output))
+                    .collect();
+                // The printer stops early once enough errors are reported
+                let _ = reports.send((sequence, batch_reports));
+            });
+        }
+        drop(reports);
+
+        let printed = print_reports(received_reports, max_errors, &mut summary);
+        stop.store(true, Ordering::Relaxed);
+        reader.join().expect("Reader panicked")?;
+        printed?;
+        Ok(summary)
+    })
+}
+
+/// Print reports in the order of the input until `max_errors` invalid records are reported.
+fn print_reports(
+    reports: Receiver<Reports>,
+    max_errors: Option<usize>,
+    summary: &mut Summary,
+) -> Result<(), serde_json::Error> {
+    // Batches are validated concurrently, restore the input order before printing
+    let mut pending = BTreeMap::new();
+    let mut next = 0;
+    for (sequence, batch_reports) in reports {
+        pending.insert(sequence, batch_reports);
+        while let Some(batch_reports) = pending.remove(&next) {
+            next += 1;
+            for report in batch_reports? {
+                let Some(report) = report else {
+                    summary.valid += 1;
+                    continue;
+                };
+                println!("{report}");
+                summary.invalid += 1;
+                if max_errors.is_some_and(|max_errors| summary.invalid >= max_errors) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_batches(
+    reader: impl BufRead,
+    name: &str,
+    batches: &SyncSender<Batch>,
+    stop: &AtomicBool,
+) -> io::Result<()> {
+    let mut sequence = 0;
+    let mut records = Vec::with_capacity(BATCH_SIZE);
+    for (idx, line) in reader.lines().enumerate() {
+        if stop.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let line = line.map_err(|error| {
+            io::Error::new(
+                error.kind(),
+                format!("Failed to read line {} of {name}: {error}", idx + 1),
+            )
+        })?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        records.push((idx + 1, line));
+        if records.len() == BATCH_SIZE {
+            if batches.send((sequence, mem::take(&mut records))).is_err() {
+                return Ok(());
+            }
+            sequence += 1;
+        }
+    }
+    if !records.is_empty() {
+        let _ = batches.send((sequence, records));
+    }
+    Ok(())
+}
+
+/// Validate a single record and return its report if it is invalid.
+fn check_record(
+    validator: &jsonschema::Validator,
+    name: &str,
+    line: usize,
+    content: &str,
+    output: Output,
+) -> Result<Option<String>, serde_json::Error> {
+    let instance: serde_json::Value = match serde_json::from_str(content) {
+        Ok(instance) => instance,
+        Err(error) => {
+            return Ok(Some(if output == Output::Text {
+                format!("line {line} - INVALID JSON: {error}")
+            } else {
+                json!({
+                    "instance": name,
+                    "line": line,
+                    "valid": false,
+                    "error": error.to_string(),
+                })
+                .to_string()
+            }))
+        }
+    };
+    if let Some(structured) = output.structured() {
+        let mut document = output_to_json(validator, name, None, &instance, structured)?;
+        if document["valid"] == true {
+            return Ok(None);
+        }
+        document["line"] = line.into();
+        return Ok(Some(document.to_string()));
+    }
+    let report = match validator.validate(&instance) {
+        Ok(()) => None,
+        Err(errors) => {
+            let mut report = format!("line {line} - INVALID. Errors:");
+            for (i, e) in errors.enumerate() {
+                let _ = write!(report, "\n{}. {}", i + 1, e);
+            }
+            Some(report)
+        }
+    };
+    Ok(report)
+}
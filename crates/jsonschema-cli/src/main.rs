@@ -1,71 +1,443 @@
 #![allow(clippy::print_stdout)]
 use std::{
+    ffi::OsStr,
     fs::File,
-    io::BufReader,
+    io::{self, BufReader},
     path::{Path, PathBuf},
     process::ExitCode,
 };
 
-use clap::Parser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+mod jsonl;
+mod resources;
+use serde::Deserialize;
+use serde_json::json;
 
 #[derive(Parser)]
-#[command(name = "jsonschema")]
+#[command(
+    name = "jsonschema",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// A path to a JSON instance (i.e. filename.json) to validate (may be specified multiple times).
     #[arg(short = 'i', long = "instance")]
     instances: Option<Vec<PathBuf>>,
 
+    /// A path to a JSON Lines file to validate line by line, or `-` to read from stdin.
+    #[arg(long = "jsonl", value_name = "PATH", conflicts_with = "instances")]
+    jsonl: Option<PathBuf>,
+
+    /// The number of worker threads validating JSON Lines. Defaults to the number of CPUs.
+    #[arg(short = 'j', long = "jobs", requires = "jsonl")]
+    jobs: Option<usize>,
+
+    /// Stop validating JSON Lines after this many invalid records.
+    #[arg(long = "max-errors", requires = "jsonl")]
+    max_errors: Option<usize>,
+
     /// The JSON Schema to validate with (i.e. schema.json).
     #[arg(value_parser, required_unless_present("version"))]
     schema: Option<PathBuf>,
 
+    /// The output format.
+    #[arg(long = "output", value_enum, default_value_t = Output::Text)]
+    output: Output,
+
+    /// The format of instances. Detected from the file extension by default.
+    #[arg(long = "instance-format", value_enum)]
+    instance_format: Option<Format>,
+
+    /// The format of the schema. Detected from the file extension by default.
+    #[arg(long = "schema-format", value_enum)]
+    schema_format: Option<Format>,
+
+    #[command(flatten)]
+    resource_args: ResourceArgs,
+
     /// Show program's version number and exit.
     #[arg(short = 'v', long = "version")]
     version: bool,
 }
 
-fn read_json(
+#[derive(Subcommand)]
+enum Command {
+    /// Bundle a schema with all external resources it references into a single document.
+    Bundle {
+        /// The JSON Schema to bundle.
+        schema: PathBuf,
+
+        /// The format of the schema. Detected from the file extension by default.
+        #[arg(long = "schema-format", value_enum)]
+        schema_format: Option<Format>,
+
+        #[command(flatten)]
+        resource_args: ResourceArgs,
+    },
+}
+
+// Options for resolving external resources, shared by validation and bundling
+#[derive(Args)]
+struct ResourceArgs {
+    /// The URI to resolve relative references of the schema against. Defaults to the schema path.
+    #[arg(long = "base-uri", value_name = "URI")]
+    base_uri: Option<String>,
+
+    /// Use the document at PATH as the resource with URI (may be specified multiple times).
+    #[arg(long = "resource", value_name = "URI=PATH", value_parser = resources::parse_mapping)]
+    resources: Vec<(String, PathBuf)>,
+
+    /// Look up resources with URIs starting with PREFIX in DIR (may be specified multiple times).
+    #[arg(long = "resource-dir", value_name = "PREFIX=DIR", value_parser = resources::parse_mapping)]
+    resource_dirs: Vec<(String, PathBuf)>,
+
+    /// Fail instead of fetching resources over the network.
+    #[arg(long = "no-remote")]
+    no_remote: bool,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Output {
+    /// Human-readable text.
+    Text,
+    /// One JSON document per instance with only the validation result.
+    Flag,
+    /// One JSON document per instance with the "basic" output.
+    Basic,
+    /// One JSON document per instance with the "detailed" output.
+    Detailed,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// JSON.
+    Json,
+    /// YAML. Documents of multi-document streams are validated one by one.
+    Yaml,
+    /// TOML.
+    Toml,
+}
+
+impl Format {
+    /// Detect the format from the file extension, defaulting to JSON.
+    fn from_path(path: &Path) -> Format {
+        match path.extension().and_then(OsStr::to_str) {
+            Some(extension)
+                if extension.eq_ignore_ascii_case("yaml")
+                    || extension.eq_ignore_ascii_case("yml") =>
+            {
+                Format::Yaml
+            }
+            Some(extension) if extension.eq_ignore_ascii_case("toml") => Format::Toml,
+            _ => Format::Json,
+        }
+    }
+}
+
+/// Read all documents from a file. Only YAML streams may contain more than one document.
+fn read_documents(
     path: &Path,
-) -> Result<serde_json::Result<serde_json::Value>, Box<dyn std::error::Error>> {
+    format: Option<Format>,
+) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
     let file = File::open(path)?;
     let reader = BufReader::new(file);
-    Ok(serde_json::from_reader(reader))
+    match format.unwrap_or_else(|| Format::from_path(path)) {
+        Format::Json => Ok(vec![serde_json::from_reader(reader)?]),
+        Format::Yaml => {
+            let contents = io::read_to_string(reader)?;
+            // The parser reports an empty stream as a single `null` document
+            if is_empty_yaml(&contents) {
+                return Err(format!("No documents found in {}", path.display()).into());
+            }
+            serde_yaml::Deserializer::from_str(&contents)
+                .enumerate()
+                .map(|(idx, document)| {
+                    serde_json::Value::deserialize(document).map_err(|error| {
+                        format!(
+                            "Failed to parse document {idx} of {}: {error}",
+                            path.display()
+                        )
+                        .into()
+                    })
+                })
+                .collect()
+        }
+        Format::Toml => {
+            let table: toml::Table = io::read_to_string(reader)?.parse()?;
+            Ok(vec![toml_to_json(toml::Value::Table(table))])
+        }
+    }
+}
+
+/// Whether a YAML stream has only document markers, comments and blank lines.
+fn is_empty_yaml(contents: &str) -> bool {
+    contents
+        .lines()
+        .map(str::trim)
+        .all(|line| line.is_empty() || line.starts_with('#') || line == "---" || line == "...")
+}
+
+/// Read a file that must contain exactly one document.
+fn read_document(
+    path: &Path,
+    format: Option<Format>,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let mut documents = read_documents(path, format)?;
+    if documents.len() != 1 {
+        return Err(format!(
+            "Expected a single document in {}, found {}",
+            path.display(),
+            documents.len()
+        )
+        .into());
+    }
+    Ok(documents.remove(0))
+}
+
+fn toml_to_json(value: toml::Value) -> serde_json::Value {
+    match value {
+        toml::Value::String(value) => value.into(),
+        toml::Value::Integer(value) => value.into(),
+        toml::Value::Float(value) => value.into(),
+        toml::Value::Boolean(value) => value.into(),
+        toml::Value::Datetime(value) => value.to_string().into(),
+        toml::Value::Array(items) => items.into_iter().map(toml_to_json).collect(),
+        toml::Value::Table(table) => table
+            .into_iter()
+            .map(|(key, value)| (key, toml_to_json(value)))
+            .collect::<serde_json::Map<_, _>>()
+            .into(),
+    }
+}
+
+/// The structured output formats, which print one JSON document per instance.
+#[derive(Copy, Clone)]
+enum Structured {
+    Flag,
+    Basic,
+    Detailed,
+}
+
+impl Output {
+    const fn structured(self) -> Option<Structured> {
+        match self {
+            Output::Text => None,
+            Output::Flag => Some(Structured::Flag),
+            Output::Basic => Some(Structured::Basic),
+            Output::Detailed => Some(Structured::Detailed),
+        }
+    }
+}
+
+fn error_to_json(error: &jsonschema::ValidationError) -> serde_json::Value {
+    json!({
+        "instance_path": error.instance_path.as_str(),
+        "schema_path": error.schema_path.as_str(),
+        "kind": error.kind.code(),
+        "message": error.to_string(),
+    })
+}
+
+fn unit_to_json(
+    instance_location: &jsonschema::paths::Location,
+    keyword_location: &jsonschema::paths::Location,
+    error: &jsonschema::output::ErrorDescription,
+) -> serde_json::Value {
+    json!({
+        "instance_path": instance_location.as_str(),
+        "schema_path": keyword_location.as_str(),
+        "kind": error.code(),
+        "message": error.to_string(),
+    })
+}
+
+/// Collect the errors of all units in the hierarchy.
+fn collect_errors(
+    unit: &jsonschema::output::HierarchicalOutput,
+    errors: &mut Vec<serde_json::Value>,
+) {
+    if let Some(error) = unit.error_description() {
+        errors.push(unit_to_json(
+            unit.instance_location(),
+            unit.keyword_location(),
+            error,
+        ));
+    }
+    for child in unit.children() {
+        collect_errors(child, errors);
+    }
+}
+
+/// Validate `instance` once and build its structured report.
+fn output_to_json(
+    validator: &jsonschema::Validator,
+    filename: &str,
+    index: Option<usize>,
+    instance: &serde_json::Value,
+    output: Structured,
+) -> Result<serde_json::Value, serde_json::Error> {
+    let (valid, unit, errors) = match output {
+        Structured::Flag => (validator.is_valid(instance), None, Vec::new()),
+        Structured::Basic => {
+            let basic = validator.apply(instance).basic();
+            let errors = match &basic {
+                jsonschema::BasicOutput::Valid(_) => Vec::new(),
+                jsonschema::BasicOutput::Invalid(units) => units
+                    .iter()
+                    .map(|unit| {
+                        unit_to_json(
+                            unit.instance_location(),
+                            unit.keyword_location(),
+                            unit.error_description(),
+                        )
+                    })
+                    .collect(),
+            };
+            (
+                basic.is_valid(),
+                Some(serde_json::to_value(&basic)?),
+                errors,
+            )
+        }
+        Structured::Detailed => {
+            let detailed = validator.apply(instance).detailed();
+            let mut errors = Vec::new();
+            collect_errors(&detailed, &mut errors);
+            (
+                detailed.is_valid(),
+                Some(serde_json::to_value(&detailed)?),
+                errors,
+            )
+        }
+    };
+    let mut document = json!({ "instance": filename, "valid": valid });
+    if let Some(index) = index {
+        document["document"] = index.into();
+    }
+    if let Some(unit) = unit {
+        document["output"] = unit;
+        document["errors"] = errors.into();
+    }
+    Ok(document)
 }
 
 fn validate_instances(
     instances: &[PathBuf],
     schema_path: &Path,
+    config: &Cli,
 ) -> Result<bool, Box<dyn std::error::Error>> {
     let mut success = true;
+    let output = config.output;
 
-    let schema_json = read_json(schema_path)??;
-    match jsonschema::validator_for(&schema_json) {
+    let schema_json = read_document(schema_path, config.schema_format)?;
+    match resources::options(schema_path, &config.resource_args)?.build(&schema_json) {
         Ok(validator) => {
             for instance in instances {
-                let instance_json = read_json(instance)??;
-                let validation = validator.validate(&instance_json);
+                let documents = read_documents(instance, config.instance_format)?;
                 let filename = instance.to_string_lossy();
-                match validation {
-                    Ok(()) => println!("{filename} - VALID"),
-                    Err(errors) => {
-                        success = false;
-
-                        println!("{filename} - INVALID. Errors:");
-                        for (i, e) in errors.enumerate() {
-                            println!("{}. {}", i + 1, e);
+                // Documents of multi-document streams are reported with their index
+                let is_stream = documents.len() > 1;
+                for (idx, instance_json) in documents.iter().enumerate() {
+                    let index = is_stream.then_some(idx);
+                    if let Some(structured) = output.structured() {
+                        let document = output_to_json(
+                            &validator,
+                            &filename,
+                            index,
+                            instance_json,
+                            structured,
+                        )?;
+                        success &= document["valid"] == true;
+                        println!("{document}");
+                        continue;
+                    }
+                    let label = match index {
+                        Some(idx) => format!("{filename}[{idx}]"),
+                        None => filename.to_string(),
+                    };
+                    let validation = validator.validate(instance_json);
+                    match validation {
+                        Ok(()) => println!("{label} - VALID"),
+                        Err(errors) => {
+                            success = false;
+
+                            println!("{label} - INVALID. Errors:");
+                            for (i, e) in errors.enumerate() {
+                                println!("{}. {}", i + 1, e);
+                            }
                         }
                     }
                 }
             }
         }
         Err(error) => {
-            println!("Schema is invalid. Error: {error}");
+            report_schema_error(schema_path, &error, output);
             success = false;
         }
     }
     Ok(success)
 }
 
+fn report_schema_error(schema_path: &Path, error: &jsonschema::ValidationError, output: Output) {
+    if output == Output::Text {
+        println!("Schema is invalid. Error: {error}");
+    } else {
+        let document = json!({
+            "schema": schema_path.to_string_lossy(),
+            "error": error_to_json(error),
+        });
+        println!("{document}");
+    }
+}
+
+fn validate_jsonl(
+    path: &Path,
+    schema_path: &Path,
+    config: &Cli,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let schema_json = read_document(schema_path, config.schema_format)?;
+    let validator =
+        match resources::options(schema_path, &config.resource_args)?.build(&schema_json) {
+            Ok(validator) => validator,
+            Err(error) => {
+                report_schema_error(schema_path, &error, config.output);
+                return Ok(false);
+            }
+        };
+    let jobs = config.jobs.unwrap_or_else(|| {
+        std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get)
+    });
+    let summary = jsonl::validate(&validator, path, config.output, jobs, config.max_errors)?;
+    if config.output == Output::Text {
+        println!(
+            "Summary: {} valid, {} invalid",
+            summary.valid, summary.invalid
+        );
+    } else {
+        let document = json!({
+            "summary": { "valid": summary.valid, "invalid": summary.invalid }
+        });
+        println!("{document}");
+    }
+    Ok(summary.invalid == 0)
+}
+
+fn bundle(
+    schema_path: &Path,
+    schema_format: Option<Format>,
+    resource_args: &ResourceArgs,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let schema_json = read_document(schema_path, schema_format)?;
+    let (base_uri, registry) = resources::registry(schema_path, schema_json, resource_args)?;
+    let bundled = registry.bundle(&base_uri)?;
+    println!("{}", serde_json::to_string_pretty(&bundled)?);
+    Ok(true)
+}
+
 fn main() -> ExitCode {
     let config = Cli::parse();
 
@@ -74,17 +446,33 @@ fn main() -> ExitCode {
         return ExitCode::SUCCESS;
     }
 
-    if let Some(schema) = config.schema {
-        if let Some(instances) = config.instances {
-            return match validate_instances(&instances, &schema) {
-                Ok(true) => ExitCode::SUCCESS,
-                Ok(false) => ExitCode::FAILURE,
-                Err(error) => {
-                    println!("Error: {error}");
-                    ExitCode::FAILURE
-                }
-            };
+    if let Some(Command::Bundle {
+        schema,
+        schema_format,
+        resource_args,
+    }) = &config.command
+    {
+        return to_exit_code(bundle(schema, *schema_format, resource_args));
+    }
+
+    if let Some(schema) = &config.schema {
+        if let Some(path) = &config.jsonl {
+            return to_exit_code(validate_jsonl(path, schema, &config));
+        }
+        if let Some(instances) = &config.instances {
+            return to_exit_code(validate_instances(instances, schema, &config));
         }
     }
     ExitCode::SUCCESS
 }
+
+fn to_exit_code(result: Result<bool, Box<dyn std::error::Error>>) -> ExitCode {
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            println!("Error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
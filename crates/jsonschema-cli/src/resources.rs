@@ -0,0 +1,189 @@
+//! Resolving of external resources: local copies of remote schemas and paths relative to the schema.
+use std::{
+    error::Error,
+    path::{Component, Path, PathBuf},
+};
+
+use jsonschema::{DefaultRetriever, Resource, Retrieve, Uri, ValidationOptions};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+use referencing::Registry;
+
+use crate::{read_document, ResourceArgs};
+
+/// Characters that are percent-encoded in a segment of a `file` URI path.
+const SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'\\')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// Parse a `<uri>=<path>` pair of `--resource` or `--resource-dir`.
+pub(crate) fn parse_mapping(value: &str) -> Result<(String, PathBuf), String> {
+    match value.rsplit_once('=') {
+        Some((uri, path)) if !uri.is_empty() && !path.is_empty() => {
+            Ok((uri.to_string(), PathBuf::from(path)))
+        }
+        _ => Err(format!("expected `<uri>=<path>`, got `{value}`")),
+    }
+}
+
+/// Options to build a validator for the schema at `schema_path`.
+///
+/// Relative references are resolved against the location of the schema file, unless another
+/// base URI is given.
+pub(crate) fn options(
+    schema_path: &Path,
+    args: &ResourceArgs,
+) -> Result<ValidationOptions, Box<dyn Error>> {
+    let mut options = jsonschema::options();
+    options.with_base_uri(base_uri(schema_path, args)?);
+    for (uri, resource) in resources(args)? {
+        options.with_resource(uri, resource);
+    }
+    options.with_retriever(Retriever::new(args));
+    Ok(options)
+}
+
+/// A registry with the schema at `schema_path` and all resources it references, together with
+/// the URI of the schema.
+pub(crate) fn registry(
+    schema_path: &Path,
+    schema: serde_json::Value,
+    args: &ResourceArgs,
+) -> Result<(String, Registry), Box<dyn Error>> {
+    let base_uri = base_uri(schema_path, args)?;
+    let schema = Resource::from_contents(schema)?;
+    // Resources without `$schema` use the draft of the schema, as in validation
+    let registry = Registry::options()
+        .draft(schema.draft())
+        .retriever(Box::new(Retriever::new(args)))
+        .try_from_resources(std::iter::once((base_uri.clone(), schema)).chain(resources(args)?))?;
+    Ok((base_uri, registry))
+}
+
+fn base_uri(schema_path: &Path, args: &ResourceArgs) -> Result<String, Box<dyn Error>> {
+    match &args.base_uri {
+        Some(base_uri) => Ok(base_uri.clone()),
+        None => Ok(path_to_uri(&schema_path.canonicalize()?)),
+    }
+}
+
+/// Resources given with `--resource`.
+fn resources(args: &ResourceArgs) -> Result<Vec<(String, Resource)>, Box<dyn Error>> {
+    args.resources
+        .iter()
+        .map(|(uri, path)| {
+            let contents = read_document(path, None)?;
+            let resource = Resource::from_contents(contents)
+                .map_err(|error| format!("Invalid resource '{uri}': {error}"))?;
+            Ok((uri.clone(), resource))
+        })
+        .collect()
+}
+
+/// Retrieves resources from local directories and files, and from the network unless disabled.
+struct Retriever {
+    /// Directories with local copies of resources under URI prefixes.
+    directories: Vec<(String, PathBuf)>,
+    remote: bool,
+}
+
+impl Retriever {
+    fn new(args: &ResourceArgs) -> Retriever {
+        Retriever {
+            directories: args.resource_dirs.clone(),
+            remote: !args.no_remote,
+        }
+    }
+}
+
+impl Retrieve for Retriever {
+    fn retrieve(&self, uri: &Uri<&str>) -> Result<serde_json::Value, Box<dyn Error + Send + Sync>> {
+        let path = if let Some((directory, rest)) =
+            self.directories.iter().find_map(|(prefix, directory)| {
+                uri.as_str()
+                    .strip_prefix(prefix.as_str())
+                    // The prefix ends at a segment boundary
+                    .filter(|rest| {
+                        prefix.ends_with('/') || rest.is_empty() || rest.starts_with('/')
+                    })
+                    .map(|rest| (directory, rest))
+            }) {
+            directory_path(directory, rest)?
+        } else {
+            match uri.scheme().as_str() {
+                "file" => path_from_uri(uri)?,
+                "http" | "https" if !self.remote => {
+                    return Err(format!("Retrieving remote resources is disabled: {uri}").into())
+                }
+                _ => return DefaultRetriever.retrieve(uri),
+            }
+        };
+        // Local files may be in any supported format
+        read_document(&path, None).map_err(|error| error.to_string().into())
+    }
+}
+
+/// The path of the resource at `rest` below the URI prefix of `directory`.
+///
+/// Paths leaving the directory, e.g. via percent-encoded `..` or `/`, are rejected.
+fn directory_path(directory: &Path, rest: &str) -> Result<PathBuf, Box<dyn Error + Send + Sync>> {
+    let rest = rest.strip_prefix('/').unwrap_or(rest);
+    let relative = percent_decode_str(rest).decode_utf8()?;
+    let relative = Path::new(relative.as_ref());
+    if relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        Ok(directory.join(relative))
+    } else {
+        Err(format!(
+            "Resource path '{}' is outside of '{}'",
+            relative.display(),
+            directory.display()
+        )
+        .into())
+    }
+}
+
+fn path_to_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for component in path.components() {
+        match component {
+            Component::Prefix(prefix) => {
+                // Windows drives, e.g. `C:`
+                uri.push('/');
+                uri.push_str(&prefix.as_os_str().to_string_lossy().replace("\\\\?\\", ""));
+            }
+            Component::Normal(segment) => {
+                uri.push('/');
+                uri.extend(utf8_percent_encode(&segment.to_string_lossy(), SEGMENT));
+            }
+            Component::RootDir | Component::CurDir | Component::ParentDir => {}
+        }
+    }
+    uri
+}
+
+fn path_from_uri(uri: &Uri<&str>) -> Result<PathBuf, Box<dyn Error + Send + Sync>> {
+    let path = percent_decode_str(uri.path().as_str()).decode_utf8()?;
+    #[cfg(windows)]
+    {
+        // Remove the leading slash before the drive letter
+        Ok(PathBuf::from(
+            path.trim_start_matches('/').replace('/', "\\"),
+        ))
+    }
+    #[cfg(not(windows))]
+    {
+        Ok(PathBuf::from(path.as_ref()))
+    }
+}
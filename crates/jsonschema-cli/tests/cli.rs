@@ -129,3 +129,667 @@ fn test_no_instances() {
     assert!(output.status.success());
     assert_snapshot!(String::from_utf8_lossy(&output.stdout));
 }
+
+fn run_with_output(format: &str, schema: &str, instances: &[&str]) -> (bool, String) {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(&dir, "schema.json", schema);
+    let instances: Vec<_> = instances
+        .iter()
+        .enumerate()
+        .map(|(idx, content)| create_temp_file(&dir, &format!("instance{idx}.json"), content))
+        .collect();
+
+    let mut cmd = cli();
+    cmd.arg(&schema).arg("--output").arg(format);
+    // Absolute keyword locations should not depend on the location of the temporary directory
+    cmd.args(["--base-uri", "https://example.com/schema.json"]);
+    for instance in &instances {
+        cmd.arg("--instance").arg(instance);
+    }
+    let output = cmd.output().unwrap();
+    let mut file_names: Vec<&str> = instances.iter().map(String::as_str).collect();
+    file_names.push(&schema);
+    let sanitized = sanitize_output(
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        &file_names,
+    );
+    (output.status.success(), sanitized)
+}
+
+const OUTPUT_SCHEMA: &str = r#"{"type": "object", "properties": {"name": {"type": "string", "minLength": 3}}, "required": ["name"]}"#;
+
+#[test]
+fn test_output_flag() {
+    let (success, output) = run_with_output(
+        "flag",
+        OUTPUT_SCHEMA,
+        &[r#"{"name": "John Doe"}"#, r#"{"name": "Jo"}"#],
+    );
+    assert!(!success);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_output_basic() {
+    let (success, output) = run_with_output(
+        "basic",
+        OUTPUT_SCHEMA,
+        &[r#"{"name": "John Doe"}"#, r#"{"name": "Jo"}"#, "{}"],
+    );
+    assert!(!success);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_output_detailed() {
+    let (success, output) = run_with_output(
+        "detailed",
+        OUTPUT_SCHEMA,
+        &[r#"{"name": "John Doe"}"#, r#"{"name": "Jo"}"#],
+    );
+    assert!(!success);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_output_valid() {
+    let (success, output) = run_with_output("basic", OUTPUT_SCHEMA, &[r#"{"name": "John Doe"}"#]);
+    assert!(success);
+    let document: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(document["valid"], true);
+    assert_eq!(document["errors"], serde_json::json!([]));
+}
+
+#[test]
+fn test_output_invalid_schema() {
+    let (success, output) = run_with_output("basic", r#"{"type": "invalid"}"#, &["{}"]);
+    assert!(!success);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_output_is_json_per_instance() {
+    let (_, output) = run_with_output("detailed", OUTPUT_SCHEMA, &["{}", "{}", "{}"]);
+    let documents: Vec<serde_json::Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(documents.len(), 3);
+    for (idx, document) in documents.iter().enumerate() {
+        assert_eq!(document["instance"], format!("{{FILE_{}}}", idx + 1));
+        assert_eq!(document["errors"][0]["kind"], "required");
+    }
+}
+
+#[test]
+fn test_output_false_schema() {
+    for output in ["basic", "detailed"] {
+        let (success, output) = run_with_output(output, "false", &["{}"]);
+        assert!(!success);
+        let document: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(document["errors"][0]["kind"], "falseSchema");
+        assert_eq!(document["errors"][0]["schema_path"], "");
+    }
+}
+
+const YAML_SCHEMA: &str = r"
+type: object
+properties:
+  replicas:
+    type: integer
+required: [kind]
+";
+
+#[test]
+fn test_yaml_stream() {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(&dir, "schema.yaml", YAML_SCHEMA);
+    let instance = create_temp_file(
+        &dir,
+        "stream.yml",
+        "kind: A\nreplicas: 1\n---\nkind: B\nreplicas: two\n---\nreplicas: 3\n",
+    );
+
+    let output = cli()
+        .arg(&schema)
+        .arg("-i")
+        .arg(&instance)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let sanitized = sanitize_output(
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        &[&instance],
+    );
+    assert_snapshot!(sanitized);
+}
+
+#[test]
+fn test_yaml_stream_output() {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(&dir, "schema.yaml", YAML_SCHEMA);
+    let single = create_temp_file(&dir, "single.yaml", "kind: A\n");
+    let stream = create_temp_file(&dir, "stream.yaml", "kind: A\n---\nreplicas: 1\n");
+
+    let output = cli()
+        .arg(&schema)
+        .args(["-i", &single, "-i", &stream, "--output", "flag"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let sanitized = sanitize_output(
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        &[&single, &stream],
+    );
+    assert_snapshot!(sanitized);
+}
+
+#[test]
+fn test_toml_instance() {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(
+        &dir,
+        "schema.json",
+        r#"{"properties": {"version": {"type": "string", "pattern": "^\\d+\\.\\d+$"}, "released": {"type": "string"}}}"#,
+    );
+    let valid = create_temp_file(
+        &dir,
+        "valid.toml",
+        "version = \"1.2\"\nreleased = 1979-05-27T07:32:00Z\n",
+    );
+    let invalid = create_temp_file(&dir, "invalid.toml", "version = 1.2\n");
+
+    let output = cli()
+        .arg(&schema)
+        .args(["-i", &valid, "-i", &invalid])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let sanitized = sanitize_output(
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        &[&valid, &invalid],
+    );
+    assert_snapshot!(sanitized);
+}
+
+#[test]
+fn test_explicit_formats() {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(&dir, "schema.txt", YAML_SCHEMA);
+    let instance = create_temp_file(&dir, "instance.txt", "kind = \"A\"\nreplicas = 1\n");
+
+    let output = cli()
+        .arg(&schema)
+        .args(["-i", &instance])
+        .args(["--schema-format", "yaml", "--instance-format", "toml"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        format!("{instance} - VALID\n")
+    );
+}
+
+#[test]
+fn test_yaml_parse_error_points_at_document() {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(&dir, "schema.yaml", YAML_SCHEMA);
+    let instance = create_temp_file(&dir, "broken.yaml", "kind: A\n---\nkind: [\n");
+
+    let output = cli()
+        .arg(&schema)
+        .arg("-i")
+        .arg(&instance)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with(&format!(
+            "Error: Failed to parse document 1 of {instance}: "
+        )),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn test_yaml_empty_stream() {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(&dir, "schema.yaml", YAML_SCHEMA);
+    for (name, contents) in [
+        ("empty.yaml", ""),
+        ("separator.yaml", "---\n"),
+        ("comment.yaml", "# Nothing here\n---\n"),
+    ] {
+        let instance = create_temp_file(&dir, name, contents);
+
+        let output = cli()
+            .arg(&schema)
+            .arg("-i")
+            .arg(&instance)
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(stdout, format!("Error: No documents found in {instance}\n"));
+    }
+}
+
+#[test]
+fn test_yaml_schema_stream() {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(&dir, "schema.yaml", "type: object\n---\ntype: array\n");
+    let instance = create_temp_file(&dir, "instance.json", "{}");
+
+    let output = cli()
+        .arg(&schema)
+        .arg("-i")
+        .arg(&instance)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        format!("Error: Expected a single document in {schema}, found 2\n")
+    );
+}
+
+const JSONL_RECORDS: &str = r#"{"kind": "a", "replicas": 1}
+
+{"kind": "b", "replicas": "two"}
+{"kind": "c
+{"replicas": 3}
+{"kind": "d"}
+"#;
+
+#[test]
+fn test_jsonl() {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(&dir, "schema.yaml", YAML_SCHEMA);
+    let records = create_temp_file(&dir, "records.jsonl", JSONL_RECORDS);
+
+    let output = cli()
+        .arg(&schema)
+        .args(["--jsonl", &records])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_snapshot!(String::from_utf8_lossy(&output.stdout));
+}
+
+#[test]
+fn test_jsonl_stdin_output() {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(&dir, "schema.yaml", YAML_SCHEMA);
+
+    let output = cli()
+        .arg(&schema)
+        .args(["--jsonl", "-", "--output", "flag"])
+        .write_stdin(JSONL_RECORDS)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_snapshot!(String::from_utf8_lossy(&output.stdout));
+}
+
+#[test]
+fn test_jsonl_max_errors() {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(&dir, "schema.yaml", YAML_SCHEMA);
+    let records = create_temp_file(&dir, "records.jsonl", JSONL_RECORDS);
+
+    let output = cli()
+        .arg(&schema)
+        .args(["--jsonl", &records, "--max-errors", "2"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("line 4 - INVALID JSON"), "{stdout}");
+    assert!(!stdout.contains("line 5"), "{stdout}");
+    assert!(
+        stdout.ends_with("Summary: 1 valid, 2 invalid\n"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn test_jsonl_read_error_line() {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(&dir, "schema.yaml", YAML_SCHEMA);
+    let records = dir.path().join("records.jsonl");
+    fs::write(&records, b"{\"kind\": \"a\"}\n{\"kind\": \"\xff\"}\n").unwrap();
+
+    let output = cli()
+        .arg(&schema)
+        .arg("--jsonl")
+        .arg(&records)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&format!(
+            "Error: Failed to read line 2 of {}",
+            records.display()
+        )),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn test_jsonl_is_ordered_with_many_workers() {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(&dir, "schema.yaml", YAML_SCHEMA);
+    let records: String = (0..2000)
+        .map(|idx| {
+            if idx % 300 == 0 {
+                format!("{{\"kind\": \"k\", \"replicas\": \"{idx}\"}}\n")
+            } else {
+                format!("{{\"kind\": \"k\", \"replicas\": {idx}}}\n")
+            }
+        })
+        .collect();
+    let records = create_temp_file(&dir, "records.jsonl", &records);
+
+    let run = |jobs: &str| {
+        let output = cli()
+            .arg(&schema)
+            .args(["--jsonl", &records, "--jobs", jobs])
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+    let sequential = run("1");
+    assert_eq!(run("4"), sequential);
+    let lines: Vec<_> = sequential
+        .lines()
+        .filter(|line| line.ends_with("INVALID. Errors:"))
+        .collect();
+    assert_eq!(lines.len(), 7);
+    assert_eq!(lines[0], "line 1 - INVALID. Errors:");
+    assert_eq!(lines[6], "line 1801 - INVALID. Errors:");
+    assert!(sequential.ends_with("Summary: 1993 valid, 7 invalid\n"));
+}
+
+#[test]
+fn test_jsonl_valid() {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(&dir, "schema.yaml", YAML_SCHEMA);
+    let records = create_temp_file(
+        &dir,
+        "records.jsonl",
+        "{\"kind\": \"a\"}\n{\"kind\": \"b\"}\n",
+    );
+
+    let output = cli()
+        .arg(&schema)
+        .args(["--jsonl", &records])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Summary: 2 valid, 0 invalid\n"
+    );
+}
+
+const REMOTE_SCHEMA: &str =
+    r#"{"properties": {"name": {"$ref": "https://example.com/schemas/name.json"}}}"#;
+
+#[test]
+fn test_relative_ref_is_resolved_against_schema_file() {
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join("schemas")).unwrap();
+    let schema = create_temp_file(&dir, "schemas/schema.json", r#"{"$ref": "./common.json"}"#);
+    create_temp_file(&dir, "schemas/common.yaml", "type: string");
+    create_temp_file(&dir, "schemas/common.json", r#"{"type": "string"}"#);
+    let instance = create_temp_file(&dir, "instance.json", "42");
+
+    let output = cli()
+        .arg(&schema)
+        .arg("-i")
+        .arg(&instance)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let sanitized = sanitize_output(
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        &[&instance],
+    );
+    assert_snapshot!(sanitized);
+}
+
+#[test]
+fn test_base_uri() {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(&dir, "schema.json", r#"{"$ref": "name.json"}"#);
+    let name = create_temp_file(&dir, "name.yaml", "type: string");
+    let instance = create_temp_file(&dir, "instance.json", "42");
+
+    let output = cli()
+        .arg(&schema)
+        .args([
+            "-i",
+            &instance,
+            "--base-uri",
+            "https://example.com/schemas/root.json",
+        ])
+        .arg("--resource")
+        .arg(format!("https://example.com/schemas/name.json={name}"))
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(r#"42 is not of type "string""#), "{stdout}");
+}
+
+#[test]
+fn test_resource() {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(&dir, "schema.json", REMOTE_SCHEMA);
+    let name = create_temp_file(&dir, "name.json", r#"{"type": "string"}"#);
+    let instance = create_temp_file(&dir, "instance.json", r#"{"name": 42}"#);
+
+    let output = cli()
+        .arg(&schema)
+        .args(["-i", &instance, "--no-remote", "--resource"])
+        .arg(format!("https://example.com/schemas/name.json={name}"))
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let sanitized = sanitize_output(
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        &[&instance],
+    );
+    assert_snapshot!(sanitized);
+}
+
+#[test]
+fn test_resource_dir() {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(&dir, "schema.json", REMOTE_SCHEMA);
+    fs::create_dir(dir.path().join("mirror")).unwrap();
+    create_temp_file(&dir, "mirror/name.json", r#"{"type": "string"}"#);
+    let instance = create_temp_file(&dir, "instance.json", r#"{"name": "John"}"#);
+
+    let output = cli()
+        .arg(&schema)
+        .args(["-i", &instance, "--no-remote", "--resource-dir"])
+        .arg(format!(
+            "https://example.com/schemas/={}",
+            dir.path().join("mirror").display()
+        ))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+}
+
+#[test]
+fn test_resource_dir_escape() {
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join("mirror")).unwrap();
+    let secret = create_temp_file(&dir, "secret.json", r#"{"type": "string"}"#);
+    let instance = create_temp_file(&dir, "instance.json", "1");
+
+    // A parent directory and an absolute path, both percent-encoded
+    for reference in [
+        "%2e%2e%2fsecret.json".to_string(),
+        format!("%2F{}", secret.trim_start_matches('/')),
+    ] {
+        let schema = create_temp_file(
+            &dir,
+            "schema.json",
+            &format!(r#"{{"$ref": "https://example.com/schemas/{reference}"}}"#),
+        );
+        let output = cli()
+            .arg(&schema)
+            .args(["-i", &instance, "--no-remote", "--resource-dir"])
+            .arg(format!(
+                "https://example.com/schemas/={}",
+                dir.path().join("mirror").display()
+            ))
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("is outside of"), "{stdout}");
+    }
+}
+
+#[test]
+fn test_resource_dir_sibling_prefix() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("mirror/-evil")).unwrap();
+    create_temp_file(&dir, "mirror/-evil/name.json", r#"{"type": "string"}"#);
+    let schema = create_temp_file(
+        &dir,
+        "schema.json",
+        r#"{"$ref": "https://example.com/schemas-evil/name.json"}"#,
+    );
+    let instance = create_temp_file(&dir, "instance.json", r#""John""#);
+
+    let output = cli()
+        .arg(&schema)
+        .args(["-i", &instance, "--no-remote", "--resource-dir"])
+        .arg(format!(
+            "https://example.com/schemas={}",
+            dir.path().join("mirror").display()
+        ))
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Retrieving remote resources is disabled"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn test_no_remote() {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(&dir, "schema.json", REMOTE_SCHEMA);
+    let instance = create_temp_file(&dir, "instance.json", r#"{"name": "John"}"#);
+
+    let output = cli()
+        .arg(&schema)
+        .args(["-i", &instance, "--no-remote"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_snapshot!(String::from_utf8_lossy(&output.stdout));
+}
+
+#[test]
+fn test_invalid_resource_mapping() {
+    let output = cli()
+        .args(["schema.json", "-i", "instance.json", "--resource", "urn:x"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("expected `<uri>=<path>`, got `urn:x`"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn test_bundle() {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(
+        &dir,
+        "schema.json",
+        r#"{"properties": {"name": {"$ref": "name.yaml"}, "tags": {"$ref": "https://example.com/schemas/name.json"}}}"#,
+    );
+    fs::create_dir(dir.path().join("mirror")).unwrap();
+    create_temp_file(&dir, "mirror/name.yaml", "type: string\nminLength: 1");
+    create_temp_file(&dir, "mirror/name.json", r#"{"type": "array"}"#);
+
+    let output = cli()
+        .args(["bundle", &schema, "--no-remote"])
+        .args(["--base-uri", "https://example.com/schemas/root.json"])
+        .arg("--resource-dir")
+        .arg(format!(
+            "https://example.com/schemas/={}",
+            dir.path().join("mirror").display()
+        ))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_snapshot!(String::from_utf8_lossy(&output.stdout));
+}
+
+#[test]
+fn test_bundle_validates_the_same() {
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join("schemas")).unwrap();
+    let schema = create_temp_file(
+        &dir,
+        "schemas/schema.json",
+        r#"{"items": {"$ref": "item.json#/$defs/item"}}"#,
+    );
+    create_temp_file(
+        &dir,
+        "schemas/item.json",
+        r#"{"$defs": {"item": {"type": "integer", "maximum": 5}}}"#,
+    );
+    let instance = create_temp_file(&dir, "instance.json", "[1, 7]");
+
+    let output = cli().args(["bundle", &schema]).output().unwrap();
+    assert!(output.status.success());
+    // The bundle is validated from another location without access to other files
+    let bundled = create_temp_file(
+        &dir,
+        "bundled.json",
+        &String::from_utf8_lossy(&output.stdout),
+    );
+    fs::remove_dir_all(dir.path().join("schemas")).unwrap();
+
+    let output = cli()
+        .args([&bundled, "-i", &instance, "--no-remote"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("7 is greater than the maximum of 5"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn test_bundle_unresolvable() {
+    let dir = tempdir().unwrap();
+    let schema = create_temp_file(&dir, "schema.json", REMOTE_SCHEMA);
+
+    let output = cli()
+        .args(["bundle", &schema, "--no-remote"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_snapshot!(String::from_utf8_lossy(&output.stdout));
+}
@@ -117,6 +117,33 @@ if jsonschema_rs is not None:
         benchmark(func, schema)
 
 
+if jsonschema_rs is not None:
+
+    @pytest.mark.parametrize(
+        "schema,instance",
+        (
+            ("openapi.json", "zuora.json"),
+            ("swagger.json", "kubernetes.json"),
+            ("geojson.json", "canada.json"),
+            ("citm_catalog_schema.json", "citm_catalog.json"),
+        ),
+    )
+    @pytest.mark.parametrize(
+        "func",
+        (
+            lambda validator, data: validator.is_valid(json.loads(data)),
+            lambda validator, data: validator.is_valid_json(data),
+        ),
+        ids=["py-parse", "rs-parse"],
+    )
+    @pytest.mark.benchmark(group="validate json")
+    def test_validate_json(benchmark, func, schema, instance):
+        benchmark.group = f"{instance}: {benchmark.group}"
+        validator = jsonschema_rs.validator_for(load_from_benches(schema))
+        data = (BENCHMARK_DATA / instance).read_bytes()
+        benchmark(func, validator, data)
+
+
 # Small schemas
 
 
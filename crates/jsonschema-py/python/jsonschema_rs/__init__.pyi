@@ -1,8 +1,14 @@
-from collections.abc import Iterator
-from typing import Any, Callable, TypeVar
+from collections.abc import Iterable, Iterator
+from typing import Any, Callable, Literal, Protocol, TypeVar
 
 _SchemaT = TypeVar("_SchemaT", bool, dict[str, Any])
 _FormatFunc = TypeVar("_FormatFunc", bound=Callable[[str], bool])
+_RetrieverFunc = Callable[[str], Any]
+class _Keyword(Protocol):
+    def validate(self, instance: Any) -> Iterable[str] | None: ...
+
+_KeywordFactory = Callable[[dict[str, Any], Any], _Keyword]
+_OutputFormat = Literal["flag", "basic", "detailed", "verbose"]
 
 def is_valid(
     schema: _SchemaT,
@@ -12,6 +18,9 @@ def is_valid(
     formats: dict[str, _FormatFunc] | None = None,
     validate_formats: bool | None = None,
     ignore_unknown_formats: bool = True,
+    retriever: _RetrieverFunc | None = None,
+    resources: dict[str, _SchemaT] | None = None,
+    keywords: dict[str, _KeywordFactory] | None = None,
 ) -> bool: ...
 def validate(
     schema: _SchemaT,
@@ -21,6 +30,9 @@ def validate(
     formats: dict[str, _FormatFunc] | None = None,
     validate_formats: bool | None = None,
     ignore_unknown_formats: bool = True,
+    retriever: _RetrieverFunc | None = None,
+    resources: dict[str, _SchemaT] | None = None,
+    keywords: dict[str, _KeywordFactory] | None = None,
 ) -> None: ...
 def iter_errors(
     schema: _SchemaT,
@@ -30,6 +42,9 @@ def iter_errors(
     formats: dict[str, _FormatFunc] | None = None,
     validate_formats: bool | None = None,
     ignore_unknown_formats: bool = True,
+    retriever: _RetrieverFunc | None = None,
+    resources: dict[str, _SchemaT] | None = None,
+    keywords: dict[str, _KeywordFactory] | None = None,
 ) -> Iterator[ValidationError]: ...
 
 class ValidationError(ValueError):
@@ -50,10 +65,17 @@ class Draft4Validator:
         formats: dict[str, _FormatFunc] | None = None,
         validate_formats: bool | None = None,
         ignore_unknown_formats: bool = True,
+        retriever: _RetrieverFunc | None = None,
+        resources: dict[str, _SchemaT] | None = None,
+        keywords: dict[str, _KeywordFactory] | None = None,
     ) -> None: ...
     def is_valid(self, instance: Any) -> bool: ...
     def validate(self, instance: Any) -> None: ...
     def iter_errors(self, instance: Any) -> Iterator[ValidationError]: ...
+    def is_valid_json(self, data: str | bytes) -> bool: ...
+    def validate_json(self, data: str | bytes) -> None: ...
+    def iter_errors_json(self, data: str | bytes) -> Iterator[ValidationError]: ...
+    def apply(self, instance: Any, output: _OutputFormat = "basic") -> dict[str, Any]: ...
 
 class Draft6Validator:
     def __init__(
@@ -62,10 +84,17 @@ class Draft6Validator:
         formats: dict[str, _FormatFunc] | None = None,
         validate_formats: bool | None = None,
         ignore_unknown_formats: bool = True,
+        retriever: _RetrieverFunc | None = None,
+        resources: dict[str, _SchemaT] | None = None,
+        keywords: dict[str, _KeywordFactory] | None = None,
     ) -> None: ...
     def is_valid(self, instance: Any) -> bool: ...
     def validate(self, instance: Any) -> None: ...
     def iter_errors(self, instance: Any) -> Iterator[ValidationError]: ...
+    def is_valid_json(self, data: str | bytes) -> bool: ...
+    def validate_json(self, data: str | bytes) -> None: ...
+    def iter_errors_json(self, data: str | bytes) -> Iterator[ValidationError]: ...
+    def apply(self, instance: Any, output: _OutputFormat = "basic") -> dict[str, Any]: ...
 
 class Draft7Validator:
     def __init__(
@@ -74,10 +103,17 @@ class Draft7Validator:
         formats: dict[str, _FormatFunc] | None = None,
         validate_formats: bool | None = None,
         ignore_unknown_formats: bool = True,
+        retriever: _RetrieverFunc | None = None,
+        resources: dict[str, _SchemaT] | None = None,
+        keywords: dict[str, _KeywordFactory] | None = None,
     ) -> None: ...
     def is_valid(self, instance: Any) -> bool: ...
     def validate(self, instance: Any) -> None: ...
     def iter_errors(self, instance: Any) -> Iterator[ValidationError]: ...
+    def is_valid_json(self, data: str | bytes) -> bool: ...
+    def validate_json(self, data: str | bytes) -> None: ...
+    def iter_errors_json(self, data: str | bytes) -> Iterator[ValidationError]: ...
+    def apply(self, instance: Any, output: _OutputFormat = "basic") -> dict[str, Any]: ...
 
 class Draft201909Validator:
     def __init__(
@@ -86,10 +122,17 @@ class Draft201909Validator:
         formats: dict[str, _FormatFunc] | None = None,
         validate_formats: bool | None = None,
         ignore_unknown_formats: bool = True,
+        retriever: _RetrieverFunc | None = None,
+        resources: dict[str, _SchemaT] | None = None,
+        keywords: dict[str, _KeywordFactory] | None = None,
     ) -> None: ...
     def is_valid(self, instance: Any) -> bool: ...
     def validate(self, instance: Any) -> None: ...
     def iter_errors(self, instance: Any) -> Iterator[ValidationError]: ...
+    def is_valid_json(self, data: str | bytes) -> bool: ...
+    def validate_json(self, data: str | bytes) -> None: ...
+    def iter_errors_json(self, data: str | bytes) -> Iterator[ValidationError]: ...
+    def apply(self, instance: Any, output: _OutputFormat = "basic") -> dict[str, Any]: ...
 
 class Draft202012Validator:
     def __init__(
@@ -98,14 +141,24 @@ class Draft202012Validator:
         formats: dict[str, _FormatFunc] | None = None,
         validate_formats: bool | None = None,
         ignore_unknown_formats: bool = True,
+        retriever: _RetrieverFunc | None = None,
+        resources: dict[str, _SchemaT] | None = None,
+        keywords: dict[str, _KeywordFactory] | None = None,
     ) -> None: ...
     def is_valid(self, instance: Any) -> bool: ...
     def validate(self, instance: Any) -> None: ...
     def iter_errors(self, instance: Any) -> Iterator[ValidationError]: ...
+    def is_valid_json(self, data: str | bytes) -> bool: ...
+    def validate_json(self, data: str | bytes) -> None: ...
+    def iter_errors_json(self, data: str | bytes) -> Iterator[ValidationError]: ...
+    def apply(self, instance: Any, output: _OutputFormat = "basic") -> dict[str, Any]: ...
 
 def validator_for(
     schema: _SchemaT,
     formats: dict[str, _FormatFunc] | None = None,
     validate_formats: bool | None = None,
     ignore_unknown_formats: bool = True,
+    retriever: _RetrieverFunc | None = None,
+    resources: dict[str, _SchemaT] | None = None,
+    keywords: dict[str, _KeywordFactory] | None = None,
 ) -> Draft4Validator | Draft6Validator | Draft7Validator | Draft201909Validator | Draft202012Validator: ...
@@ -0,0 +1,37 @@
+use pyo3::{
+    prelude::*,
+    types::{PyDict, PyList},
+};
+use serde_json::Value;
+
+/// Convert a JSON value into the equivalent Python object.
+pub(crate) fn to_python(py: Python<'_>, value: &Value) -> PyResult<PyObject> {
+    Ok(match value {
+        Value::Null => py.None(),
+        Value::Bool(value) => value.into_py(py),
+        Value::Number(number) => {
+            if let Some(value) = number.as_u64() {
+                value.into_py(py)
+            } else if let Some(value) = number.as_i64() {
+                value.into_py(py)
+            } else {
+                number.as_f64().unwrap_or(f64::NAN).into_py(py)
+            }
+        }
+        Value::String(value) => value.into_py(py),
+        Value::Array(items) => {
+            let list = PyList::empty_bound(py);
+            for item in items {
+                list.append(to_python(py, item)?)?;
+            }
+            list.into_py(py)
+        }
+        Value::Object(properties) => {
+            let dict = PyDict::new_bound(py);
+            for (key, value) in properties {
+                dict.set_item(key, to_python(py, value)?)?;
+            }
+            dict.into_py(py)
+        }
+    })
+}
@@ -1,3 +1,5 @@
+// PyO3 0.22 wrappers convert the errors of `PyResult` functions into `PyErr` again
+#![allow(clippy::useless_conversion)]
 use std::{
     any::Any,
     cell::RefCell,
@@ -9,12 +11,13 @@ use pyo3::{
     exceptions::{self, PyValueError},
     ffi::PyUnicode_AsUTF8AndSize,
     prelude::*,
-    types::{PyAny, PyDict, PyList, PyString, PyType},
+    types::{PyAny, PyBytes, PyDict, PyList, PyString, PyType},
     wrap_pyfunction,
 };
 #[macro_use]
 extern crate pyo3_built;
 
+mod de;
 mod ffi;
 mod ser;
 mod types;
@@ -134,11 +137,95 @@ thread_local! {
     static LAST_FORMAT_ERROR: RefCell<Option<PyErr>> = const { RefCell::new(None) };
 }
 
+/// Abort validation because a Python callback raised an exception.
+///
+/// The exception is re-raised by `handle_format_checked_panic` once the panic is caught.
+fn raise_from_callback(error: PyErr, reason: &'static str) -> ! {
+    LAST_FORMAT_ERROR.with(|last| {
+        *last.borrow_mut() = Some(error);
+    });
+    std::panic::set_hook(Box::new(|_| {}));
+    // Should be caught
+    panic!("{}", reason)
+}
+
+/// A custom keyword implemented by a Python object with a `validate(instance)` method that
+/// yields error messages.
+struct Keyword {
+    validator: Py<PyAny>,
+    location: jsonschema::paths::Location,
+}
+
+impl Keyword {
+    fn messages(&self, instance: &serde_json::Value) -> Vec<String> {
+        Python::with_gil(|py| {
+            let instance = de::to_python(py, instance)?;
+            let result = self.validator.call_method1(py, "validate", (instance,))?;
+            if result.is_none(py) {
+                return Ok(Vec::new());
+            }
+            let mut messages = Vec::new();
+            for message in result.bind(py).iter()? {
+                messages.push(message?.str()?.to_string());
+            }
+            Ok(messages)
+        })
+        .unwrap_or_else(|error| raise_from_callback(error, "Keyword validation failed"))
+    }
+}
+
+impl jsonschema::Keyword for Keyword {
+    fn validate<'i>(
+        &self,
+        instance: &'i serde_json::Value,
+        location: &jsonschema::paths::LazyLocation,
+    ) -> jsonschema::ErrorIterator<'i> {
+        let instance_path = jsonschema::paths::Location::from(location);
+        let errors: Vec<_> = self
+            .messages(instance)
+            .into_iter()
+            .map(|message| {
+                jsonschema::ValidationError::custom(
+                    self.location.clone(),
+                    instance_path.clone(),
+                    instance,
+                    message,
+                )
+            })
+            .collect();
+        Box::new(errors.into_iter())
+    }
+    fn is_valid(&self, instance: &serde_json::Value) -> bool {
+        self.messages(instance).is_empty()
+    }
+}
+
+/// Retrieves external resources by calling a Python function with their URIs.
+struct Retriever {
+    func: Py<PyAny>,
+}
+
+impl jsonschema::Retrieve for Retriever {
+    fn retrieve(
+        &self,
+        uri: &jsonschema::Uri<&str>,
+    ) -> Result<serde_json::Value, Box<dyn std::error::Error + Send + Sync>> {
+        Python::with_gil(|py| {
+            let value = self.func.call1(py, (uri.as_str(),))?;
+            ser::to_value(value.bind(py))
+        })
+        .map_err(|error| Box::new(error) as Box<dyn std::error::Error + Send + Sync>)
+    }
+}
+
 fn make_options(
     draft: Option<u8>,
     formats: Option<&Bound<'_, PyDict>>,
     validate_formats: Option<bool>,
     ignore_unknown_formats: Option<bool>,
+    retriever: Option<&Bound<'_, PyAny>>,
+    resources: Option<&Bound<'_, PyDict>>,
+    keywords: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<jsonschema::ValidationOptions> {
     let mut options = jsonschema::options();
     if let Some(raw_draft_version) = draft {
@@ -150,6 +237,66 @@ fn make_options(
     if let Some(yes) = ignore_unknown_formats {
         options.should_ignore_unknown_formats(yes);
     }
+    if let Some(retriever) = retriever {
+        if !retriever.is_callable() {
+            return Err(exceptions::PyValueError::new_err(
+                "Retriever must be a callable",
+            ));
+        }
+        options.with_retriever(Retriever {
+            func: retriever.clone().unbind(),
+        });
+    }
+    if let Some(resources) = resources {
+        for (uri, schema) in resources.iter() {
+            let uri = uri.extract::<String>()?;
+            let resource =
+                jsonschema::Resource::from_contents(ser::to_value(&schema)?).map_err(|error| {
+                    exceptions::PyValueError::new_err(format!(
+                        "Invalid resource '{}': {}",
+                        uri, error
+                    ))
+                })?;
+            options.with_resource(uri, resource);
+        }
+    }
+    if let Some(keywords) = keywords {
+        for (name, factory) in keywords.iter() {
+            if !factory.is_callable() {
+                return Err(exceptions::PyValueError::new_err(format!(
+                    "Keyword factory for '{}' must be a callable",
+                    name
+                )));
+            }
+            let factory: Py<PyAny> = factory.clone().unbind();
+            options.with_keyword(
+                name.to_string(),
+                move |parent: &serde_json::Map<String, serde_json::Value>,
+                      value: &serde_json::Value,
+                      location: jsonschema::paths::Location| {
+                    Python::with_gil(|py| {
+                        let parent = de::to_python(py, &serde_json::Value::Object(parent.clone()))?;
+                        let value = de::to_python(py, value)?;
+                        factory.call1(py, (parent, value))
+                    })
+                    .map(|validator| {
+                        Box::new(Keyword {
+                            validator,
+                            location: location.clone(),
+                        }) as Box<dyn jsonschema::Keyword>
+                    })
+                    .map_err(|error| {
+                        jsonschema::ValidationError::custom(
+                            location,
+                            jsonschema::paths::Location::new(),
+                            value,
+                            error.to_string(),
+                        )
+                    })
+                },
+            );
+        }
+    }
     if let Some(formats) = formats {
         for (name, callback) in formats.iter() {
             if !callback.is_callable() {
@@ -169,14 +316,7 @@ fn make_options(
                 name.to_string(),
                 move |value: &str| match call_py_callback(value) {
                     Ok(r) => r,
-                    Err(e) => {
-                        LAST_FORMAT_ERROR.with(|last| {
-                            *last.borrow_mut() = Some(e);
-                        });
-                        std::panic::set_hook(Box::new(|_| {}));
-                        // Should be caught
-                        panic!("Format checker failed")
-                    }
+                    Err(e) => raise_from_callback(e, "Format checker failed"),
                 },
             );
         }
@@ -220,6 +360,32 @@ fn raise_on_error(
     error.map_or_else(|| Ok(()), |err| Err(into_py_err(py, err)?))
 }
 
+/// Parse a JSON document given as `str` or `bytes` and run `callback` on it with the GIL released.
+fn with_json<T, F>(py: Python<'_>, data: &Bound<'_, PyAny>, callback: F) -> PyResult<T>
+where
+    T: Send,
+    F: FnOnce(&serde_json::Value) -> T + Send,
+{
+    let bytes = if let Ok(data) = data.downcast::<PyBytes>() {
+        data.as_bytes()
+    } else if let Ok(data) = data.downcast::<PyString>() {
+        data.to_str()?.as_bytes()
+    } else {
+        return Err(exceptions::PyTypeError::new_err(format!(
+            "Expected 'str' or 'bytes', got '{}'",
+            data.get_type().name()?
+        )));
+    };
+    py.allow_threads(|| {
+        let instance = serde_json::from_slice(bytes)?;
+        Ok(panic::catch_unwind(AssertUnwindSafe(|| {
+            callback(&instance)
+        })))
+    })
+    .map_err(|error: serde_json::Error| PyValueError::new_err(format!("Invalid JSON: {}", error)))?
+    .map_err(handle_format_checked_panic)
+}
+
 fn to_error_message(error: &jsonschema::ValidationError<'_>) -> String {
     let mut message = error.to_string();
     message.push('\n');
@@ -263,7 +429,7 @@ fn to_error_message(error: &jsonschema::ValidationError<'_>) -> String {
     message
 }
 
-/// is_valid(schema, instance, draft=None, formats=None, validate_formats=None, ignore_unknown_formats=True)
+/// is_valid(schema, instance, draft=None, formats=None, validate_formats=None, ignore_unknown_formats=True, retriever=None, resources=None, keywords=None)
 ///
 /// A shortcut for validating the input instance against the schema.
 ///
@@ -274,7 +440,7 @@ fn to_error_message(error: &jsonschema::ValidationError<'_>) -> String {
 /// instead.
 #[pyfunction]
 #[allow(unused_variables, clippy::too_many_arguments)]
-#[pyo3(signature = (schema, instance, draft=None, formats=None, validate_formats=None, ignore_unknown_formats=true))]
+#[pyo3(signature = (schema, instance, draft=None, formats=None, validate_formats=None, ignore_unknown_formats=true, retriever=None, resources=None, keywords=None))]
 fn is_valid(
     py: Python<'_>,
     schema: &Bound<'_, PyAny>,
@@ -283,8 +449,19 @@ fn is_valid(
     formats: Option<&Bound<'_, PyDict>>,
     validate_formats: Option<bool>,
     ignore_unknown_formats: Option<bool>,
+    retriever: Option<&Bound<'_, PyAny>>,
+    resources: Option<&Bound<'_, PyDict>>,
+    keywords: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<bool> {
-    let options = make_options(draft, formats, validate_formats, ignore_unknown_formats)?;
+    let options = make_options(
+        draft,
+        formats,
+        validate_formats,
+        ignore_unknown_formats,
+        retriever,
+        resources,
+        keywords,
+    )?;
     let schema = ser::to_value(schema)?;
     match options.build(&schema) {
         Ok(validator) => {
@@ -296,7 +473,7 @@ fn is_valid(
     }
 }
 
-/// validate(schema, instance, draft=None, formats=None, validate_formats=None, ignore_unknown_formats=True)
+/// validate(schema, instance, draft=None, formats=None, validate_formats=None, ignore_unknown_formats=True, retriever=None, resources=None, keywords=None)
 ///
 /// Validate the input instance and raise `ValidationError` in the error case
 ///
@@ -309,7 +486,7 @@ fn is_valid(
 /// instead.
 #[pyfunction]
 #[allow(unused_variables, clippy::too_many_arguments)]
-#[pyo3(signature = (schema, instance, draft=None, formats=None, validate_formats=None, ignore_unknown_formats=true))]
+#[pyo3(signature = (schema, instance, draft=None, formats=None, validate_formats=None, ignore_unknown_formats=true, retriever=None, resources=None, keywords=None))]
 fn validate(
     py: Python<'_>,
     schema: &Bound<'_, PyAny>,
@@ -318,8 +495,19 @@ fn validate(
     formats: Option<&Bound<'_, PyDict>>,
     validate_formats: Option<bool>,
     ignore_unknown_formats: Option<bool>,
+    retriever: Option<&Bound<'_, PyAny>>,
+    resources: Option<&Bound<'_, PyDict>>,
+    keywords: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<()> {
-    let options = make_options(draft, formats, validate_formats, ignore_unknown_formats)?;
+    let options = make_options(
+        draft,
+        formats,
+        validate_formats,
+        ignore_unknown_formats,
+        retriever,
+        resources,
+        keywords,
+    )?;
     let schema = ser::to_value(schema)?;
     match options.build(&schema) {
         Ok(validator) => raise_on_error(py, &validator, instance),
@@ -327,7 +515,7 @@ fn validate(
     }
 }
 
-/// iter_errors(schema, instance, draft=None, formats=None, validate_formats=None, ignore_unknown_formats=True)
+/// iter_errors(schema, instance, draft=None, formats=None, validate_formats=None, ignore_unknown_formats=True, retriever=None, resources=None, keywords=None)
 ///
 /// Iterate the validation errors of the input instance
 ///
@@ -339,7 +527,7 @@ fn validate(
 /// instead.
 #[pyfunction]
 #[allow(unused_variables, clippy::too_many_arguments)]
-#[pyo3(signature = (schema, instance, draft=None, formats=None, validate_formats=None, ignore_unknown_formats=true))]
+#[pyo3(signature = (schema, instance, draft=None, formats=None, validate_formats=None, ignore_unknown_formats=true, retriever=None, resources=None, keywords=None))]
 fn iter_errors(
     py: Python<'_>,
     schema: &Bound<'_, PyAny>,
@@ -348,8 +536,19 @@ fn iter_errors(
     formats: Option<&Bound<'_, PyDict>>,
     validate_formats: Option<bool>,
     ignore_unknown_formats: Option<bool>,
+    retriever: Option<&Bound<'_, PyAny>>,
+    resources: Option<&Bound<'_, PyDict>>,
+    keywords: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<ValidationErrorIter> {
-    let options = make_options(draft, formats, validate_formats, ignore_unknown_formats)?;
+    let options = make_options(
+        draft,
+        formats,
+        validate_formats,
+        ignore_unknown_formats,
+        retriever,
+        resources,
+        keywords,
+    )?;
     let schema = ser::to_value(schema)?;
     match options.build(&schema) {
         Ok(validator) => iter_on_error(py, &validator, instance),
@@ -380,13 +579,35 @@ fn handle_format_checked_panic(err: Box<dyn Any + Send>) -> PyErr {
     })
 }
 
+enum OutputFormat {
+    Flag,
+    Basic,
+    Detailed,
+    Verbose,
+}
+
+impl OutputFormat {
+    fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "flag" => Ok(OutputFormat::Flag),
+            "basic" => Ok(OutputFormat::Basic),
+            "detailed" => Ok(OutputFormat::Detailed),
+            "verbose" => Ok(OutputFormat::Verbose),
+            _ => Err(exceptions::PyValueError::new_err(format!(
+                "Unknown output format: '{}'. Expected one of 'flag', 'basic', 'detailed' or 'verbose'",
+                name
+            ))),
+        }
+    }
+}
+
 #[pyclass(module = "jsonschema_rs", subclass)]
 struct Validator {
     validator: jsonschema::Validator,
     repr: String,
 }
 
-/// validator_for(schema, formats=None, validate_formats=None, ignore_unknown_formats=True)
+/// validator_for(schema, formats=None, validate_formats=None, ignore_unknown_formats=True, retriever=None, resources=None, keywords=None)
 ///
 /// Create a validator for the input schema with automatic draft detection and default options.
 ///
@@ -395,13 +616,17 @@ struct Validator {
 ///     False
 ///
 #[pyfunction]
-#[pyo3(signature = (schema, formats=None, validate_formats=None, ignore_unknown_formats=true))]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (schema, formats=None, validate_formats=None, ignore_unknown_formats=true, retriever=None, resources=None, keywords=None))]
 fn validator_for(
     py: Python<'_>,
     schema: &Bound<'_, PyAny>,
     formats: Option<&Bound<'_, PyDict>>,
     validate_formats: Option<bool>,
     ignore_unknown_formats: Option<bool>,
+    retriever: Option<&Bound<'_, PyAny>>,
+    resources: Option<&Bound<'_, PyDict>>,
+    keywords: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<Validator> {
     validator_for_impl(
         py,
@@ -410,9 +635,13 @@ fn validator_for(
         formats,
         validate_formats,
         ignore_unknown_formats,
+        retriever,
+        resources,
+        keywords,
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 fn validator_for_impl(
     py: Python<'_>,
     schema: &Bound<'_, PyAny>,
@@ -420,6 +649,9 @@ fn validator_for_impl(
     formats: Option<&Bound<'_, PyDict>>,
     validate_formats: Option<bool>,
     ignore_unknown_formats: Option<bool>,
+    retriever: Option<&Bound<'_, PyAny>>,
+    resources: Option<&Bound<'_, PyDict>>,
+    keywords: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<Validator> {
     let obj_ptr = schema.as_ptr();
     let object_type = unsafe { pyo3::ffi::Py_TYPE(obj_ptr) };
@@ -432,7 +664,15 @@ fn validator_for_impl(
     } else {
         ser::to_value(schema)?
     };
-    let options = make_options(draft, formats, validate_formats, ignore_unknown_formats)?;
+    let options = make_options(
+        draft,
+        formats,
+        validate_formats,
+        ignore_unknown_formats,
+        retriever,
+        resources,
+        keywords,
+    )?;
     match options.build(&schema) {
         Ok(validator) => Ok(Validator {
             validator,
@@ -445,13 +685,17 @@ fn validator_for_impl(
 #[pymethods]
 impl Validator {
     #[new]
-    #[pyo3(signature = (schema, formats=None, validate_formats=None, ignore_unknown_formats=true))]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (schema, formats=None, validate_formats=None, ignore_unknown_formats=true, retriever=None, resources=None, keywords=None))]
     fn new(
         py: Python<'_>,
         schema: &Bound<'_, PyAny>,
         formats: Option<&Bound<'_, PyDict>>,
         validate_formats: Option<bool>,
         ignore_unknown_formats: Option<bool>,
+        retriever: Option<&Bound<'_, PyAny>>,
+        resources: Option<&Bound<'_, PyDict>>,
+        keywords: Option<&Bound<'_, PyDict>>,
     ) -> PyResult<Self> {
         validator_for(
             py,
@@ -459,6 +703,9 @@ impl Validator {
             formats,
             validate_formats,
             ignore_unknown_formats,
+            retriever,
+            resources,
+            keywords,
         )
     }
     /// is_valid(instance)
@@ -506,6 +753,106 @@ impl Validator {
     ) -> PyResult<ValidationErrorIter> {
         iter_on_error(py, &self.validator, instance)
     }
+    /// apply(instance, output="basic")
+    ///
+    /// Validate the input instance and return the result in one of the output formats from the JSON Schema specification
+    ///
+    ///     >>> validator = validator_for({"minimum": 5})
+    ///     >>> validator.apply(3, output="flag")
+    ///     {'valid': False}
+    ///
+    /// Supported formats are "flag", "basic", "detailed" and "verbose". Besides errors, the "basic" and
+    /// hierarchical formats contain annotations together with keyword and instance locations.
+    #[pyo3(signature = (instance, output="basic"))]
+    fn apply(
+        &self,
+        py: Python<'_>,
+        instance: &Bound<'_, PyAny>,
+        output: &str,
+    ) -> PyResult<PyObject> {
+        let format = OutputFormat::from_name(output)?;
+        let instance = ser::to_value(instance)?;
+        let output = panic::catch_unwind(AssertUnwindSafe(|| {
+            let output = self.validator.apply(&instance);
+            match format {
+                OutputFormat::Flag => Ok(serde_json::json!({ "valid": output.flag() })),
+                OutputFormat::Basic => serde_json::to_value(output.basic()),
+                OutputFormat::Detailed => serde_json::to_value(output.detailed()),
+                OutputFormat::Verbose => serde_json::to_value(output.verbose()),
+            }
+        }))
+        .map_err(handle_format_checked_panic)?
+        .map_err(|error| exceptions::PyRuntimeError::new_err(error.to_string()))?;
+        de::to_python(py, &output)
+    }
+    /// is_valid_json(data)
+    ///
+    /// Perform fast validation of a JSON document given as `str` or `bytes`.
+    ///
+    ///     >>> validator = validator_for({"minimum": 5})
+    ///     >>> validator.is_valid_json(b"3")
+    ///     False
+    ///
+    /// The document is parsed in Rust without creating Python objects, and the GIL is released
+    /// while it is parsed and validated.
+    #[pyo3(text_signature = "(data)")]
+    fn is_valid_json(&self, py: Python<'_>, data: &Bound<'_, PyAny>) -> PyResult<bool> {
+        let validator = &self.validator;
+        with_json(py, data, |instance| validator.is_valid(instance))
+    }
+    /// validate_json(data)
+    ///
+    /// Validate a JSON document given as `str` or `bytes` and raise `ValidationError` in the error case
+    ///
+    ///     >>> validator = validator_for({"minimum": 5})
+    ///     >>> validator.validate_json("3")
+    ///     ...
+    ///     ValidationError: 3 is less than the minimum of 5
+    ///
+    /// If the document is invalid, only the first occurred error is raised. Raises `ValueError` if
+    /// the input is not valid JSON.
+    #[pyo3(text_signature = "(data)")]
+    fn validate_json(&self, py: Python<'_>, data: &Bound<'_, PyAny>) -> PyResult<()> {
+        let validator = &self.validator;
+        let error = with_json(py, data, |instance| {
+            validator.validate(instance).err().map(|mut errors| {
+                errors
+                    .next()
+                    .expect("Iterator should not be empty")
+                    .into_owned()
+            })
+        })?;
+        error.map_or_else(|| Ok(()), |err| Err(into_py_err(py, err)?))
+    }
+    /// iter_errors_json(data)
+    ///
+    /// Iterate the validation errors of a JSON document given as `str` or `bytes`
+    ///
+    ///     >>> validator = validator_for({"minimum": 5})
+    ///     >>> next(validator.iter_errors_json(b"3"))
+    ///     ...
+    ///     ValidationError: 3 is less than the minimum of 5
+    #[pyo3(text_signature = "(data)")]
+    fn iter_errors_json(
+        &self,
+        py: Python<'_>,
+        data: &Bound<'_, PyAny>,
+    ) -> PyResult<ValidationErrorIter> {
+        let validator = &self.validator;
+        let errors = with_json(py, data, |instance| match validator.validate(instance) {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors
+                .map(jsonschema::ValidationError::into_owned)
+                .collect(),
+        })?;
+        let pyerrors = errors
+            .into_iter()
+            .map(|error| into_py_err(py, error))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(ValidationErrorIter {
+            iter: pyerrors.into_iter(),
+        })
+    }
     fn __repr__(&self) -> String {
         let draft = match self.validator.draft() {
             Draft::Draft4 => "Draft4",
@@ -519,7 +866,7 @@ impl Validator {
     }
 }
 
-/// Draft4Validator(schema, formats=None, validate_formats=None, ignore_unknown_formats=True)
+/// Draft4Validator(schema, formats=None, validate_formats=None, ignore_unknown_formats=True, retriever=None, resources=None, keywords=None)
 ///
 /// A JSON Schema Draft 4 validator.
 ///
@@ -533,13 +880,17 @@ struct Draft4Validator {}
 #[pymethods]
 impl Draft4Validator {
     #[new]
-    #[pyo3(signature = (schema, formats=None, validate_formats=None, ignore_unknown_formats=true))]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (schema, formats=None, validate_formats=None, ignore_unknown_formats=true, retriever=None, resources=None, keywords=None))]
     fn new(
         py: Python<'_>,
         schema: &Bound<'_, PyAny>,
         formats: Option<&Bound<'_, PyDict>>,
         validate_formats: Option<bool>,
         ignore_unknown_formats: Option<bool>,
+        retriever: Option<&Bound<'_, PyAny>>,
+        resources: Option<&Bound<'_, PyDict>>,
+        keywords: Option<&Bound<'_, PyDict>>,
     ) -> PyResult<(Self, Validator)> {
         Ok((
             Draft4Validator {},
@@ -550,12 +901,15 @@ impl Draft4Validator {
                 formats,
                 validate_formats,
                 ignore_unknown_formats,
+                retriever,
+                resources,
+                keywords,
             )?,
         ))
     }
 }
 
-/// Draft6Validator(schema, formats=None, validate_formats=None, ignore_unknown_formats=True)
+/// Draft6Validator(schema, formats=None, validate_formats=None, ignore_unknown_formats=True, retriever=None, resources=None, keywords=None)
 ///
 /// A JSON Schema Draft 6 validator.
 ///
@@ -569,13 +923,17 @@ struct Draft6Validator {}
 #[pymethods]
 impl Draft6Validator {
     #[new]
-    #[pyo3(signature = (schema, formats=None, validate_formats=None, ignore_unknown_formats=true))]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (schema, formats=None, validate_formats=None, ignore_unknown_formats=true, retriever=None, resources=None, keywords=None))]
     fn new(
         py: Python<'_>,
         schema: &Bound<'_, PyAny>,
         formats: Option<&Bound<'_, PyDict>>,
         validate_formats: Option<bool>,
         ignore_unknown_formats: Option<bool>,
+        retriever: Option<&Bound<'_, PyAny>>,
+        resources: Option<&Bound<'_, PyDict>>,
+        keywords: Option<&Bound<'_, PyDict>>,
     ) -> PyResult<(Self, Validator)> {
         Ok((
             Draft6Validator {},
@@ -586,12 +944,15 @@ impl Draft6Validator {
                 formats,
                 validate_formats,
                 ignore_unknown_formats,
+                retriever,
+                resources,
+                keywords,
             )?,
         ))
     }
 }
 
-/// Draft7Validator(schema, formats=None, validate_formats=None, ignore_unknown_formats=True)
+/// Draft7Validator(schema, formats=None, validate_formats=None, ignore_unknown_formats=True, retriever=None, resources=None, keywords=None)
 ///
 /// A JSON Schema Draft 7 validator.
 ///
@@ -605,13 +966,17 @@ struct Draft7Validator {}
 #[pymethods]
 impl Draft7Validator {
     #[new]
-    #[pyo3(signature = (schema, formats=None, validate_formats=None, ignore_unknown_formats=true))]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (schema, formats=None, validate_formats=None, ignore_unknown_formats=true, retriever=None, resources=None, keywords=None))]
     fn new(
         py: Python<'_>,
         schema: &Bound<'_, PyAny>,
         formats: Option<&Bound<'_, PyDict>>,
         validate_formats: Option<bool>,
         ignore_unknown_formats: Option<bool>,
+        retriever: Option<&Bound<'_, PyAny>>,
+        resources: Option<&Bound<'_, PyDict>>,
+        keywords: Option<&Bound<'_, PyDict>>,
     ) -> PyResult<(Self, Validator)> {
         Ok((
             Draft7Validator {},
@@ -622,12 +987,15 @@ impl Draft7Validator {
                 formats,
                 validate_formats,
                 ignore_unknown_formats,
+                retriever,
+                resources,
+                keywords,
             )?,
         ))
     }
 }
 
-/// Draft201909Validator(schema, formats=None, validate_formats=None, ignore_unknown_formats=True)
+/// Draft201909Validator(schema, formats=None, validate_formats=None, ignore_unknown_formats=True, retriever=None, resources=None, keywords=None)
 ///
 /// A JSON Schema Draft 2019-09 validator.
 ///
@@ -641,13 +1009,17 @@ struct Draft201909Validator {}
 #[pymethods]
 impl Draft201909Validator {
     #[new]
-    #[pyo3(signature = (schema, formats=None, validate_formats=None, ignore_unknown_formats=true))]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (schema, formats=None, validate_formats=None, ignore_unknown_formats=true, retriever=None, resources=None, keywords=None))]
     fn new(
         py: Python<'_>,
         schema: &Bound<'_, PyAny>,
         formats: Option<&Bound<'_, PyDict>>,
         validate_formats: Option<bool>,
         ignore_unknown_formats: Option<bool>,
+        retriever: Option<&Bound<'_, PyAny>>,
+        resources: Option<&Bound<'_, PyDict>>,
+        keywords: Option<&Bound<'_, PyDict>>,
     ) -> PyResult<(Self, Validator)> {
         Ok((
             Draft201909Validator {},
@@ -658,12 +1030,15 @@ impl Draft201909Validator {
                 formats,
                 validate_formats,
                 ignore_unknown_formats,
+                retriever,
+                resources,
+                keywords,
             )?,
         ))
     }
 }
 
-/// Draft202012Validator(schema, formats=None, validate_formats=None, ignore_unknown_formats=True)
+/// Draft202012Validator(schema, formats=None, validate_formats=None, ignore_unknown_formats=True, retriever=None, resources=None, keywords=None)
 ///
 /// A JSON Schema Draft 2020-12 validator.
 ///
@@ -677,13 +1052,17 @@ struct Draft202012Validator {}
 #[pymethods]
 impl Draft202012Validator {
     #[new]
-    #[pyo3(signature = (schema, formats=None, validate_formats=None, ignore_unknown_formats=true))]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (schema, formats=None, validate_formats=None, ignore_unknown_formats=true, retriever=None, resources=None, keywords=None))]
     fn new(
         py: Python<'_>,
         schema: &Bound<'_, PyAny>,
         formats: Option<&Bound<'_, PyDict>>,
         validate_formats: Option<bool>,
         ignore_unknown_formats: Option<bool>,
+        retriever: Option<&Bound<'_, PyAny>>,
+        resources: Option<&Bound<'_, PyDict>>,
+        keywords: Option<&Bound<'_, PyDict>>,
     ) -> PyResult<(Self, Validator)> {
         Ok((
             Draft202012Validator {},
@@ -694,6 +1073,9 @@ impl Draft202012Validator {
                 formats,
                 validate_formats,
                 ignore_unknown_formats,
+                retriever,
+                resources,
+                keywords,
             )?,
         ))
     }
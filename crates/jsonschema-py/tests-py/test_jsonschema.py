@@ -364,3 +364,235 @@ def test_ignore_unknown_formats(cls, ignore_unknown_formats, should_raise):
     else:
         validator = cls(unknown_format_schema, ignore_unknown_formats=ignore_unknown_formats)
         assert validator.is_valid("any string")
+
+
+def test_apply_flag():
+    validator = validator_for({"minimum": 5})
+    assert validator.apply(3, output="flag") == {"valid": False}
+    assert validator.apply(7, output="flag") == {"valid": True}
+
+
+def test_apply_basic():
+    validator = validator_for({"properties": {"age": {"minimum": 18, "title": "Age"}}})
+    assert validator.apply({"age": 16}) == {
+        "valid": False,
+        "errors": [
+            {
+                "keywordLocation": "/properties/age/minimum",
+                "instanceLocation": "/age",
+                "error": "16 is less than the minimum of 18",
+            }
+        ],
+    }
+    output = validator.apply({"age": 21}, output="basic")
+    assert output["valid"] is True
+    assert {
+        "keywordLocation": "/properties/age",
+        "instanceLocation": "/age",
+        "annotations": {"title": "Age"},
+    } in output["annotations"]
+
+
+@pytest.mark.parametrize("output", ("detailed", "verbose"))
+def test_apply_hierarchical(output):
+    validator = validator_for({"minimum": 5})
+    result = validator.apply(3, output=output)
+    assert result["valid"] is False
+    assert result["keywordLocation"] == ""
+    assert result["instanceLocation"] == ""
+
+
+def test_apply_unknown_output():
+    validator = validator_for({"minimum": 5})
+    with pytest.raises(ValueError, match="Unknown output format: 'unknown'"):
+        validator.apply(3, output="unknown")
+
+
+def test_apply_with_format_exception():
+    def is_currency(_):
+        raise ValueError("Invalid currency")
+
+    validator = validator_for(
+        {"type": "string", "format": "currency"}, formats={"currency": is_currency}, validate_formats=True
+    )
+    with pytest.raises(ValueError, match="Invalid currency"):
+        validator.apply("USD")
+
+
+PERSON = {"type": "object", "properties": {"name": {"type": "string"}}, "required": ["name"]}
+
+
+def test_retriever():
+    def retrieve(uri):
+        assert uri == "https://example.com/person.json"
+        return PERSON
+
+    validator = validator_for({"$ref": "https://example.com/person.json"}, retriever=retrieve)
+    assert validator.is_valid({"name": "Alice"})
+    assert not validator.is_valid({"name": 42})
+
+
+def test_retriever_with_exception():
+    def retrieve(uri):
+        raise KeyError(uri)
+
+    with pytest.raises(
+        ValidationError,
+        match="Resource 'https://example.com/person.json' is not present in a registry and retrieving it failed: KeyError",
+    ):
+        validator_for({"$ref": "https://example.com/person.json"}, retriever=retrieve)
+
+
+def test_retriever_invalid_callback():
+    with pytest.raises(ValueError, match="Retriever must be a callable"):
+        validator_for({"$ref": "https://example.com/person.json"}, retriever=42)
+
+
+def test_resources_in_shortcuts():
+    schema = {"$ref": "https://example.com/person.json"}
+    resources = {"https://example.com/person.json": PERSON}
+    assert is_valid(schema, {"name": "Alice"}, resources=resources)
+    with pytest.raises(ValidationError, match="\"name\" is a required property"):
+        validate(schema, {}, resources=resources)
+
+
+@pytest.mark.parametrize("cls", (validator_for, Draft7Validator, Draft202012Validator))
+def test_resources(cls):
+    def retrieve(uri):
+        raise AssertionError("Resources should not be retrieved")
+
+    validator = cls(
+        {"$ref": "https://example.com/person.json"},
+        retriever=retrieve,
+        resources={"https://example.com/person.json": PERSON},
+    )
+    assert validator.is_valid({"name": "Alice"})
+    assert not validator.is_valid({})
+
+
+def test_invalid_resource():
+    with pytest.raises(ValueError, match="Invalid resource 'https://example.com/person.json'"):
+        validator_for({}, resources={"https://example.com/person.json": {"$schema": "unknown"}})
+
+
+class EvenSum:
+    def __init__(self, parent_schema, value):
+        self.enabled = value
+
+    def validate(self, instance):
+        if self.enabled and isinstance(instance, list) and sum(instance) % 2:
+            yield f"The sum of {instance} is odd"
+
+
+def test_custom_keyword():
+    validator = validator_for({"properties": {"numbers": {"even-sum": True}}}, keywords={"even-sum": EvenSum})
+    assert validator.is_valid({"numbers": [1, 3]})
+    assert not validator.is_valid({"numbers": [1, 2]})
+    error = next(validator.iter_errors({"numbers": [1, 2]}))
+    assert error.message == "The sum of [1, 2] is odd"
+    assert error.schema_path == ["properties", "numbers", "even-sum"]
+    assert error.instance_path == ["numbers"]
+
+
+def test_custom_keyword_parent_schema():
+    class MaxOf:
+        def __init__(self, parent_schema, value):
+            self.limit = parent_schema[value]
+
+        def validate(self, instance):
+            if instance > self.limit:
+                return [f"{instance} is greater than {self.limit}"]
+            return None
+
+    validator = validator_for({"limit": 3, "max-of": "limit"}, keywords={"max-of": MaxOf})
+    assert validator.is_valid(2)
+    with pytest.raises(ValidationError, match="4 is greater than 3"):
+        validator.validate(4)
+
+
+@pytest.mark.parametrize("cls", (Draft4Validator, Draft7Validator, Draft202012Validator))
+def test_custom_keyword_in_draft_validators(cls):
+    validator = cls({"even-sum": True}, keywords={"even-sum": EvenSum})
+    assert validator.apply([1, 2], output="basic")["errors"] == [
+        {"keywordLocation": "/even-sum", "instanceLocation": "", "error": "The sum of [1, 2] is odd"}
+    ]
+    assert not is_valid({"even-sum": True}, [1, 2], keywords={"even-sum": EvenSum})
+
+
+def test_custom_keyword_invalid_factory():
+    with pytest.raises(ValueError, match="Keyword factory for 'even-sum' must be a callable"):
+        validator_for({"even-sum": True}, keywords={"even-sum": 42})
+
+
+def test_custom_keyword_factory_with_exception():
+    class Invalid:
+        def __init__(self, parent_schema, value):
+            raise ValueError("Expected a boolean")
+
+    with pytest.raises(ValidationError, match="Expected a boolean") as exc:
+        validator_for({"even-sum": 42}, keywords={"even-sum": Invalid})
+    assert exc.value.schema_path == ["even-sum"]
+
+
+def test_custom_keyword_with_exception():
+    class Failing:
+        def __init__(self, parent_schema, value):
+            pass
+
+        def validate(self, instance):
+            raise TypeError("Unexpected instance")
+
+    validator = validator_for({"failing": True}, keywords={"failing": Failing})
+    with pytest.raises(TypeError, match="Unexpected instance"):
+        validator.is_valid(1)
+    with pytest.raises(TypeError, match="Unexpected instance"):
+        validator.validate(1)
+    with pytest.raises(TypeError, match="Unexpected instance"):
+        for _ in validator.iter_errors(1):
+            pass
+    with pytest.raises(TypeError, match="Unexpected instance"):
+        validator.apply(1)
+
+
+@pytest.mark.parametrize("data", ('{"age": 16}', b'{"age": 16}'))
+def test_json_input(data):
+    validator = validator_for({"properties": {"age": {"minimum": 18}}})
+    assert not validator.is_valid_json(data)
+    with pytest.raises(ValidationError, match="16 is less than the minimum of 18") as exc:
+        validator.validate_json(data)
+    assert exc.value.instance_path == ["age"]
+    errors = list(validator.iter_errors_json(data))
+    assert len(errors) == 1
+    assert errors[0].schema_path == ["properties", "age", "minimum"]
+
+
+def test_json_input_valid():
+    validator = validator_for({"properties": {"age": {"minimum": 18}}})
+    assert validator.is_valid_json(b'{"age": 21}')
+    assert validator.validate_json('{"age": 21}') is None
+    assert list(validator.iter_errors_json(b'{"age": 21}')) == []
+
+
+@pytest.mark.parametrize("method", ("is_valid_json", "validate_json", "iter_errors_json"))
+def test_json_input_invalid_json(method):
+    validator = validator_for({"minimum": 5})
+    with pytest.raises(ValueError, match="Invalid JSON: EOF while parsing"):
+        getattr(validator, method)(b'{"age": ')
+
+
+@pytest.mark.parametrize("method", ("is_valid_json", "validate_json", "iter_errors_json"))
+def test_json_input_invalid_type(method):
+    validator = validator_for({"minimum": 5})
+    with pytest.raises(TypeError, match="Expected 'str' or 'bytes', got 'dict'"):
+        getattr(validator, method)({"age": 16})
+
+
+def test_json_input_with_format_exception():
+    def is_currency(_):
+        raise ValueError("Invalid currency")
+
+    validator = validator_for(
+        {"type": "string", "format": "currency"}, formats={"currency": is_currency}, validate_formats=True
+    )
+    with pytest.raises(ValueError, match="Invalid currency"):
+        validator.is_valid_json('"USD"')
@@ -0,0 +1,457 @@
+//! Bundling of a schema with its external resources into a single compound document.
+use std::collections::VecDeque;
+
+use ahash::AHashSet;
+use fluent_uri::Uri;
+use serde_json::{Map, Value};
+
+use crate::{meta, uri, Draft, Error, Registry, Resource};
+
+impl Registry {
+    /// Bundle the resource at `root_uri` with all external resources it references.
+    ///
+    /// Referenced resources are embedded into `$defs` (`definitions` before Draft 2019-09) of the
+    /// root, keyed and identified by their canonical URIs. As in a compound schema document, each
+    /// `$ref`, `$dynamicRef` and anchor keeps resolving to the same contents without retrieving
+    /// anything. The root gets an identifier equal to `root_uri` if it has none and some resources
+    /// are embedded. Meta-schemas are not embedded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a reference can't be resolved, or if a resource can't be embedded
+    /// without changing how references resolve. This happens when a Draft 4, 6 or 7 resource
+    /// contains `$ref` next to which the identifier would be ignored, when a document declares
+    /// an identifier other than the URI it is referenced by, or when a resource of another draft
+    /// is referenced from a Draft 4, 6 or 7 root, which would interpret it under its own draft.
+    pub fn bundle(&self, root_uri: &str) -> Result<Value, Error> {
+        let root_uri = uri::from_str(root_uri.trim_end_matches('#'))?;
+        let root = self.get_or_retrieve(&root_uri)?;
+
+        // Identifiers defined in already bundled documents and references to follow
+        let mut defined = AHashSet::new();
+        let mut references = VecDeque::new();
+        defined.insert(root_uri.clone());
+        collect(
+            root.draft(),
+            root.contents(),
+            &root_uri,
+            &mut defined,
+            &mut references,
+        )?;
+
+        let mut embedded = Vec::new();
+        while let Some(uri) = references.pop_front() {
+            if defined.contains(&uri) || is_meta_schema(&uri) {
+                continue;
+            }
+            let resource = self.get_or_retrieve(&uri)?;
+            let mut ids = AHashSet::new();
+            ids.insert(uri.clone());
+            collect(
+                resource.draft(),
+                resource.contents(),
+                &uri,
+                &mut ids,
+                &mut references,
+            )?;
+            defined.extend(ids.iter().cloned());
+            embedded.push((uri, resource, ids));
+        }
+        // A resource might be embedded in a document that was reached later
+        let nested: AHashSet<_> = embedded
+            .iter()
+            .flat_map(|(uri, _, ids)| ids.iter().filter(move |id| *id != uri))
+            .cloned()
+            .collect();
+        embedded.retain(|(uri, _, _)| !nested.contains(uri));
+        embedded.sort_unstable_by(|(left, _, _), (right, _, _)| left.as_str().cmp(right.as_str()));
+
+        let mut bundled = root.contents().clone();
+        if embedded.is_empty() {
+            return Ok(bundled);
+        }
+        let draft = root.draft();
+        let Value::Object(object) = &mut bundled else {
+            unreachable!("Boolean schemas have no references");
+        };
+        if draft.id_of(root.contents()).is_none() {
+            // Relative references of the root are resolved against `root_uri`
+            identify(draft, object, &root_uri)?;
+        }
+        let definitions = object
+            .entry(definitions_keyword(draft))
+            .or_insert_with(|| Value::Object(Map::new()));
+        let Value::Object(definitions) = definitions else {
+            return Err(Error::unbundlable(
+                root_uri.as_str(),
+                format!("`{}` is not an object", definitions_keyword(draft)),
+            ));
+        };
+        for (uri, resource, _) in embedded {
+            definitions.insert(uri.as_str().to_string(), embed(draft, &uri, resource)?);
+        }
+        Ok(bundled)
+    }
+}
+
+/// Collect identifiers and references of external resources within a resource.
+fn collect(
+    draft: Draft,
+    contents: &Value,
+    base: &Uri<String>,
+    ids: &mut AHashSet<Uri<String>>,
+    references: &mut VecDeque<Uri<String>>,
+) -> Result<(), Error> {
+    let draft = draft.detect(contents)?;
+    let resolved;
+    let base = if let Some(id) = draft.id_of(contents) {
+        resolved = uri::resolve_against(&base.borrow(), id.trim_end_matches('#'))?;
+        ids.insert(resolved.clone());
+        &resolved
+    } else {
+        base
+    };
+    for keyword in ["$ref", "$dynamicRef"] {
+        if let Some(reference) = contents.get(keyword).and_then(Value::as_str) {
+            let mut target = uri::resolve_against(&base.borrow(), reference)?;
+            target.set_fragment(None);
+            if !ids.contains(&target) {
+                references.push_back(target);
+            }
+        }
+    }
+    for subresource in draft.subresources_of(contents) {
+        collect(draft, subresource, base, ids, references)?;
+    }
+    Ok(())
+}
+
+/// Prepare a resource to be embedded into a document of the given draft.
+fn embed(root_draft: Draft, uri: &Uri<String>, resource: &Resource) -> Result<Value, Error> {
+    let draft = resource.draft();
+    let mut object = match resource.contents() {
+        Value::Object(object) => object.clone(),
+        // Boolean schemas can't have identifiers
+        Value::Bool(true) => Map::new(),
+        Value::Bool(false) => Map::from_iter([("not".to_string(), Value::Object(Map::new()))]),
+        _ => {
+            return Err(Error::unbundlable(
+                uri.as_str(),
+                "it is not a schema".to_string(),
+            ))
+        }
+    };
+    if draft != root_draft && root_draft <= Draft::Draft7 {
+        // Older drafts ignore `$schema` in subschemas
+        return Err(Error::unbundlable(
+            uri.as_str(),
+            "Draft 4, 6 and 7 documents can't embed resources of other drafts".to_string(),
+        ));
+    }
+    identify(draft, &mut object, uri)?;
+    if draft != root_draft && !object.contains_key("$schema") {
+        // Embedded resources are interpreted under the draft of the root otherwise
+        object.insert("$schema".to_string(), meta_schema_uri(draft).into());
+    }
+    Ok(Value::Object(object))
+}
+
+/// Make `uri` the identifier of a resource.
+fn identify(draft: Draft, object: &mut Map<String, Value>, uri: &Uri<String>) -> Result<(), Error> {
+    if draft <= Draft::Draft7 && object.contains_key("$ref") {
+        return Err(Error::unbundlable(
+            uri.as_str(),
+            "its identifier would be ignored next to `$ref`".to_string(),
+        ));
+    }
+    let keyword = if draft == Draft::Draft4 { "id" } else { "$id" };
+    if object
+        .get(keyword)
+        .and_then(Value::as_str)
+        .is_some_and(|id| id.starts_with('#'))
+    {
+        return Err(Error::unbundlable(
+            uri.as_str(),
+            "its identifier is an anchor".to_string(),
+        ));
+    }
+    if let Some(id) = draft.id_of(&Value::Object(object.clone())) {
+        let resolved = uri::resolve_against(&uri.borrow(), id.trim_end_matches('#'))?;
+        if resolved != *uri {
+            return Err(Error::unbundlable(
+                uri.as_str(),
+                format!("it declares another identifier '{resolved}'"),
+            ));
+        }
+    }
+    // Relative identifiers would be resolved against the root
+    object.insert(keyword.to_string(), uri.as_str().into());
+    Ok(())
+}
+
+fn definitions_keyword(draft: Draft) -> &'static str {
+    if draft >= Draft::Draft201909 {
+        "$defs"
+    } else {
+        "definitions"
+    }
+}
+
+fn meta_schema_uri(draft: Draft) -> &'static str {
+    match draft {
+        Draft::Draft4 => "http://json-schema.org/draft-04/schema#",
+        Draft::Draft6 => "http://json-schema.org/draft-06/schema#",
+        Draft::Draft7 => "http://json-schema.org/draft-07/schema#",
+        Draft::Draft201909 => "https://json-schema.org/draft/2019-09/schema",
+        Draft::Draft202012 => "https://json-schema.org/draft/2020-12/schema",
+    }
+}
+
+fn is_meta_schema(uri: &Uri<String>) -> bool {
+    meta::META_SCHEMAS
+        .iter()
+        .any(|(meta_schema, _)| meta_schema.trim_end_matches('#') == uri.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use ahash::AHashMap;
+    use fluent_uri::Uri;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    use crate::{Registry, Resource, Retrieve};
+
+    struct InMemory(AHashMap<&'static str, Value>);
+
+    impl Retrieve for InMemory {
+        fn retrieve(
+            &self,
+            uri: &Uri<&str>,
+        ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+            self.0
+                .get(uri.as_str())
+                .cloned()
+                .ok_or_else(|| format!("Failed to find {uri}").into())
+        }
+    }
+
+    fn bundle(root: Value, remote: &[(&'static str, Value)]) -> Result<Value, crate::Error> {
+        let registry = Registry::options()
+            .retriever(Box::new(InMemory(remote.iter().cloned().collect())))
+            .try_new(
+                "https://example.com/root.json",
+                Resource::from_contents(root).expect("Invalid resource"),
+            )
+            .expect("Invalid resources");
+        registry.bundle("https://example.com/root.json")
+    }
+
+    #[test_case(
+        json!({"properties": {"a": {"$ref": "#/$defs/a"}}, "$defs": {"a": {"type": "string"}}}),
+        &[],
+        &json!({"properties": {"a": {"$ref": "#/$defs/a"}}, "$defs": {"a": {"type": "string"}}});
+        "no external references"
+    )]
+    #[test_case(
+        json!({"properties": {"a": {"$ref": "a.json"}, "b": {"$ref": "nested/b.json#/$defs/b"}}}),
+        &[
+            ("https://example.com/a.json", json!({"$ref": "nested/b.json"})),
+            ("https://example.com/nested/b.json", json!({"$defs": {"b": {"type": "string"}}})),
+        ],
+        &json!({
+            "$id": "https://example.com/root.json",
+            "properties": {"a": {"$ref": "a.json"}, "b": {"$ref": "nested/b.json#/$defs/b"}},
+            "$defs": {
+                "https://example.com/a.json": {
+                    "$id": "https://example.com/a.json",
+                    "$ref": "nested/b.json"
+                },
+                "https://example.com/nested/b.json": {
+                    "$id": "https://example.com/nested/b.json",
+                    "$defs": {"b": {"type": "string"}}
+                }
+            }
+        });
+        "relative references"
+    )]
+    #[test_case(
+        json!({
+            "$id": "https://example.com/strict-tree",
+            "$dynamicAnchor": "node",
+            "$ref": "tree",
+            "unevaluatedProperties": false
+        }),
+        &[(
+            "https://example.com/tree",
+            json!({
+                "$id": "https://example.com/tree",
+                "$dynamicAnchor": "node",
+                "type": "object",
+                "properties": {
+                    "children": {"type": "array", "items": {"$dynamicRef": "#node"}}
+                }
+            }),
+        )],
+        &json!({
+            "$id": "https://example.com/strict-tree",
+            "$dynamicAnchor": "node",
+            "$ref": "tree",
+            "unevaluatedProperties": false,
+            "$defs": {
+                "https://example.com/tree": {
+                    "$id": "https://example.com/tree",
+                    "$dynamicAnchor": "node",
+                    "type": "object",
+                    "properties": {
+                        "children": {"type": "array", "items": {"$dynamicRef": "#node"}}
+                    }
+                }
+            }
+        });
+        "dynamic references"
+    )]
+    #[test_case(
+        json!({"allOf": [{"$ref": "inner.json#anchor"}, {"$ref": "outer.json"}]}),
+        &[
+            (
+                "https://example.com/inner.json",
+                json!({"$id": "https://example.com/inner.json", "$anchor": "anchor"}),
+            ),
+            (
+                "https://example.com/outer.json",
+                json!({"$defs": {"inner": {"$id": "inner.json", "$anchor": "anchor"}}}),
+            ),
+        ],
+        &json!({
+            "$id": "https://example.com/root.json",
+            "allOf": [{"$ref": "inner.json#anchor"}, {"$ref": "outer.json"}],
+            "$defs": {
+                "https://example.com/outer.json": {
+                    "$id": "https://example.com/outer.json",
+                    "$defs": {"inner": {"$id": "inner.json", "$anchor": "anchor"}}
+                }
+            }
+        });
+        "nested resources are embedded once"
+    )]
+    #[test_case(
+        json!({"items": {"$ref": "legacy.json"}, "not": {"$ref": "false.json"}}),
+        &[
+            ("https://example.com/legacy.json", json!({"$schema": "http://json-schema.org/draft-04/schema#", "id": "legacy.json", "type": "integer"})),
+            ("https://example.com/false.json", json!(false)),
+        ],
+        &json!({
+            "$id": "https://example.com/root.json",
+            "items": {"$ref": "legacy.json"},
+            "not": {"$ref": "false.json"},
+            "$defs": {
+                "https://example.com/false.json": {"$id": "https://example.com/false.json", "not": {}},
+                "https://example.com/legacy.json": {
+                    "$schema": "http://json-schema.org/draft-04/schema#",
+                    "id": "https://example.com/legacy.json",
+                    "type": "integer"
+                }
+            }
+        });
+        "other drafts and boolean schemas"
+    )]
+    #[test_case(
+        json!({"$schema": "http://json-schema.org/draft-07/schema#", "items": {"$ref": "a.json"}}),
+        &[("https://example.com/a.json", json!({"$schema": "http://json-schema.org/draft-07/schema#", "type": "string"}))],
+        &json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "$id": "https://example.com/root.json",
+            "items": {"$ref": "a.json"},
+            "definitions": {
+                "https://example.com/a.json": {
+                    "$id": "https://example.com/a.json",
+                    "$schema": "http://json-schema.org/draft-07/schema#",
+                    "type": "string"
+                }
+            }
+        });
+        "legacy root"
+    )]
+    #[test_case(
+        json!({"$ref": "https://json-schema.org/draft/2020-12/schema"}),
+        &[],
+        &json!({"$ref": "https://json-schema.org/draft/2020-12/schema"});
+        "meta-schemas are not embedded"
+    )]
+    fn test_bundle(root: Value, remote: &[(&'static str, Value)], expected: &Value) {
+        let bundled = bundle(root, remote).expect("Failed to bundle");
+        assert_eq!(&bundled, expected);
+    }
+
+    #[test]
+    fn test_bundle_is_self_contained() {
+        let bundled = bundle(
+            json!({"properties": {"a": {"$ref": "a.json"}}}),
+            &[
+                (
+                    "https://example.com/a.json",
+                    json!({"$defs": {"b": {"$ref": "c.json"}}, "$ref": "#/$defs/b"}),
+                ),
+                ("https://example.com/c.json", json!({"type": "string"})),
+            ],
+        )
+        .expect("Failed to bundle");
+        // Nothing is retrieved and the bundle may be loaded from any location
+        let registry = Registry::try_new(
+            "https://other.example.com/bundle.json",
+            Resource::from_contents(bundled).expect("Invalid resource"),
+        )
+        .expect("Invalid resources");
+        let resolver = registry
+            .try_resolver("https://example.com/root.json")
+            .expect("Invalid base URI");
+        let resolved = resolver.lookup("a.json#/$defs/b").expect("Lookup failed");
+        let resolved = resolved
+            .resolver()
+            .lookup(resolved.contents()["$ref"].as_str().unwrap())
+            .expect("Lookup failed");
+        assert_eq!(
+            resolved.contents(),
+            &json!({"$id": "https://example.com/c.json", "type": "string"})
+        );
+    }
+
+    #[test_case(
+        json!({"$ref": "a.json"}),
+        &[("https://example.com/a.json", json!({"$schema": "http://json-schema.org/draft-07/schema#", "$ref": "b.json"})), ("https://example.com/b.json", json!({}))],
+        "Resource 'https://example.com/a.json' can not be bundled: its identifier would be ignored next to `$ref`";
+        "legacy reference"
+    )]
+    #[test_case(
+        json!({"$ref": "a.json"}),
+        &[("https://example.com/a.json", json!({"$id": "https://example.com/other.json"}))],
+        "Resource 'https://example.com/a.json' can not be bundled: it declares another identifier 'https://example.com/other.json'";
+        "another identifier"
+    )]
+    #[test_case(
+        json!({"$schema": "http://json-schema.org/draft-07/schema#", "items": {"$ref": "a.json"}}),
+        &[("https://example.com/a.json", json!({"$schema": "https://json-schema.org/draft/2020-12/schema", "type": "string"}))],
+        "Resource 'https://example.com/a.json' can not be bundled: Draft 4, 6 and 7 documents can't embed resources of other drafts";
+        "legacy root with another draft"
+    )]
+    fn test_bundle_error(root: Value, remote: &[(&'static str, Value)], expected: &str) {
+        let error = bundle(root, remote).expect_err("Should fail");
+        assert_eq!(error.to_string(), expected);
+    }
+
+    #[test]
+    fn test_bundle_unknown_root() {
+        let registry = Registry::try_new(
+            "https://example.com/root.json",
+            Resource::from_contents(json!({})).expect("Invalid resource"),
+        )
+        .expect("Invalid resources");
+        let error = registry
+            .bundle("https://example.com/unknown.json")
+            .expect_err("Should fail");
+        assert!(error.to_string().starts_with(
+            "Resource 'https://example.com/unknown.json' is not present in a registry"
+        ));
+    }
+}
@@ -0,0 +1,470 @@
+//! Dereferencing of schemas by inlining the contents of their references.
+use ahash::{AHashMap, AHashSet};
+use serde_json::{Map, Value};
+
+use crate::{uri, Draft, Error, Registry, Resolver};
+
+impl Registry {
+    /// Produce a copy of the resource at `uri` with every `$ref` replaced by the contents it
+    /// points to.
+    ///
+    /// Inlined contents keep their meaning outside of their original location: relative `$id`s
+    /// become absolute, identifiers of resources inlined more than once are kept on the first copy
+    /// only, and anchors of contents inlined without their resource are removed. Since Draft
+    /// 2019-09, keywords next to `$ref` are kept and the referenced contents are added to their
+    /// `allOf`. `$dynamicRef` and `$recursiveRef` depend on the dynamic scope and are kept as
+    /// they are.
+    ///
+    /// A reference to contents that are being inlined is a cycle. It is kept as a `$ref` to the
+    /// location of these contents in the result, and the result gets an identifier equal to
+    /// `uri` if it has none. Use [`Registry::dereference_strict`] to report cycles instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URI is invalid or if a reference can't be resolved.
+    pub fn dereference(&self, uri: &str) -> Result<Value, Error> {
+        self.dereference_impl(uri, false)
+    }
+    /// Like [`Registry::dereference`], but fails on cyclic references.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::CyclicReference`] for the first reference that leads to contents that
+    /// are being inlined, or any error of [`Registry::dereference`].
+    pub fn dereference_strict(&self, uri: &str) -> Result<Value, Error> {
+        self.dereference_impl(uri, true)
+    }
+    fn dereference_impl(&self, uri: &str, strict: bool) -> Result<Value, Error> {
+        let resolver = self.try_resolver(uri.trim_end_matches('#'))?;
+        let base_uri = resolver.base_uri();
+        let resource = self.get_or_retrieve(&base_uri)?;
+        let mut dereferencer = Dereferencer {
+            strict,
+            identifiers: AHashSet::new(),
+            expanding: AHashMap::from_iter([(base_uri.as_str().to_string(), Vec::new())]),
+            cycles: Vec::new(),
+        };
+        let mut dereferenced = dereferencer.schema(
+            resource.contents(),
+            resource.draft(),
+            &resolver,
+            &mut Vec::new(),
+            false,
+        )?;
+        if dereferencer.cycles.is_empty() {
+            return Ok(dereferenced);
+        }
+        // Cycles are references to locations within the result
+        let draft = resource.draft().detect(&dereferenced)?;
+        let root_uri = if let Some(id) = draft.id_of(&dereferenced) {
+            id.trim_end_matches('#').to_string()
+        } else {
+            if let Value::Object(object) = &mut dereferenced {
+                object.insert(id_keyword(draft).to_string(), base_uri.as_str().into());
+            }
+            base_uri.as_str().to_string()
+        };
+        for (location, target) in dereferencer.cycles {
+            let mut reference = format!("{root_uri}#");
+            for segment in target {
+                reference.push('/');
+                uri::encode_to(
+                    &segment.replace('~', "~0").replace('/', "~1"),
+                    &mut reference,
+                );
+            }
+            if let Some(Value::Object(object)) =
+                location
+                    .iter()
+                    .try_fold(&mut dereferenced, |value, segment| match value {
+                        Value::Object(object) => object.get_mut(segment),
+                        Value::Array(items) => items.get_mut(segment.parse::<usize>().ok()?),
+                        _ => None,
+                    })
+            {
+                object.insert("$ref".to_string(), reference.into());
+            }
+        }
+        Ok(dereferenced)
+    }
+}
+
+struct Dereferencer {
+    strict: bool,
+    /// Absolute identifiers that are already present in the result.
+    identifiers: AHashSet<String>,
+    /// References that are being inlined and the locations of their contents in the result.
+    expanding: AHashMap<String, Vec<String>>,
+    /// Locations of cyclic references and of the contents they point to.
+    cycles: Vec<(Vec<String>, Vec<String>)>,
+}
+
+impl Dereferencer {
+    /// Dereference a schema that will be placed at `location` in the result.
+    ///
+    /// Anchors are removed from `foreign` schemas that are inlined without their resource.
+    fn schema(
+        &mut self,
+        contents: &Value,
+        draft: Draft,
+        resolver: &Resolver<'_>,
+        location: &mut Vec<String>,
+        mut foreign: bool,
+    ) -> Result<Value, Error> {
+        let draft = draft.detect(contents)?;
+        let Value::Object(object) = contents else {
+            return Ok(contents.clone());
+        };
+        let resolver = resolver.in_subresource(draft.create_resource_ref(contents))?;
+        let reference = object.get("$ref").and_then(Value::as_str);
+        if let (Some(reference), true) = (reference, draft <= Draft::Draft7) {
+            // Keywords next to `$ref` are ignored before Draft 2019-09
+            return Ok(self
+                .reference(reference, &resolver, location)?
+                .unwrap_or_else(|| {
+                    Value::Object(Map::from_iter([("$ref".to_string(), reference.into())]))
+                }));
+        }
+
+        let mut dereferenced = Map::with_capacity(object.len());
+        let id = draft.id_of(contents);
+        let mut resource = None;
+        if id.is_some() {
+            let absolute = resolver.base_uri().as_str().to_string();
+            if self.identifiers.insert(absolute.clone()) {
+                dereferenced.insert(id_keyword(draft).to_string(), absolute.clone().into());
+                foreign = false;
+            } else {
+                foreign = true;
+            }
+            // References to this resource from within it are cycles
+            if !self.expanding.contains_key(&absolute) {
+                self.expanding.insert(absolute.clone(), location.clone());
+                resource = Some(absolute);
+            }
+        }
+        let subresources: AHashSet<*const Value> = draft
+            .subresources_of(contents)
+            .map(|subresource| subresource as *const Value)
+            .collect();
+        for (keyword, value) in object {
+            if (id.is_some() && keyword == id_keyword(draft))
+                || (keyword == "$ref" && reference.is_some())
+                || (foreign && is_anchor(draft, keyword, value))
+            {
+                continue;
+            }
+            location.push(keyword.clone());
+            let value =
+                self.subschemas(value, &subresources, draft, &resolver, location, foreign)?;
+            location.pop();
+            dereferenced.insert(keyword.clone(), value);
+        }
+
+        let dereferenced = self.merge_reference(dereferenced, reference, &resolver, location);
+        if let Some(resource) = resource {
+            self.expanding.remove(&resource);
+        }
+        dereferenced
+    }
+
+    /// Combine keywords of a schema with the contents of its `$ref`.
+    fn merge_reference(
+        &mut self,
+        mut dereferenced: Map<String, Value>,
+        reference: Option<&str>,
+        resolver: &Resolver<'_>,
+        location: &mut Vec<String>,
+    ) -> Result<Value, Error> {
+        let Some(reference) = reference else {
+            return Ok(Value::Object(dereferenced));
+        };
+        if dereferenced.is_empty() {
+            return Ok(self
+                .reference(reference, resolver, location)?
+                .unwrap_or_else(|| {
+                    Value::Object(Map::from_iter([("$ref".to_string(), reference.into())]))
+                }));
+        }
+        // A cycle is kept as a `$ref` next to the other keywords
+        if self.is_cycle(reference, resolver, location)? {
+            dereferenced.insert("$ref".to_string(), reference.into());
+            return Ok(Value::Object(dereferenced));
+        }
+        // Referenced contents are applied together with the keywords next to `$ref`
+        let all_of = match dereferenced.get("allOf") {
+            Some(Value::Array(items)) => items.len(),
+            _ => 0,
+        };
+        location.extend(["allOf".to_string(), all_of.to_string()]);
+        let inlined = self
+            .reference(reference, resolver, location)?
+            .expect("Cycles are already handled");
+        location.truncate(location.len() - 2);
+        if let Some(Value::Array(items)) = dereferenced.get_mut("allOf") {
+            items.push(inlined);
+        } else {
+            dereferenced.insert("allOf".to_string(), Value::Array(vec![inlined]));
+        }
+        Ok(Value::Object(dereferenced))
+    }
+
+    /// Dereference subschemas within the value of a keyword.
+    fn subschemas(
+        &mut self,
+        value: &Value,
+        subresources: &AHashSet<*const Value>,
+        draft: Draft,
+        resolver: &Resolver<'_>,
+        location: &mut Vec<String>,
+        foreign: bool,
+    ) -> Result<Value, Error> {
+        if subresources.contains(&(value as *const Value)) {
+            return self.schema(value, draft, resolver, location, foreign);
+        }
+        let mut nested = |key: String, value: &Value, this: &mut Self| {
+            if subresources.contains(&(value as *const Value)) {
+                location.push(key);
+                let value = this.schema(value, draft, resolver, location, foreign);
+                location.pop();
+                value
+            } else {
+                Ok(value.clone())
+            }
+        };
+        match value {
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(idx, item)| nested(idx.to_string(), item, self))
+                .collect::<Result<_, _>>()
+                .map(Value::Array),
+            Value::Object(object) => object
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), nested(key.clone(), value, self)?)))
+                .collect::<Result<_, _>>()
+                .map(Value::Object),
+            _ => Ok(value.clone()),
+        }
+    }
+
+    /// Whether a reference leads to contents that are being inlined.
+    ///
+    /// Cycles are recorded with `location` as the schema that keeps the `$ref`.
+    fn is_cycle(
+        &mut self,
+        reference: &str,
+        resolver: &Resolver<'_>,
+        location: &[String],
+    ) -> Result<bool, Error> {
+        let target = target_of(reference, resolver)?;
+        let Some(expanded) = self.expanding.get(&target) else {
+            return Ok(false);
+        };
+        if self.strict {
+            return Err(Error::cyclic_reference(target));
+        }
+        self.cycles.push((location.to_vec(), expanded.clone()));
+        Ok(true)
+    }
+
+    /// Inline the contents of a reference at `location`, or `None` if it is a cycle.
+    fn reference(
+        &mut self,
+        reference: &str,
+        resolver: &Resolver<'_>,
+        location: &mut Vec<String>,
+    ) -> Result<Option<Value>, Error> {
+        if self.is_cycle(reference, resolver, location)? {
+            return Ok(None);
+        }
+        let target = target_of(reference, resolver)?;
+        let (contents, resolver, draft) = resolver.lookup(reference)?.into_inner();
+        self.expanding.insert(target.clone(), location.clone());
+        // Contents without an identifier are inlined without the resource they belong to
+        let foreign = draft.detect(contents)?.id_of(contents).is_none();
+        let inlined = self.schema(contents, draft, &resolver, location, foreign)?;
+        self.expanding.remove(&target);
+        Ok(Some(inlined))
+    }
+}
+
+/// The absolute URI a reference points to.
+fn target_of(reference: &str, resolver: &Resolver<'_>) -> Result<String, Error> {
+    let mut target = uri::resolve_against(&resolver.base_uri().borrow(), reference)?.into_string();
+    // An empty fragment points to the resource itself
+    if target.ends_with('#') {
+        target.pop();
+    }
+    Ok(target)
+}
+
+fn id_keyword(draft: Draft) -> &'static str {
+    if draft == Draft::Draft4 {
+        "id"
+    } else {
+        "$id"
+    }
+}
+
+fn is_anchor(draft: Draft, keyword: &str, value: &Value) -> bool {
+    if draft >= Draft::Draft201909 {
+        keyword == "$anchor"
+    } else {
+        // Plain-name fragments in identifiers are anchors before Draft 2019-09
+        keyword == id_keyword(draft) && value.as_str().is_some_and(|id| id.starts_with('#'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ahash::AHashMap;
+    use fluent_uri::Uri;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    use crate::{Registry, Resource, Retrieve};
+
+    struct InMemory(AHashMap<&'static str, Value>);
+
+    impl Retrieve for InMemory {
+        fn retrieve(
+            &self,
+            uri: &Uri<&str>,
+        ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+            self.0
+                .get(uri.as_str())
+                .cloned()
+                .ok_or_else(|| format!("Failed to find {uri}").into())
+        }
+    }
+
+    fn registry(root: Value, remote: &[(&'static str, Value)]) -> Registry {
+        Registry::options()
+            .retriever(Box::new(InMemory(remote.iter().cloned().collect())))
+            .try_new(
+                "https://example.com/root.json",
+                Resource::from_contents(root).expect("Invalid resource"),
+            )
+            .expect("Invalid resources")
+    }
+
+    #[test_case(
+        json!({"properties": {"a": {"$ref": "#/$defs/a"}}, "$defs": {"a": {"type": "string"}}}),
+        &[],
+        &json!({"properties": {"a": {"type": "string"}}, "$defs": {"a": {"type": "string"}}});
+        "local reference"
+    )]
+    #[test_case(
+        json!({"items": {"$ref": "a.json"}}),
+        &[
+            ("https://example.com/a.json", json!({"$ref": "nested/b.json"})),
+            ("https://example.com/nested/b.json", json!({"properties": {"c": {"$ref": "c.json"}}})),
+            ("https://example.com/nested/c.json", json!({"type": "integer"})),
+        ],
+        &json!({"items": {"properties": {"c": {"type": "integer"}}}});
+        "relative references in remote documents"
+    )]
+    #[test_case(
+        json!({"properties": {"a": {"$ref": "#/$defs/a", "minLength": 1}}, "$defs": {"a": {"type": "string"}}}),
+        &[],
+        &json!({"properties": {"a": {"minLength": 1, "allOf": [{"type": "string"}]}}, "$defs": {"a": {"type": "string"}}});
+        "keywords next to reference"
+    )]
+    #[test_case(
+        json!({"$schema": "http://json-schema.org/draft-07/schema#", "properties": {"a": {"$ref": "#/definitions/a", "minLength": 1}}, "definitions": {"a": {"type": "string"}}}),
+        &[],
+        &json!({"$schema": "http://json-schema.org/draft-07/schema#", "properties": {"a": {"type": "string"}}, "definitions": {"a": {"type": "string"}}});
+        "keywords next to legacy reference"
+    )]
+    #[test_case(
+        json!({"properties": {"a": {"$ref": "nested/a.json"}, "b": {"$ref": "nested/a.json"}}}),
+        &[("https://example.com/nested/a.json", json!({"$id": "a.json", "$anchor": "a", "type": "string"}))],
+        &json!({"properties": {
+            "a": {"$id": "https://example.com/nested/a.json", "$anchor": "a", "type": "string"},
+            "b": {"type": "string"}
+        }});
+        "identifiers"
+    )]
+    #[test_case(
+        json!({"properties": {"a": {"$ref": "a.json#/$defs/a"}}}),
+        &[("https://example.com/a.json", json!({"$defs": {"a": {"$anchor": "a", "type": "string"}}}))],
+        &json!({"properties": {"a": {"type": "string"}}});
+        "anchors of inlined contents"
+    )]
+    #[test_case(
+        json!({"properties": {"a": {"$ref": "#/$defs/a"}}, "$defs": {"a": {"$id": "a.json", "$defs": {"b": {"$id": "b.json"}}}}}),
+        &[],
+        &json!({
+            "properties": {"a": {"$defs": {"b": {}}}},
+            "$defs": {"a": {"$id": "https://example.com/a.json", "$defs": {"b": {"$id": "https://example.com/b.json"}}}}
+        });
+        "relative identifiers"
+    )]
+    #[test_case(
+        json!({"$ref": "#/$defs/node", "$defs": {"node": {"properties": {"next": {"$ref": "#/$defs/node"}}}}}),
+        &[],
+        &json!({
+            "$id": "https://example.com/root.json",
+            "allOf": [{"properties": {"next": {"$ref": "https://example.com/root.json#/allOf/0"}}}],
+            "$defs": {"node": {"properties": {"next": {
+                "properties": {"next": {"$ref": "https://example.com/root.json#/$defs/node/properties/next"}}
+            }}}}
+        });
+        "cycle"
+    )]
+    #[test_case(
+        json!({"properties": {"self": {"$ref": "#"}}}),
+        &[],
+        &json!({"$id": "https://example.com/root.json", "properties": {"self": {"$ref": "https://example.com/root.json#"}}});
+        "cycle to the root"
+    )]
+    #[test_case(
+        json!({"properties": {"x": {"$ref": "other.json"}}}),
+        &[("https://example.com/other.json", json!({"properties": {"next": {"$ref": "#", "type": "object"}}}))],
+        &json!({
+            "$id": "https://example.com/root.json",
+            "properties": {"x": {"properties": {"next": {
+                "$ref": "https://example.com/root.json#/properties/x",
+                "type": "object"
+            }}}}
+        });
+        "cycle next to other keywords"
+    )]
+    #[test_case(
+        json!({"enum": [{"$ref": "#/$defs/a"}], "const": {"$ref": "#/$defs/a"}, "$defs": {"a": {}}}),
+        &[],
+        &json!({"enum": [{"$ref": "#/$defs/a"}], "const": {"$ref": "#/$defs/a"}, "$defs": {"a": {}}});
+        "values"
+    )]
+    fn test_dereference(root: Value, remote: &[(&'static str, Value)], expected: &Value) {
+        let dereferenced = registry(root, remote)
+            .dereference("https://example.com/root.json")
+            .expect("Dereferencing failed");
+        assert_eq!(&dereferenced, expected);
+    }
+
+    #[test]
+    fn test_dereference_strict() {
+        let registry = registry(
+            json!({"properties": {"a": {"$ref": "#/$defs/a"}}, "$defs": {"a": {"items": {"$ref": "#/$defs/a"}}}}),
+            &[],
+        );
+        let error = registry
+            .dereference_strict("https://example.com/root.json")
+            .expect_err("Should fail");
+        assert_eq!(
+            error.to_string(),
+            "Reference 'https://example.com/root.json#/$defs/a' is cyclic"
+        );
+    }
+
+    #[test]
+    fn test_dereference_unresolvable() {
+        let registry = registry(json!({"$ref": "#/$defs/missing"}), &[]);
+        let error = registry
+            .dereference("https://example.com/root.json")
+            .expect_err("Should fail");
+        assert_eq!(error.to_string(), "Pointer '/$defs/missing' does not exist");
+    }
+}
@@ -32,6 +32,10 @@ pub enum Error {
     InvalidUri(UriError),
     /// An unknown JSON Schema specification was encountered.
     UnknownSpecification { specification: String },
+    /// A resource can't be embedded into a bundle without changing how references resolve.
+    Unbundlable { uri: String, reason: String },
+    /// A reference leads to contents that are being dereferenced.
+    CyclicReference { reference: String },
 }
 
 impl Error {
@@ -73,6 +77,19 @@ impl Error {
         }
     }
 
+    pub(crate) fn unbundlable(uri: impl Into<String>, reason: String) -> Error {
+        Error::Unbundlable {
+            uri: uri.into(),
+            reason,
+        }
+    }
+
+    pub(crate) fn cyclic_reference(reference: impl Into<String>) -> Error {
+        Error::CyclicReference {
+            reference: reference.into(),
+        }
+    }
+
     pub(crate) fn unretrievable(
         uri: impl Into<String>,
         source: Option<Box<dyn std::error::Error + Send + Sync>>,
@@ -141,6 +158,12 @@ impl fmt::Display for Error {
             Error::UnknownSpecification { specification } => {
                 f.write_fmt(format_args!("Unknown specification: {specification}"))
             }
+            Error::Unbundlable { uri, reason } => {
+                f.write_fmt(format_args!("Resource '{uri}' can not be bundled: {reason}"))
+            }
+            Error::CyclicReference { reference } => {
+                f.write_fmt(format_args!("Reference '{reference}' is cyclic"))
+            }
         }
     }
 }
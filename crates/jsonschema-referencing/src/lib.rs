@@ -2,6 +2,8 @@
 //!
 //! An implementation-agnostic JSON reference resolution library for Rust.
 mod anchors;
+mod bundle;
+mod dereference;
 mod error;
 mod list;
 pub mod meta;
@@ -21,6 +23,8 @@ pub use list::List;
 pub use registry::{Registry, RegistryOptions, SPECIFICATIONS};
 pub use resolver::{Resolved, Resolver};
 pub use resource::{Resource, ResourceRef};
+#[cfg(feature = "retrieve-async")]
+pub use retriever::AsyncRetrieve;
 pub use retriever::{DefaultRetriever, Retrieve};
 pub(crate) use segments::Segments;
 pub use specification::Draft;
@@ -10,6 +10,8 @@ use fluent_uri::Uri;
 use once_cell::sync::Lazy;
 use serde_json::Value;
 
+#[cfg(feature = "retrieve-async")]
+use crate::AsyncRetrieve;
 use crate::{
     anchors::{AnchorKey, AnchorKeyRef},
     list::List,
@@ -236,6 +238,30 @@ impl Registry {
             resolving_cache: RwLock::new(AHashMap::new()),
         })
     }
+    /// Create a new registry with new resources, retrieving external resources asynchronously.
+    ///
+    /// External resources referenced by the same resource are retrieved concurrently.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any URI is invalid, if there's an issue processing the resources or
+    /// if an external resource can't be retrieved.
+    #[cfg(feature = "retrieve-async")]
+    pub async fn try_with_resources_and_async_retriever(
+        self,
+        pairs: impl Iterator<Item = (impl Into<String>, Resource)>,
+        retriever: &dyn AsyncRetrieve,
+        draft: Draft,
+    ) -> Result<Registry, Error> {
+        let mut resources = self.resources;
+        let mut anchors = self.anchors;
+        process_resources_async(pairs, retriever, &mut resources, &mut anchors, draft).await?;
+        Ok(Registry {
+            resources,
+            anchors,
+            resolving_cache: RwLock::new(AHashMap::new()),
+        })
+    }
     /// Create a new [`Resolver`] for this registry with the given base URI.
     ///
     /// # Errors
@@ -258,6 +284,15 @@ impl Registry {
     ) -> Resolver {
         Resolver::from_parts(self, base_uri, scopes)
     }
+    /// Iterate over all resources in this registry together with their canonical URIs.
+    ///
+    /// Subresources that have their own IDs are yielded separately from the documents
+    /// containing them.
+    pub fn resources(&self) -> impl Iterator<Item = (&Uri<String>, &Resource)> {
+        self.resources
+            .iter()
+            .map(|(uri, resource)| (uri, &**resource))
+    }
     pub(crate) fn get_or_retrieve<'r>(&'r self, uri: &Uri<String>) -> Result<&'r Resource, Error> {
         if let Some(resource) = self.resources.get(uri) {
             Ok(resource)
@@ -338,6 +373,8 @@ impl Registry {
     }
 }
 
+type Queue = VecDeque<(Uri<String>, Arc<Resource>)>;
+
 fn process_resources(
     pairs: impl Iterator<Item = (impl Into<String>, Resource)>,
     retriever: &dyn Retrieve,
@@ -349,13 +386,7 @@ fn process_resources(
     let mut seen = AHashSet::new();
     let mut external = AHashSet::new();
 
-    // Populate the resources & queue from the input
-    for (uri, resource) in pairs {
-        let uri = uri::from_str(uri.into().trim_end_matches('#'))?;
-        let resource = Arc::new(resource);
-        resources.insert(uri.clone(), Arc::clone(&resource));
-        queue.push_back((uri, resource));
-    }
+    populate(pairs, resources, &mut queue)?;
 
     loop {
         if queue.is_empty() && external.is_empty() {
@@ -363,48 +394,8 @@ fn process_resources(
         }
 
         // Process current queue and collect references to external resources
-        while let Some((mut base, resource)) = queue.pop_front() {
-            if let Some(id) = resource.id() {
-                base = uri::resolve_against(&base.borrow(), id)?;
-            }
-
-            // Look for anchors
-            for anchor in resource.anchors() {
-                anchors.insert(
-                    AnchorKey::new(base.clone(), anchor.name().to_string()),
-                    anchor,
-                );
-            }
+        process_queue(&mut queue, resources, anchors, &mut external, &mut seen)?;
 
-            // Collect references to external resources in this resource
-            collect_external_resources(&base, resource.contents(), &mut external, &mut seen)?;
-
-            // Process subresources
-            for subresource in resource.subresources() {
-                let subresource = Arc::new(subresource?);
-                // Collect references to external resources at this level
-                if let Some(sub_id) = subresource.id() {
-                    let base = uri::resolve_against(&base.borrow(), sub_id)?;
-                    collect_external_resources(
-                        &base,
-                        subresource.contents(),
-                        &mut external,
-                        &mut seen,
-                    )?;
-                } else {
-                    collect_external_resources(
-                        &base,
-                        subresource.contents(),
-                        &mut external,
-                        &mut seen,
-                    )?;
-                };
-                queue.push_back((base.clone(), subresource));
-            }
-            if resource.id().is_some() {
-                resources.insert(base, resource);
-            }
-        }
         // Retrieve external resources
         for uri in external.drain() {
             let mut fragmentless = uri.clone();
@@ -413,30 +404,173 @@ fn process_resources(
                 let retrieved = retriever
                     .retrieve(&fragmentless.borrow())
                     .map_err(|err| Error::unretrievable(fragmentless.as_str(), Some(err)))?;
-                let resource = Arc::new(Resource::from_contents_and_specification(
+                handle_retrieved(
+                    uri,
+                    fragmentless,
                     retrieved,
+                    resources,
+                    &mut queue,
                     default_draft,
-                )?);
-                resources.insert(fragmentless.clone(), Arc::clone(&resource));
-                if let Some(fragment) = uri.fragment() {
-                    // The original `$ref` could have a fragment that points to a place that won't
-                    // be discovered via the regular sub-resources discovery. Therefore we need to
-                    // explicitly check it
-                    if let Some(resolved) = resource.contents().pointer(fragment.as_str()) {
-                        queue.push_back((
-                            uri,
-                            Arc::new(Resource::from_contents_and_specification(
-                                resolved.clone(),
-                                default_draft,
-                            )?),
-                        ));
-                    }
-                }
-                queue.push_back((fragmentless, resource));
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "retrieve-async")]
+async fn process_resources_async(
+    pairs: impl Iterator<Item = (impl Into<String>, Resource)>,
+    retriever: &dyn AsyncRetrieve,
+    resources: &mut ResourceMap,
+    anchors: &mut AHashMap<AnchorKey, Anchor>,
+    default_draft: Draft,
+) -> Result<(), Error> {
+    let mut queue = VecDeque::with_capacity(32);
+    let mut seen = AHashSet::new();
+    let mut external = AHashSet::new();
+
+    populate(pairs, resources, &mut queue)?;
+
+    loop {
+        if queue.is_empty() && external.is_empty() {
+            break;
+        }
+
+        process_queue(&mut queue, resources, anchors, &mut external, &mut seen)?;
+
+        // Retrieve all external resources discovered at this step concurrently
+        let pending: Vec<_> = external
+            .drain()
+            .map(|uri| {
+                let mut fragmentless = uri.clone();
+                fragmentless.set_fragment(None);
+                (uri, fragmentless)
+            })
+            .filter(|(_, fragmentless)| !resources.contains_key(fragmentless))
+            .collect();
+        let mut to_retrieve: Vec<&Uri<String>> = pending.iter().map(|(_, f)| f).collect();
+        to_retrieve.sort_unstable_by(|left, right| left.as_str().cmp(right.as_str()));
+        to_retrieve.dedup();
+        let retrieved = futures_util::future::join_all(to_retrieve.iter().map(|uri| async move {
+            retriever
+                .retrieve(&uri.borrow())
+                .await
+                .map_err(|err| Error::unretrievable(uri.as_str(), Some(err)))
+        }))
+        .await;
+        let mut retrieved: AHashMap<Uri<String>, Value> = to_retrieve
+            .into_iter()
+            .cloned()
+            .zip(retrieved)
+            .map(|(uri, result)| result.map(|value| (uri, value)))
+            .collect::<Result<_, _>>()?;
+
+        for (uri, fragmentless) in pending {
+            if !resources.contains_key(&fragmentless) {
+                let value = retrieved
+                    .remove(&fragmentless)
+                    .expect("All pending resources are retrieved");
+                handle_retrieved(
+                    uri,
+                    fragmentless,
+                    value,
+                    resources,
+                    &mut queue,
+                    default_draft,
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn populate(
+    pairs: impl Iterator<Item = (impl Into<String>, Resource)>,
+    resources: &mut ResourceMap,
+    queue: &mut Queue,
+) -> Result<(), Error> {
+    for (uri, resource) in pairs {
+        let uri = uri::from_str(uri.into().trim_end_matches('#'))?;
+        let resource = Arc::new(resource);
+        resources.insert(uri.clone(), Arc::clone(&resource));
+        queue.push_back((uri, resource));
+    }
+    Ok(())
+}
+
+fn process_queue(
+    queue: &mut Queue,
+    resources: &mut ResourceMap,
+    anchors: &mut AHashMap<AnchorKey, Anchor>,
+    external: &mut AHashSet<Uri<String>>,
+    seen: &mut AHashSet<u64>,
+) -> Result<(), Error> {
+    while let Some((mut base, resource)) = queue.pop_front() {
+        if let Some(id) = resource.id() {
+            base = uri::resolve_against(&base.borrow(), id)?;
+        }
+
+        // Look for anchors
+        for anchor in resource.anchors() {
+            anchors.insert(
+                AnchorKey::new(base.clone(), anchor.name().to_string()),
+                anchor,
+            );
+        }
+
+        // Collect references to external resources in this resource
+        collect_external_resources(&base, resource.contents(), external, seen)?;
+
+        // Process subresources
+        for subresource in resource.subresources() {
+            let subresource = Arc::new(subresource?);
+            // Collect references to external resources at this level
+            if let Some(sub_id) = subresource.id() {
+                let base = uri::resolve_against(&base.borrow(), sub_id)?;
+                collect_external_resources(&base, subresource.contents(), external, seen)?;
+            } else {
+                collect_external_resources(&base, subresource.contents(), external, seen)?;
             }
+            queue.push_back((base.clone(), subresource));
+        }
+        if resource.id().is_some() {
+            resources.insert(base, resource);
         }
     }
+    Ok(())
+}
 
+fn handle_retrieved(
+    uri: Uri<String>,
+    fragmentless: Uri<String>,
+    retrieved: Value,
+    resources: &mut ResourceMap,
+    queue: &mut Queue,
+    default_draft: Draft,
+) -> Result<(), Error> {
+    let resource = Arc::new(Resource::from_contents_and_specification(
+        retrieved,
+        default_draft,
+    )?);
+    resources.insert(fragmentless.clone(), Arc::clone(&resource));
+    if let Some(fragment) = uri.fragment() {
+        // The original `$ref` could have a fragment that points to a place that won't
+        // be discovered via the regular sub-resources discovery. Therefore we need to
+        // explicitly check it
+        if let Some(resolved) = resource.contents().pointer(fragment.as_str()) {
+            queue.push_back((
+                uri,
+                Arc::new(Resource::from_contents_and_specification(
+                    resolved.clone(),
+                    default_draft,
+                )?),
+            ));
+        }
+    }
+    queue.push_back((fragmentless, resource));
     Ok(())
 }
 
@@ -926,4 +1060,121 @@ mod tests {
             .expect("Lookup failed");
         assert_eq!(resolved.contents(), &json!({"type": "object"}));
     }
+
+    #[cfg(feature = "retrieve-async")]
+    #[async_trait::async_trait]
+    impl crate::AsyncRetrieve for TestRetriever {
+        async fn retrieve(
+            &self,
+            uri: &Uri<&str>,
+        ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+            Retrieve::retrieve(self, uri)
+        }
+    }
+
+    #[cfg(feature = "retrieve-async")]
+    #[tokio::test]
+    async fn test_try_with_resources_and_async_retriever() {
+        let retriever = create_test_retriever(&[
+            (
+                "http://example.com/schema2",
+                json!({"$ref": "http://example.com/schema3#/$defs/A"}),
+            ),
+            (
+                "http://example.com/schema3",
+                json!({"$defs": {"A": {"type": "integer"}}}),
+            ),
+        ]);
+        let registry = SPECIFICATIONS
+            .clone()
+            .try_with_resources_and_async_retriever(
+                [(
+                    "http://example.com",
+                    Resource::from_contents(json!({"$ref": "http://example.com/schema2"}))
+                        .expect("Invalid resource"),
+                )]
+                .into_iter(),
+                &retriever,
+                Draft::default(),
+            )
+            .await
+            .expect("Invalid resource");
+        let resolver = registry.try_resolver("").expect("Invalid base URI");
+        let resolved = resolver
+            .lookup("http://example.com/schema3#/$defs/A")
+            .expect("Lookup failed");
+        assert_eq!(resolved.contents(), &json!({"type": "integer"}));
+    }
+
+    #[cfg(feature = "retrieve-async")]
+    #[tokio::test]
+    async fn test_async_retriever_fetches_concurrently() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Default)]
+        struct CountingRetriever {
+            in_flight: AtomicUsize,
+            max_in_flight: AtomicUsize,
+        }
+
+        #[async_trait::async_trait]
+        impl crate::AsyncRetrieve for CountingRetriever {
+            async fn retrieve(
+                &self,
+                _: &Uri<&str>,
+            ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+                let current = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                self.max_in_flight.fetch_max(current, Ordering::SeqCst);
+                tokio::task::yield_now().await;
+                self.in_flight.fetch_sub(1, Ordering::SeqCst);
+                Ok(json!({"type": "string"}))
+            }
+        }
+
+        let retriever = CountingRetriever::default();
+        SPECIFICATIONS
+            .clone()
+            .try_with_resources_and_async_retriever(
+                [(
+                    "http://example.com",
+                    Resource::from_contents(json!({
+                        "properties": {
+                            "a": {"$ref": "http://example.com/a"},
+                            "b": {"$ref": "http://example.com/b"},
+                            "c": {"$ref": "http://example.com/c"}
+                        }
+                    }))
+                    .expect("Invalid resource"),
+                )]
+                .into_iter(),
+                &retriever,
+                Draft::default(),
+            )
+            .await
+            .expect("Invalid resource");
+        assert_eq!(retriever.max_in_flight.load(Ordering::SeqCst), 3);
+    }
+
+    #[cfg(feature = "retrieve-async")]
+    #[tokio::test]
+    async fn test_async_retriever_error() {
+        let error = SPECIFICATIONS
+            .clone()
+            .try_with_resources_and_async_retriever(
+                [(
+                    "http://example.com",
+                    Resource::from_contents(json!({"$ref": "http://example.com/missing"}))
+                        .expect("Invalid resource"),
+                )]
+                .into_iter(),
+                &create_test_retriever(&[]),
+                Draft::default(),
+            )
+            .await
+            .expect_err("Should fail");
+        assert_eq!(
+            error.to_string(),
+            "Resource 'http://example.com/missing' is not present in a registry and retrieving it failed: Failed to find http://example.com/missing"
+        );
+    }
 }
@@ -20,6 +20,30 @@ pub trait Retrieve: Send + Sync {
     fn retrieve(&self, uri: &Uri<&str>) -> Result<Value, Box<dyn std::error::Error + Send + Sync>>;
 }
 
+/// Trait for retrieving resources from external sources asynchronously.
+///
+/// This is the asynchronous counterpart of [`Retrieve`]. All resources are retrieved before the
+/// [`crate::Registry`] is built, and independent resources are retrieved concurrently.
+///
+/// Implementations should use the [`async_trait`](https://docs.rs/async-trait) attribute.
+#[cfg(feature = "retrieve-async")]
+#[async_trait::async_trait]
+pub trait AsyncRetrieve: Send + Sync {
+    /// Attempt to retrieve a resource from the given URI.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The URI of the resource to retrieve.
+    ///
+    /// # Errors
+    ///
+    /// If the resource couldn't be retrieved or an error occurred.
+    async fn retrieve(
+        &self,
+        uri: &Uri<&str>,
+    ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>>;
+}
+
 #[derive(Debug, Clone)]
 struct DefaultRetrieverError;
 
@@ -39,3 +63,14 @@ impl Retrieve for DefaultRetriever {
         Err(Box::new(DefaultRetrieverError))
     }
 }
+
+#[cfg(feature = "retrieve-async")]
+#[async_trait::async_trait]
+impl AsyncRetrieve for DefaultRetriever {
+    async fn retrieve(
+        &self,
+        _: &Uri<&str>,
+    ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+        Err(Box::new(DefaultRetrieverError))
+    }
+}
@@ -8,6 +8,16 @@ fn bench_build(c: &mut Criterion, name: &str, schema: &Value) {
     });
 }
 
+fn bench_from_bytes(c: &mut Criterion, name: &str, schema: &Value) {
+    let bytes = jsonschema::validator_for(schema)
+        .expect("Valid schema")
+        .to_bytes()
+        .expect("Supported draft");
+    c.bench_with_input(BenchmarkId::new("from_bytes", name), &bytes, |b, bytes| {
+        b.iter(|| jsonschema::Validator::from_bytes(bytes).expect("Valid input"))
+    });
+}
+
 fn bench_is_valid(c: &mut Criterion, name: &str, schema: &Value, instance: &Value) {
     let validator = jsonschema::validator_for(schema).expect("Valid schema");
     c.bench_with_input(
@@ -47,6 +57,7 @@ fn run_benchmarks(c: &mut Criterion) {
     for benchmark in Benchmark::iter() {
         benchmark.run(&mut |name, schema, instances| {
             bench_build(c, name, schema);
+            bench_from_bytes(c, name, schema);
             for instance in instances {
                 let name = format!("{}/{}", name, instance.name);
                 bench_is_valid(c, &name, schema, &instance.data);
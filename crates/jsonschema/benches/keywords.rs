@@ -1,6 +1,8 @@
 use benchmark::run_keyword_benchmarks;
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
-use serde_json::Value;
+use jsonschema::PatternOptions;
+use serde_json::{json, Map, Value};
+use std::thread;
 
 fn bench_keyword_build(c: &mut Criterion, name: &str, schema: &Value) {
     c.bench_function(&format!("keyword/{}/build", name), |b| {
@@ -45,5 +47,75 @@ fn run_benchmarks(c: &mut Criterion) {
     });
 }
 
-criterion_group!(keywords, run_benchmarks);
+/// A schema with `count` properties, each with one of `distinct` patterns.
+fn patterns_schema(count: usize, distinct: usize) -> Value {
+    let properties: Map<String, Value> = (0..count)
+        .map(|idx| {
+            let pattern = format!("^[a-z]+-{}-\\d{{2,4}}$", idx % distinct);
+            (format!("p{idx}"), json!({"pattern": pattern}))
+        })
+        .collect();
+    json!({"properties": properties})
+}
+
+fn run_pattern_benchmarks(c: &mut Criterion) {
+    for (count, distinct) in [(100, 1), (100, 100), (1000, 1000)] {
+        let schema = patterns_schema(count, distinct);
+        let name = format!("pattern/{count}-properties-{distinct}-patterns");
+        c.bench_function(&format!("keyword/{name}/build"), |b| {
+            b.iter(|| jsonschema::validator_for(&schema).expect("Valid schema"))
+        });
+        // Multiple validators built concurrently don't share a cache or a lock
+        c.bench_function(&format!("keyword/{name}/build-parallel"), |b| {
+            b.iter(|| {
+                thread::scope(|scope| {
+                    for _ in 0..4 {
+                        scope.spawn(|| jsonschema::validator_for(&schema).expect("Valid schema"));
+                    }
+                });
+            })
+        });
+    }
+    let validator = jsonschema::options()
+        .should_validate_formats(true)
+        .build(&json!({"items": {"format": "regex"}}))
+        .expect("Valid schema");
+    let instance: Value = (0..100)
+        .map(|idx| Value::String(format!("^[a-z]+-{}-\\d{{2,4}}$", idx % 10)))
+        .collect();
+    c.bench_with_input(
+        BenchmarkId::new("keyword/format/regex-100-items-10-patterns", "is_valid"),
+        &instance,
+        |b, instance| {
+            b.iter(|| {
+                let _ = validator.is_valid(instance);
+            })
+        },
+    );
+    // A working set of 1000 distinct patterns with a cache that can't hold it and one that can
+    let instance: Value = (0..1000)
+        .map(|idx| Value::String(format!("^[a-z]+-{idx}-\\d{{2,4}}$")))
+        .collect();
+    for capacity in [100, 2000] {
+        let validator = jsonschema::options()
+            .should_validate_formats(true)
+            .with_pattern_options(PatternOptions::default().cache_capacity(capacity))
+            .build(&json!({"items": {"format": "regex"}}))
+            .expect("Valid schema");
+        c.bench_with_input(
+            BenchmarkId::new(
+                format!("keyword/format/regex-1000-patterns-capacity-{capacity}"),
+                "is_valid",
+            ),
+            &instance,
+            |b, instance| {
+                b.iter(|| {
+                    let _ = validator.is_valid(instance);
+                })
+            },
+        );
+    }
+}
+
+criterion_group!(keywords, run_pattern_benchmarks, run_benchmarks);
 criterion_main!(keywords);
@@ -1,9 +1,11 @@
 use crate::{
-    content_encoding::{ContentEncodingCheckType, ContentEncodingConverterType},
-    content_media_type::ContentMediaTypeCheckType,
+    content_encoding::ContentEncodingHandler,
+    content_media_type::ContentMediaTypeHandler,
+    dialect,
     keywords::{
         self,
-        custom::{CustomKeyword, KeywordFactory},
+        custom::{self, CustomKeyword, KeywordFactory},
+        error_message::ErrorMessages,
         format::Format,
         BoxedValidator, BuiltinKeyword, Keyword,
     },
@@ -11,16 +13,18 @@ use crate::{
     options::ValidationOptions,
     paths::{Location, LocationSegment},
     primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
-    ValidationError, Validator,
+    regex::{PatternCache, PatternError, Regex},
+    snapshot::Snapshot,
+    SchemaErrors, ValidationError, Validator,
 };
 use ahash::{AHashMap, AHashSet};
 use once_cell::sync::Lazy;
 use referencing::{
-    uri, Draft, List, Registry, Resolved, Resolver, Resource, ResourceRef, Uri, Vocabulary,
-    VocabularySet, SPECIFICATIONS,
+    uri, DefaultRetriever, Draft, List, Registry, Resolved, Resolver, Resource, ResourceRef, Uri,
+    Vocabulary, VocabularySet, SPECIFICATIONS,
 };
 use serde_json::Value;
-use std::{cell::RefCell, rc::Rc, sync::Arc};
+use std::{cell::RefCell, fmt, rc::Rc, sync::Arc};
 
 const DEFAULT_SCHEME: &str = "json-schema";
 pub(crate) const DEFAULT_ROOT_URL: &str = "json-schema:///";
@@ -39,6 +43,24 @@ pub(crate) struct Context<'a> {
     location: Location,
     pub(crate) draft: Draft,
     seen: Rc<RefCell<AHashSet<Arc<Uri<String>>>>>,
+    custom_keywords: Rc<RefCell<InitializedKeywords>>,
+    patterns: Arc<PatternCache>,
+    /// URI of the custom dialect of the current resource.
+    dialect: Option<Arc<str>>,
+    /// When set, keyword compilation errors are recorded here instead of aborting compilation.
+    errors: Option<Rc<RefCell<Vec<ValidationError<'static>>>>>,
+}
+
+/// Custom keywords initialized during a compilation, keyed by the address of their value.
+#[derive(Default)]
+struct InitializedKeywords(AHashMap<*const Value, Arc<dyn custom::Keyword>>);
+
+impl fmt::Debug for InitializedKeywords {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("InitializedKeywords")
+            .field(&self.0.len())
+            .finish()
+    }
 }
 
 impl<'a> Context<'a> {
@@ -49,6 +71,7 @@ impl<'a> Context<'a> {
         vocabularies: VocabularySet,
         draft: Draft,
         location: Location,
+        patterns: Arc<PatternCache>,
     ) -> Self {
         Context {
             config,
@@ -58,7 +81,12 @@ impl<'a> Context<'a> {
             vocabularies,
             draft,
             seen: Rc::new(RefCell::new(AHashSet::new())),
+            custom_keywords: Rc::new(RefCell::new(InitializedKeywords::default())),
+            patterns,
+            dialect: None,
+            errors: None,
         }
+        .with_resource_dialect()
     }
     pub(crate) fn draft(&self) -> Draft {
         self.draft
@@ -81,7 +109,12 @@ impl<'a> Context<'a> {
             draft: resource.draft(),
             location: self.location.clone(),
             seen: Rc::clone(&self.seen),
-        })
+            custom_keywords: Rc::clone(&self.custom_keywords),
+            patterns: Arc::clone(&self.patterns),
+            dialect: self.dialect.clone(),
+            errors: self.errors.clone(),
+        }
+        .with_resource_dialect())
     }
     pub(crate) fn as_resource_ref<'r>(&'a self, contents: &'r Value) -> ResourceRef<'r> {
         self.draft
@@ -101,6 +134,10 @@ impl<'a> Context<'a> {
             location,
             draft: self.draft,
             seen: Rc::clone(&self.seen),
+            custom_keywords: Rc::clone(&self.custom_keywords),
+            patterns: Arc::clone(&self.patterns),
+            dialect: self.dialect.clone(),
+            errors: self.errors.clone(),
         }
     }
 
@@ -130,13 +167,20 @@ impl<'a> Context<'a> {
         !matches!(self.draft, Draft::Draft4)
     }
     pub(crate) fn validates_formats_by_default(&self) -> bool {
-        self.config
-            .validate_formats()
-            .unwrap_or_else(|| matches!(self.draft, Draft::Draft4 | Draft::Draft6 | Draft::Draft7))
+        self.config.validate_formats().unwrap_or(matches!(
+            self.draft,
+            Draft::Draft4 | Draft::Draft6 | Draft::Draft7
+        ))
     }
     pub(crate) fn are_unknown_formats_ignored(&self) -> bool {
         self.config.are_unknown_formats_ignored()
     }
+    pub(crate) fn are_error_messages_used(&self) -> bool {
+        self.config.are_error_messages_used()
+    }
+    pub(crate) fn validates_content_schema(&self) -> bool {
+        self.config.validates_content_schema()
+    }
     pub(crate) fn with_resolver_and_draft(
         &'a self,
         resolver: Resolver<'a>,
@@ -152,29 +196,79 @@ impl<'a> Context<'a> {
             vocabularies,
             location,
             seen: Rc::clone(&self.seen),
+            custom_keywords: Rc::clone(&self.custom_keywords),
+            patterns: Arc::clone(&self.patterns),
+            dialect: self.dialect.clone(),
+            errors: self.errors.clone(),
         }
+        .with_resource_dialect()
     }
     pub(crate) fn get_content_media_type_check(
         &self,
         media_type: &str,
-    ) -> Option<ContentMediaTypeCheckType> {
+    ) -> Option<ContentMediaTypeHandler> {
         self.config.get_content_media_type_check(media_type)
     }
-    pub(crate) fn get_content_encoding_check(
+    pub(crate) fn get_content_encoding(
         &self,
         content_encoding: &str,
-    ) -> Option<ContentEncodingCheckType> {
-        self.config.content_encoding_check(content_encoding)
+    ) -> Option<ContentEncodingHandler> {
+        self.config.get_content_encoding(content_encoding)
     }
-
-    pub(crate) fn get_content_encoding_convert(
-        &self,
-        content_encoding: &str,
-    ) -> Option<ContentEncodingConverterType> {
-        self.config.get_content_encoding_convert(content_encoding)
+    /// Compile a pattern with the configured regular expression engine, reusing cached patterns.
+    pub(crate) fn compile_pattern(&self, pattern: &str) -> Result<Regex, PatternError> {
+        self.patterns.get_or_compile(pattern)
+    }
+    pub(crate) fn patterns(&self) -> &Arc<PatternCache> {
+        &self.patterns
     }
+    /// Factory of a custom keyword, registered in the options or in the current dialect.
     pub(crate) fn get_keyword_factory(&self, name: &str) -> Option<&Arc<dyn KeywordFactory>> {
-        self.config.get_keyword_factory(name)
+        self.config.get_keyword_factory(name).or_else(|| {
+            self.dialect
+                .as_deref()
+                .and_then(|uri| self.config.dialect(uri))
+                .and_then(|dialect| dialect.keyword(name))
+        })
+    }
+    /// Switch to the dialect declared by the current resource, if it declares one.
+    fn with_resource_dialect(mut self) -> Self {
+        if !self.config.has_dialects() {
+            return self;
+        }
+        if let Ok(resolved) = self.resolver.lookup("#") {
+            if let Some(uri) = dialect::declared_uri(resolved.contents()) {
+                self.dialect = self
+                    .config
+                    .dialect(uri)
+                    .map(|dialect| Arc::clone(dialect.uri()));
+            }
+        }
+        self
+    }
+    /// Initialize the custom keyword `keyword` of `parent`.
+    ///
+    /// `unevaluatedProperties` and `unevaluatedItems` need the custom keywords of the schemas
+    /// they depend on, which are also compiled as regular validators. The factory runs only once
+    /// per keyword value and both share the result.
+    pub(crate) fn init_custom_keyword<'s>(
+        &self,
+        factory: &dyn KeywordFactory,
+        parent: &'s serde_json::Map<String, Value>,
+        keyword: &str,
+        value: &'s Value,
+    ) -> Result<Arc<dyn custom::Keyword>, ValidationError<'s>> {
+        let key: *const Value = value;
+        if let Some(initialized) = self.custom_keywords.borrow().0.get(&key) {
+            return Ok(Arc::clone(initialized));
+        }
+        let initialized: Arc<dyn custom::Keyword> =
+            Arc::from(factory.init(parent, value, self.location.join(keyword))?);
+        self.custom_keywords
+            .borrow_mut()
+            .0
+            .insert(key, Arc::clone(&initialized));
+        Ok(initialized)
     }
     pub(crate) fn get_format(&self, format: &str) -> Option<(&String, &Arc<dyn Format>)> {
         self.config.get_format(format)
@@ -233,6 +327,33 @@ impl<'a> Context<'a> {
         &self.vocabularies
     }
 
+    /// Collect keyword compilation errors instead of stopping at the first one.
+    fn collecting_errors(mut self) -> Self {
+        self.errors = Some(Rc::new(RefCell::new(Vec::new())));
+        self
+    }
+
+    /// Record a failed keyword compilation if errors are collected, otherwise return it.
+    fn report<T>(
+        &self,
+        keyword: &str,
+        result: Result<T, ValidationError<'_>>,
+    ) -> Result<Option<T>, ValidationError<'static>> {
+        match (result, &self.errors) {
+            (Ok(value), _) => Ok(Some(value)),
+            (Err(mut error), Some(errors)) => {
+                // Some errors point to the enclosing schema or have no location at all
+                let location = error.instance_path.as_str();
+                if location.is_empty() || location == self.location.as_str() {
+                    error.instance_path = self.location.join(keyword);
+                }
+                errors.borrow_mut().push(error.into_owned());
+                Ok(None)
+            }
+            (Err(error), None) => Err(error.into_owned()),
+        }
+    }
+
     pub(crate) fn has_vocabulary(&self, vocabulary: &Vocabulary) -> bool {
         if self.draft() < Draft::Draft201909 || vocabulary == &Vocabulary::Core {
             true
@@ -243,71 +364,293 @@ impl<'a> Context<'a> {
 }
 
 const EXPECT_MESSAGE: &str = "Invalid meta-schema";
-static META_SCHEMA_VALIDATORS: Lazy<AHashMap<Draft, Validator>> = Lazy::new(|| {
+static META_SCHEMA_VALIDATORS: Lazy<AHashMap<Draft, Arc<Validator>>> = Lazy::new(|| {
     let mut validators = AHashMap::with_capacity(5);
     let mut options = crate::options();
     options.without_schema_validation();
     validators.insert(
         Draft::Draft4,
-        options
-            .build(&referencing::meta::DRAFT4)
-            .expect(EXPECT_MESSAGE),
+        Arc::new(
+            options
+                .build(&referencing::meta::DRAFT4)
+                .expect(EXPECT_MESSAGE),
+        ),
     );
     validators.insert(
         Draft::Draft6,
-        options
-            .build(&referencing::meta::DRAFT6)
-            .expect(EXPECT_MESSAGE),
+        Arc::new(
+            options
+                .build(&referencing::meta::DRAFT6)
+                .expect(EXPECT_MESSAGE),
+        ),
     );
     validators.insert(
         Draft::Draft7,
-        options
-            .build(&referencing::meta::DRAFT7)
-            .expect(EXPECT_MESSAGE),
+        Arc::new(
+            options
+                .build(&referencing::meta::DRAFT7)
+                .expect(EXPECT_MESSAGE),
+        ),
     );
     validators.insert(
         Draft::Draft201909,
-        options
-            .build(&referencing::meta::DRAFT201909)
-            .expect(EXPECT_MESSAGE),
+        Arc::new(
+            options
+                .build(&referencing::meta::DRAFT201909)
+                .expect(EXPECT_MESSAGE),
+        ),
     );
     validators.insert(
         Draft::Draft202012,
-        options
-            .without_schema_validation()
-            .build(&referencing::meta::DRAFT202012)
-            .expect(EXPECT_MESSAGE),
+        Arc::new(
+            options
+                .without_schema_validation()
+                .build(&referencing::meta::DRAFT202012)
+                .expect(EXPECT_MESSAGE),
+        ),
     );
     validators
 });
 
+/// Validator for the meta-schema of the given schema.
+///
+/// Meta-schemas of custom dialects are compiled with the registry of the schema, which already
+/// holds them along with the resources they reference.
+fn meta_schema_validator(
+    config: &ValidationOptions,
+    registry: &Arc<Registry>,
+    draft: Draft,
+    schema: &Value,
+) -> Result<Arc<Validator>, ValidationError<'static>> {
+    if let Some(dialect) = config.dialect_for(schema) {
+        let validator = build_with_registry(
+            config.for_meta_schema(dialect),
+            dialect.meta_schema(),
+            dialect.draft(),
+            dialect.uri(),
+            Arc::clone(registry),
+        )?;
+        Ok(Arc::new(validator))
+    } else {
+        Ok(Arc::clone(
+            META_SCHEMA_VALIDATORS.get(&draft).expect("Existing draft"),
+        ))
+    }
+}
+
 pub(crate) fn build_validator(
     mut config: ValidationOptions,
     schema: &Value,
 ) -> Result<Validator, ValidationError<'static>> {
     let draft = config.draft_for(schema);
-    let resource_ref = draft.create_resource_ref(schema);
-    let resource = draft.create_resource(schema.clone());
-    let base_uri = resource.id().unwrap_or(DEFAULT_ROOT_URL).to_string();
-
-    // Prepare additional resources to use in resolving
-    let mut resources = Vec::with_capacity(1 + config.resources.len());
-    resources.push((base_uri.clone(), resource));
-    for (uri, resource) in config.resources.drain() {
-        resources.push((uri, resource));
-    }
+    let (base_uri, resources) = prepare_resources(&mut config, draft, schema);
 
     // Get retriever for external resources
     let retriever = Arc::clone(&config.retriever);
 
     // Build a registry & resolver needed for validator compilation
-    let registry = Arc::new(SPECIFICATIONS.clone().try_with_resources_and_retriever(
+    let registry = SPECIFICATIONS.clone().try_with_resources_and_retriever(
         resources.into_iter(),
         &*retriever,
         draft,
-    )?);
+    )?;
+    build_with_registry(config, schema, draft, &base_uri, Arc::new(registry))
+}
+
+#[cfg(feature = "resolve-async")]
+pub(crate) async fn build_validator_async(
+    mut config: ValidationOptions,
+    schema: &Value,
+) -> Result<Validator, ValidationError<'static>> {
+    let draft = config.draft_for_async(schema).await;
+    let (base_uri, resources) = prepare_resources(&mut config, draft, schema);
+
+    let retriever = Arc::clone(&config.async_retriever);
+
+    // All external resources are retrieved before compilation
+    let registry = SPECIFICATIONS
+        .clone()
+        .try_with_resources_and_async_retriever(resources.into_iter(), &*retriever, draft)
+        .await?;
+    build_with_registry(config, schema, draft, &base_uri, Arc::new(registry))
+}
+
+/// Build a validator from a snapshot produced by [`Validator::to_bytes`].
+///
+/// All external resources are already part of the snapshot and the schema was valid when the
+/// snapshot was taken, so neither retrieval nor meta-schema validation happens here.
+pub(crate) fn build_from_snapshot(
+    mut config: ValidationOptions,
+    snapshot: Snapshot<'_>,
+) -> Result<Validator, ValidationError<'static>> {
+    let draft = snapshot.draft.draft();
+    snapshot.options.apply(&mut config);
+    config.with_draft(draft).without_schema_validation();
+    config.resources.clear();
+    let schema = snapshot.schema.into_owned();
+    let resources = std::iter::once((
+        snapshot.base_uri.to_string(),
+        draft.create_resource(schema.clone()),
+    ))
+    .chain(snapshot.resources.into_iter().map(|resource| {
+        (
+            resource.uri.into_owned(),
+            resource
+                .draft
+                .draft()
+                .create_resource(resource.contents.into_owned()),
+        )
+    }));
+    let registry = SPECIFICATIONS.clone().try_with_resources_and_retriever(
+        resources,
+        &DefaultRetriever,
+        draft,
+    )?;
+    build_with_registry(
+        config,
+        &schema,
+        draft,
+        &snapshot.base_uri,
+        Arc::new(registry),
+    )
+}
+
+/// Collect all problems in a schema: meta-schema violations, resolving errors and errors of
+/// individual keywords.
+pub(crate) fn check_schema(
+    mut config: ValidationOptions,
+    schema: &Value,
+) -> Result<(), SchemaErrors> {
+    let draft = config.draft_for(schema);
+    let (base_uri, resources) = prepare_resources(&mut config, draft, schema);
+    let retriever = Arc::clone(&config.retriever);
+    let registry = SPECIFICATIONS
+        .clone()
+        .try_with_resources_and_retriever(resources.into_iter(), &*retriever, draft)
+        .map(Arc::new);
+    let validator = match &registry {
+        Ok(registry) => Some(meta_schema_validator(&config, registry, draft, schema)),
+        // Dialect meta-schemas are compiled with the registry, but drafts do not need it
+        Err(_) if config.dialect_for(schema).is_none() => Some(Ok(Arc::clone(
+            META_SCHEMA_VALIDATORS.get(&draft).expect("Existing draft"),
+        ))),
+        Err(_) => None,
+    };
+    let mut errors: Vec<_> = match validator {
+        Some(Ok(validator)) => validator
+            .validate(schema)
+            .err()
+            .into_iter()
+            .flatten()
+            .map(ValidationError::into_owned)
+            .collect(),
+        Some(Err(error)) => vec![error],
+        None => Vec::new(),
+    };
+    match registry {
+        Ok(registry) => {
+            let compile_errors = match compile_all(config, schema, draft, &base_uri, registry) {
+                Ok(compile_errors) => compile_errors,
+                Err(error) => vec![error],
+            };
+            // Invalid keyword values are usually reported by the meta-schema already
+            let reported: Vec<_> = errors
+                .iter()
+                .map(|error| error.instance_path.as_str().to_string())
+                .collect();
+            errors.extend(compile_errors.into_iter().filter(|error| {
+                let location = error.instance_path.as_str();
+                !reported.iter().any(|reported| {
+                    location
+                        .strip_prefix(reported.as_str())
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+                })
+            }));
+        }
+        Err(error) => errors.push(error.into()),
+    }
+    // The same location may violate several branches of a meta-schema in the same way
+    let mut seen = AHashSet::with_capacity(errors.len());
+    errors
+        .retain(|error| seen.insert((error.instance_path.as_str().to_string(), error.to_string())));
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(SchemaErrors::new(errors))
+    }
+}
+
+/// Compile the schema and return errors of all keywords that failed to compile.
+fn compile_all(
+    config: ValidationOptions,
+    schema: &Value,
+    draft: Draft,
+    base_uri: &str,
+    registry: Arc<Registry>,
+) -> Result<Vec<ValidationError<'static>>, ValidationError<'static>> {
+    let vocabularies = registry.find_vocabularies(draft, schema);
+    let resolver = Rc::new(registry.try_resolver(base_uri)?);
+    let config = Arc::new(config);
+    let ctx = Context::new(
+        Arc::clone(&config),
+        Arc::clone(&registry),
+        resolver,
+        vocabularies,
+        draft,
+        Location::new(),
+        Arc::new(PatternCache::new(config.pattern_options())),
+    )
+    .collecting_errors();
+    let mut errors = Vec::new();
+    if let Err(error) = compile(&ctx, draft.create_resource_ref(schema)) {
+        errors.push(error.into_owned());
+    }
+    if let Some(collected) = &ctx.errors {
+        errors.extend(collected.borrow_mut().drain(..));
+    }
+    Ok(errors)
+}
+
+/// Collect the root schema and additional resources to use in resolving.
+fn prepare_resources(
+    config: &mut ValidationOptions,
+    draft: Draft,
+    schema: &Value,
+) -> (String, Vec<(String, Resource)>) {
+    let resource = draft.create_resource(schema.clone());
+    let base_uri = match (resource.id(), config.base_uri.as_deref()) {
+        // A relative `$id` is resolved against the configured base URI
+        (Some(id), Some(base_uri)) => uri::from_str(base_uri)
+            .and_then(|base_uri| uri::resolve_against(&base_uri.borrow(), id))
+            .map_or_else(|_| id.to_string(), |resolved| resolved.into_string()),
+        (Some(id), None) => id.to_string(),
+        (None, base_uri) => base_uri.unwrap_or(DEFAULT_ROOT_URL).to_string(),
+    };
+
+    let mut resources = Vec::with_capacity(1 + config.resources.len());
+    resources.push((base_uri.clone(), resource));
+    for dialect in config.dialects() {
+        // Meta-schemas are needed to find the vocabularies of their dialects
+        if !config.resources.contains_key(&**dialect.uri()) {
+            resources.push((dialect.uri().to_string(), dialect.resource()));
+        }
+    }
+    for (uri, resource) in config.resources.drain() {
+        resources.push((uri, resource));
+    }
+    (base_uri, resources)
+}
+
+fn build_with_registry(
+    config: ValidationOptions,
+    schema: &Value,
+    draft: Draft,
+    base_uri: &str,
+    registry: Arc<Registry>,
+) -> Result<Validator, ValidationError<'static>> {
+    let resource_ref = draft.create_resource_ref(schema);
     let vocabularies = registry.find_vocabularies(draft, schema);
-    let resolver = Rc::new(registry.try_resolver(&base_uri)?);
+    let resolver = Rc::new(registry.try_resolver(base_uri)?);
 
     let config = Arc::new(config);
     let ctx = Context::new(
@@ -317,13 +660,12 @@ pub(crate) fn build_validator(
         vocabularies,
         draft,
         Location::new(),
+        Arc::new(PatternCache::new(config.pattern_options())),
     );
 
     // Validate the schema itself
     if config.validate_schema {
-        if let Some(mut errors) = META_SCHEMA_VALIDATORS
-            .get(&draft)
-            .expect("Existing draft")
+        if let Some(mut errors) = meta_schema_validator(&config, &registry, draft, schema)?
             .validate(schema)
             .err()
         {
@@ -336,7 +678,13 @@ pub(crate) fn build_validator(
 
     // Finally, compile the validator
     let root = compile(&ctx, resource_ref).map_err(|err| err.into_owned())?;
-    Ok(Validator { root, config })
+    Ok(Validator {
+        root,
+        config,
+        registry,
+        draft,
+        base_uri: base_uri.to_string(),
+    })
 }
 
 /// Compile a JSON Schema instance to a tree of nodes.
@@ -384,11 +732,16 @@ pub(crate) fn compile_with<'a>(
                     return if let Some(validator) =
                         keywords::ref_::compile_ref(ctx, schema, reference)
                     {
-                        let validators = vec![(BuiltinKeyword::Ref.into(), validator?)];
+                        let validators = ctx
+                            .report("$ref", validator)?
+                            .map(|validator| (BuiltinKeyword::Ref.into(), validator))
+                            .into_iter()
+                            .collect();
                         Ok(SchemaNode::from_keywords(
                             ctx,
                             validators,
                             Some(annotations),
+                            None,
                         ))
                     } else {
                         // Infinite reference to the same location
@@ -399,17 +752,25 @@ pub(crate) fn compile_with<'a>(
 
             let mut validators = Vec::with_capacity(schema.len());
             let mut annotations = AHashMap::new();
+            let mut error_messages = None;
             for (keyword, value) in schema {
                 // Check if this keyword is overridden, then check the standard definitions
                 if let Some(factory) = ctx.get_keyword_factory(keyword) {
-                    let path = ctx.location().join(keyword);
-                    let validator = CustomKeyword::new(factory.init(schema, value, path)?);
-                    let validator: BoxedValidator = Box::new(validator);
-                    validators.push((Keyword::custom(keyword), validator));
+                    if let Some(validator) = ctx.report(
+                        keyword,
+                        ctx.init_custom_keyword(&**factory, schema, keyword, value),
+                    )? {
+                        let validator: BoxedValidator = Box::new(CustomKeyword::new(validator));
+                        validators.push((Keyword::custom(keyword), validator));
+                    }
+                } else if keyword == "errorMessage" && ctx.are_error_messages_used() {
+                    error_messages = ctx.report(keyword, ErrorMessages::compile(ctx, value))?;
                 } else if let Some((keyword, validator)) = keywords::get_for_draft(ctx, keyword)
                     .and_then(|(keyword, f)| f(ctx, schema, value).map(|v| (keyword, v)))
                 {
-                    validators.push((keyword, validator.map_err(|err| err.into_owned())?));
+                    if let Some(validator) = ctx.report(keyword.as_str(), validator)? {
+                        validators.push((keyword, validator));
+                    }
                 } else if !ctx.is_known_keyword(keyword) {
                     // Treat all non-validation keywords as annotations
                     annotations.insert(keyword.to_string(), value.clone());
@@ -420,7 +781,12 @@ pub(crate) fn compile_with<'a>(
             } else {
                 Some(annotations)
             };
-            Ok(SchemaNode::from_keywords(ctx, validators, annotations))
+            Ok(SchemaNode::from_keywords(
+                ctx,
+                validators,
+                annotations,
+                error_messages,
+            ))
         }
         _ => Err(ValidationError::multiple_type_error(
             Location::new(),
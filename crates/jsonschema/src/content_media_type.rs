@@ -1,17 +1,157 @@
+//! Checks for the `contentMediaType` keyword.
 use ahash::AHashMap;
 use once_cell::sync::Lazy;
 use serde_json::{from_str, Value};
+use std::{error::Error, fmt, sync::Arc};
 
-pub(crate) type ContentMediaTypeCheckType = fn(&str) -> bool;
+/// A check for content of a specific media type, used by the `contentMediaType` keyword.
+///
+/// Implement this trait for checks that carry their own configuration, e.g. a compiled XML
+/// schema. Any `Fn(&str) -> bool` closure implements it too, without an explanation of failures.
+///
+/// # Example
+///
+/// ```rust
+/// use jsonschema::ContentMediaType;
+///
+/// struct Prefixed {
+///     prefix: String,
+/// }
+///
+/// impl ContentMediaType for Prefixed {
+///     fn check(&self, content: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+///         if content.starts_with(&self.prefix) {
+///             Ok(())
+///         } else {
+///             Err(format!("expected content to start with `{}`", self.prefix).into())
+///         }
+///     }
+/// }
+///
+/// let schema = serde_json::json!({"contentMediaType": "application/x-custom"});
+/// let validator = jsonschema::options()
+///     .with_content_media_type("application/x-custom", Prefixed { prefix: "custom:".into() })
+///     .with_draft(jsonschema::Draft::Draft7)
+///     .build(&schema)
+///     .expect("Invalid schema");
+/// let instance = serde_json::json!("other");
+/// let error = validator.validate(&instance).expect_err("Should fail").next().unwrap();
+/// assert_eq!(
+///     error.to_string(),
+///     r#""other" is not compliant with "application/x-custom" media type: expected content to start with `custom:`"#
+/// );
+/// ```
+pub trait ContentMediaType: Send + Sync + 'static {
+    /// Check the content, returning the reason it is not valid for this media type otherwise.
+    ///
+    /// # Errors
+    ///
+    /// The content is not valid for this media type.
+    fn check(&self, content: &str) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+impl<F> ContentMediaType for F
+where
+    F: Fn(&str) -> bool + Send + Sync + 'static,
+{
+    fn check(&self, content: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if self(content) {
+            Ok(())
+        } else {
+            Err(Box::new(Invalid))
+        }
+    }
+}
+
+/// Failure of a handler that does not explain why the content is invalid.
+#[derive(Debug)]
+pub(crate) struct Invalid;
+
+impl fmt::Display for Invalid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid content")
+    }
+}
+
+impl Error for Invalid {}
+
+/// The explanation of a handler failure, if there is one.
+pub(crate) fn reason(error: &(dyn Error + Send + Sync + 'static)) -> Option<String> {
+    if error.is::<Invalid>() {
+        None
+    } else {
+        Some(error.to_string())
+    }
+}
+
+pub(crate) type ContentMediaTypeHandler = Arc<dyn ContentMediaType>;
+type CheckResult = Result<(), Box<dyn Error + Send + Sync>>;
+
+fn check_json(content: &str) -> CheckResult {
+    from_str::<Value>(content)?;
+    Ok(())
+}
+
+fn check_form_urlencoded(content: &str) -> CheckResult {
+    let bytes = content.as_bytes();
+    for (idx, byte) in bytes.iter().enumerate() {
+        match byte {
+            b'%' => {
+                let is_escape = bytes
+                    .get(idx + 1..idx + 3)
+                    .is_some_and(|digits| digits.iter().all(u8::is_ascii_hexdigit));
+                if !is_escape {
+                    return Err(format!("invalid percent-encoding at position {idx}").into());
+                }
+            }
+            b'#' | b' ' => {
+                return Err(format!("unexpected character at position {idx}").into());
+            }
+            byte if byte.is_ascii_control() => {
+                return Err(format!("unexpected character at position {idx}").into());
+            }
+            _ => {}
+        }
+    }
+    percent_encoding::percent_decode_str(content).decode_utf8()?;
+    Ok(())
+}
+
+#[cfg(feature = "csv")]
+fn check_csv(content: &str) -> CheckResult {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(content.as_bytes());
+    for record in reader.records() {
+        record?;
+    }
+    Ok(())
+}
+
+/// Whether content of this media type is JSON, e.g. `application/json` or `application/geo+json`.
+pub(crate) fn is_json_media_type(media_type: &str) -> bool {
+    let essence = media_type.split(';').next().unwrap_or_default().trim();
+    essence.eq_ignore_ascii_case("application/json") || essence.ends_with("+json")
+}
+
+struct Checker(fn(&str) -> CheckResult);
 
-pub(crate) fn is_json(instance_string: &str) -> bool {
-    from_str::<Value>(instance_string).is_ok()
+impl ContentMediaType for Checker {
+    fn check(&self, content: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        (self.0)(content)
+    }
 }
 
 pub(crate) static DEFAULT_CONTENT_MEDIA_TYPE_CHECKS: Lazy<
-    AHashMap<&'static str, ContentMediaTypeCheckType>,
+    AHashMap<&'static str, ContentMediaTypeHandler>,
 > = Lazy::new(|| {
-    let mut map: AHashMap<&'static str, ContentMediaTypeCheckType> = AHashMap::with_capacity(1);
-    map.insert("application/json", is_json);
+    let mut map: AHashMap<&'static str, ContentMediaTypeHandler> = AHashMap::with_capacity(3);
+    map.insert("application/json", Arc::new(Checker(check_json)));
+    map.insert(
+        "application/x-www-form-urlencoded",
+        Arc::new(Checker(check_form_urlencoded)),
+    );
+    #[cfg(feature = "csv")]
+    map.insert("text/csv", Arc::new(Checker(check_csv)));
     map
 });
@@ -0,0 +1,404 @@
+//! Applying `default` values to instances.
+//!
+//! Defaults are collected by walking the compiled schema tree alongside the instance. Each
+//! validator that composes other schemas decides which of its subschemas contribute defaults,
+//! see [`Validator::validate_and_apply_defaults`](crate::Validator::validate_and_apply_defaults).
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    error::ValidationError,
+    node::SchemaNode,
+    paths::{LazyLocation, Location},
+    validator::Validate,
+};
+
+/// Policy for `default` values declared inside conditional subschemas.
+///
+/// Applies to `if` / `then` / `else`, `anyOf` and `oneOf`. Defaults from `properties`, `items`,
+/// `prefixItems`, `allOf`, `$ref`, `$recursiveRef` and `$dynamicRef` are always applied, while
+/// defaults inside `not` and the `if` schema itself are never applied.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConditionalDefaults {
+    /// Apply defaults only from branches that match the instance: `then` if the `if` schema is
+    /// valid and `else` otherwise, every valid `anyOf` branch and the `oneOf` branch if exactly one
+    /// of them is valid.
+    #[default]
+    Matching,
+    /// Never apply defaults declared inside conditional branches.
+    Ignore,
+}
+
+/// A member of an object or an array that receives a default value.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Member {
+    Property(String),
+    Index(usize),
+}
+
+#[derive(Debug)]
+struct Candidate {
+    value: Value,
+    schema_path: Location,
+}
+
+/// Default values collected from the schema tree, keyed by the location of their container
+/// and the missing member.
+#[derive(Debug)]
+pub(crate) struct Defaults {
+    policy: ConditionalDefaults,
+    /// Whether the instance being walked is a default value itself rather than a part of the
+    /// input instance.
+    expanding: bool,
+    // Ordered so parents are always inserted before defaults nested in them
+    candidates: BTreeMap<(String, Member), Vec<Candidate>>,
+}
+
+impl Defaults {
+    pub(crate) fn new(policy: ConditionalDefaults) -> Self {
+        Defaults {
+            policy,
+            expanding: false,
+            candidates: BTreeMap::new(),
+        }
+    }
+
+    /// Whether defaults from conditional branches should be collected.
+    pub(crate) fn follows_conditionals(&self) -> bool {
+        self.policy == ConditionalDefaults::Matching
+    }
+
+    /// Whether the current instance is a default value. Recursive references are not followed in
+    /// this case, otherwise a recursive schema with defaults would expand indefinitely.
+    pub(crate) fn is_expanding(&self) -> bool {
+        self.expanding
+    }
+
+    /// Collect defaults for the properties declared in `properties` and recurse into the present
+    /// ones.
+    pub(crate) fn collect_properties<'a>(
+        &mut self,
+        properties: impl Iterator<Item = (&'a String, &'a SchemaNode)>,
+        object: &Map<String, Value>,
+        location: &LazyLocation,
+    ) {
+        for (name, node) in properties {
+            if let Some(value) = object.get(name) {
+                node.collect_defaults(value, &location.push(name), self);
+            } else if let Some(default) = node.default_value() {
+                self.add(node, default, location, Member::Property(name.clone()));
+            }
+        }
+    }
+
+    /// Collect defaults for positional `schemas` and recurse into the present items. Defaults
+    /// are only added contiguously after the last item of the array.
+    pub(crate) fn collect_items(
+        &mut self,
+        schemas: &[SchemaNode],
+        items: &[Value],
+        location: &LazyLocation,
+    ) {
+        for (idx, node) in schemas.iter().enumerate() {
+            if let Some(item) = items.get(idx) {
+                node.collect_defaults(item, &location.push(idx), self);
+            } else if let Some(default) = node.default_value() {
+                self.add(node, default, location, Member::Index(idx));
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn add(&mut self, node: &SchemaNode, default: &Value, location: &LazyLocation, member: Member) {
+        // Defaults nested inside the default value are collected as if it was already inserted
+        {
+            let location = match &member {
+                Member::Property(name) => location.push(name),
+                Member::Index(idx) => location.push(*idx),
+            };
+            let expanding = std::mem::replace(&mut self.expanding, true);
+            node.collect_defaults(default, &location, self);
+            self.expanding = expanding;
+        }
+        let parent = Location::from(location);
+        let candidates = self
+            .candidates
+            .entry((parent.as_str().to_string(), member))
+            .or_default();
+        if candidates
+            .iter()
+            .all(|candidate| candidate.value != *default)
+        {
+            candidates.push(Candidate {
+                value: default.clone(),
+                schema_path: node.location().join("default"),
+            });
+        }
+    }
+
+    /// Insert the collected defaults into `instance`. If some member has more than one distinct
+    /// default, the instance is not modified and an error is returned for every such member.
+    pub(crate) fn apply(self, instance: &mut Value) -> Result<(), Vec<ValidationError<'static>>> {
+        let conflicts: Vec<_> = self
+            .candidates
+            .iter()
+            .filter(|(_, candidates)| candidates.len() > 1)
+            .map(|((parent, member), candidates)| {
+                let location = Location::from_escaped(parent.clone());
+                let instance_path = match member {
+                    Member::Property(name) => location.join(name),
+                    Member::Index(idx) => location.join(*idx),
+                };
+                ValidationError::conflicting_defaults(
+                    candidates[1].schema_path.clone(),
+                    instance_path,
+                    candidates
+                        .iter()
+                        .map(|candidate| candidate.value.clone())
+                        .collect(),
+                )
+            })
+            .collect();
+        if !conflicts.is_empty() {
+            return Err(conflicts);
+        }
+        for ((parent, member), mut candidates) in self.candidates {
+            let value = candidates.pop().expect("At least one candidate").value;
+            match (instance.pointer_mut(&parent), member) {
+                (Some(Value::Object(object)), Member::Property(name)) => {
+                    object.entry(name).or_insert(value);
+                }
+                (Some(Value::Array(items)), Member::Index(idx)) if idx == items.len() => {
+                    items.push(value);
+                }
+                // The container is a default with a different shape than the schema expects
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::ValidationErrorKind, ConditionalDefaults, ValidationError};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn apply(schema: &Value, instance: &Value) -> Value {
+        let validator = crate::validator_for(schema).expect("Invalid schema");
+        let mut instance = instance.clone();
+        // Some cases are invalid by design, only the inserted defaults matter here
+        let _ = validator.validate_and_apply_defaults(&mut instance);
+        instance
+    }
+
+    #[test_case(
+        &json!({"properties": {"a": {"default": 1}, "b": {"default": "x"}}}),
+        &json!({"b": "y"}),
+        &json!({"a": 1, "b": "y"});
+        "missing properties"
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"properties": {"b": {"default": 1}}}}}),
+        &json!({"a": {}}),
+        &json!({"a": {"b": 1}});
+        "nested properties"
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"default": {}, "properties": {"b": {"default": 1}}}}}),
+        &json!({}),
+        &json!({"a": {"b": 1}});
+        "defaults inside defaults"
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"properties": {"b": {"default": 1}}}}}),
+        &json!({}),
+        &json!({});
+        "no default for the parent"
+    )]
+    #[test_case(
+        &json!({"items": {"properties": {"a": {"default": 0}}}}),
+        &json!([{}, {"a": 1}]),
+        &json!([{"a": 0}, {"a": 1}]);
+        "items"
+    )]
+    #[test_case(
+        &json!({"$schema": "https://json-schema.org/draft/2020-12/schema", "prefixItems": [{}, {"default": 1}, {"default": 2}]}),
+        &json!(["a"]),
+        &json!(["a", 1, 2]);
+        "prefix items"
+    )]
+    #[test_case(
+        &json!({"$schema": "https://json-schema.org/draft/2020-12/schema", "prefixItems": [{"default": 0}, {}, {"default": 2}]}),
+        &json!([]),
+        &json!([0]);
+        "prefix items stop at the first item without default"
+    )]
+    #[test_case(
+        &json!({"$defs": {"A": {"properties": {"a": {"default": 1}}}}, "$ref": "#/$defs/A"}),
+        &json!({}),
+        &json!({"a": 1});
+        "reference"
+    )]
+    #[test_case(
+        &json!({"allOf": [{"properties": {"a": {"default": 1}}}, {"properties": {"a": {"default": 1}, "b": {"default": 2}}}]}),
+        &json!({}),
+        &json!({"a": 1, "b": 2});
+        "all of with equal defaults"
+    )]
+    #[test_case(
+        &json!({"if": {"required": ["kind"]}, "then": {"properties": {"a": {"default": 1}}}, "else": {"properties": {"b": {"default": 2}}}}),
+        &json!({"kind": "x"}),
+        &json!({"kind": "x", "a": 1});
+        "if then"
+    )]
+    #[test_case(
+        &json!({"if": {"required": ["kind"]}, "then": {"properties": {"a": {"default": 1}}}, "else": {"properties": {"b": {"default": 2}}}}),
+        &json!({}),
+        &json!({"b": 2});
+        "if else"
+    )]
+    #[test_case(
+        &json!({"oneOf": [{"properties": {"a": {"const": 1}, "b": {"default": 1}}}, {"properties": {"a": {"const": 2}, "c": {"default": 2}}}]}),
+        &json!({"a": 2}),
+        &json!({"a": 2, "c": 2});
+        "one of with a single valid branch"
+    )]
+    #[test_case(
+        &json!({"oneOf": [{"properties": {"b": {"default": 1}}}, {"properties": {"c": {"default": 2}}, "required": ["c"]}]}),
+        &json!({"c": 0}),
+        &json!({"c": 0});
+        "one of with multiple valid branches"
+    )]
+    #[test_case(
+        &json!({"anyOf": [{"properties": {"b": {"default": 1}}}, {"properties": {"c": {"default": 2}}, "required": ["a"]}]}),
+        &json!({}),
+        &json!({"b": 1});
+        "any of"
+    )]
+    #[test_case(
+        &json!({"not": {"properties": {"a": {"default": 1}, "b": {"const": 2}}}}),
+        &json!({"b": 3}),
+        &json!({"b": 3});
+        "not"
+    )]
+    #[test_case(
+        &json!({"$defs": {"Config": {"properties": {"port": {"default": 80}}}}, "properties": {"config": {"$ref": "#/$defs/Config", "default": {}}}}),
+        &json!({}),
+        &json!({"config": {"port": 80}});
+        "defaults inside referenced defaults"
+    )]
+    #[test_case(
+        &json!({"properties": {"node": {"$ref": "#", "default": {}}, "value": {"default": 0}}}),
+        &json!({"value": 1}),
+        &json!({"node": {"node": {}, "value": 0}, "value": 1});
+        "recursive schema"
+    )]
+    #[test_case(
+        &json!({"$schema": "https://json-schema.org/draft/2020-12/schema", "$dynamicAnchor": "node", "properties": {"child": {"$dynamicRef": "#node"}, "value": {"default": 0}}}),
+        &json!({"child": {"child": {}}}),
+        &json!({"child": {"child": {"value": 0}, "value": 0}, "value": 0});
+        "dynamic reference"
+    )]
+    #[test_case(
+        &json!({"$schema": "https://json-schema.org/draft/2019-09/schema", "$recursiveAnchor": true, "properties": {"child": {"$recursiveRef": "#"}, "value": {"default": 0}}}),
+        &json!({"child": {"child": {}}}),
+        &json!({"child": {"child": {"value": 0}, "value": 0}, "value": 0});
+        "recursive reference"
+    )]
+    #[test_case(
+        &json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "$id": "https://example.com/strict-tree",
+            "$dynamicAnchor": "node",
+            "$ref": "tree",
+            "properties": {"label": {"default": "unnamed"}},
+            "$defs": {
+                "tree": {
+                    "$id": "https://example.com/tree",
+                    "$dynamicAnchor": "node",
+                    "properties": {"children": {"items": {"$dynamicRef": "#node"}}}
+                }
+            }
+        }),
+        &json!({"children": [{}]}),
+        &json!({"children": [{"label": "unnamed"}], "label": "unnamed"});
+        "dynamic reference into the dynamic scope"
+    )]
+    #[test_case(
+        &json!({"$schema": "https://json-schema.org/draft/2020-12/schema", "$dynamicAnchor": "node", "properties": {"child": {"$dynamicRef": "#node", "default": {}}, "value": {"default": 0}}}),
+        &json!({}),
+        &json!({"child": {"child": {}, "value": 0}, "value": 0});
+        "dynamic reference inside a default"
+    )]
+    fn applies_defaults(schema: &Value, instance: &Value, expected: &Value) {
+        assert_eq!(apply(schema, instance), *expected);
+    }
+
+    #[test]
+    fn ignore_conditional_defaults() {
+        let schema = json!({
+            "properties": {"a": {"default": 1}},
+            "if": true,
+            "then": {"properties": {"b": {"default": 2}}},
+            "anyOf": [{"properties": {"c": {"default": 3}}}]
+        });
+        let validator = crate::options()
+            .with_conditional_defaults(ConditionalDefaults::Ignore)
+            .build(&schema)
+            .expect("Invalid schema");
+        let mut instance = json!({});
+        assert!(validator.validate_and_apply_defaults(&mut instance).is_ok());
+        assert_eq!(instance, json!({"a": 1}));
+    }
+
+    #[test]
+    fn conflicting_defaults() {
+        let schema = json!({
+            "allOf": [
+                {"properties": {"a": {"default": 1}, "b": {"default": 1}}},
+                {"properties": {"a": {"default": 2}}}
+            ]
+        });
+        let validator = crate::validator_for(&schema).expect("Invalid schema");
+        let mut instance = json!({});
+        let errors: Vec<_> = validator
+            .validate_and_apply_defaults(&mut instance)
+            .expect_err("Defaults should conflict")
+            .map(ValidationError::into_owned)
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].instance_path.as_str(), "/a");
+        assert_eq!(
+            errors[0].schema_path.as_str(),
+            "/allOf/1/properties/a/default"
+        );
+        assert!(matches!(
+            &errors[0].kind,
+            ValidationErrorKind::ConflictingDefaults { defaults } if *defaults == [json!(1), json!(2)]
+        ));
+        assert_eq!(errors[0].to_string(), "Conflicting default values: 1, 2");
+        // The instance is left untouched
+        assert_eq!(instance, json!({}));
+    }
+
+    #[test]
+    fn validates_after_applying() {
+        let schema = json!({
+            "properties": {"a": {"type": "string", "default": 1}},
+            "required": ["a"]
+        });
+        let validator = crate::validator_for(&schema).expect("Invalid schema");
+        let mut instance = json!({});
+        let errors: Vec<_> = validator
+            .validate_and_apply_defaults(&mut instance)
+            .expect_err("The default is invalid")
+            .map(|error| error.to_string())
+            .collect();
+        assert_eq!(errors, vec![r#"1 is not of type "string""#]);
+    }
+}
@@ -0,0 +1,451 @@
+//! Custom JSON Schema dialects.
+use crate::{
+    keywords::custom::{Keyword, KeywordFactory},
+    paths::Location,
+    ValidationError,
+};
+use ahash::AHashMap;
+use referencing::{Draft, Resource};
+use serde_json::{Map, Value};
+use std::{fmt, sync::Arc};
+
+/// A custom JSON Schema dialect, identified by the URI of its meta-schema.
+///
+/// Schemas that declare this URI in `$schema` are validated against the dialect's meta-schema
+/// and compiled with the keywords of the draft the meta-schema is written in, the vocabularies
+/// listed in its `$vocabulary`, and the keywords registered with [`Dialect::with_keyword`].
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use jsonschema::{paths::{LazyLocation, Location}, Dialect, ErrorIterator, Keyword, ValidationError};
+/// use serde_json::{json, Map, Value};
+///
+/// struct Even;
+///
+/// impl Keyword for Even {
+///     fn validate<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
+///         if self.is_valid(instance) {
+///             Box::new(std::iter::empty())
+///         } else {
+///             let error = ValidationError::custom(
+///                 Location::new(),
+///                 location.into(),
+///                 instance,
+///                 "Number is not even",
+///             );
+///             Box::new(std::iter::once(error))
+///         }
+///     }
+///     fn is_valid(&self, instance: &Value) -> bool {
+///         instance.as_u64().map_or(true, |value| value % 2 == 0)
+///     }
+/// }
+///
+/// let dialect = Dialect::new(
+///     "https://example.com/dialect",
+///     json!({
+///         "$schema": "https://json-schema.org/draft/2020-12/schema",
+///         "$id": "https://example.com/dialect",
+///         "$vocabulary": {
+///             "https://json-schema.org/draft/2020-12/vocab/core": true,
+///             "https://json-schema.org/draft/2020-12/vocab/applicator": true,
+///             "https://json-schema.org/draft/2020-12/vocab/validation": true,
+///             "https://example.com/vocab/parity": true
+///         },
+///         "$dynamicAnchor": "meta",
+///         "allOf": [{"$ref": "https://json-schema.org/draft/2020-12/schema"}],
+///         "properties": {"even": {"type": "boolean"}}
+///     }),
+/// )
+/// .with_keyword("even", |_: &Map<String, Value>, _: &Value, _: Location| {
+///     Ok(Box::new(Even) as Box<dyn Keyword>)
+/// });
+///
+/// let schema = json!({"$schema": "https://example.com/dialect", "even": true});
+/// let validator = jsonschema::options().with_dialect(dialect.clone()).build(&schema)?;
+/// assert!(validator.is_valid(&json!(2)));
+/// assert!(!validator.is_valid(&json!(3)));
+///
+/// // The schema is checked against the dialect's meta-schema
+/// let schema = json!({"$schema": "https://example.com/dialect", "even": "yes"});
+/// assert!(jsonschema::options().with_dialect(dialect).build(&schema).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Dialect {
+    uri: Arc<str>,
+    draft: Draft,
+    meta_schema: Arc<Value>,
+    keywords: AHashMap<String, Arc<dyn KeywordFactory>>,
+}
+
+impl fmt::Debug for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dialect")
+            .field("uri", &self.uri)
+            .field("draft", &self.draft)
+            .field("keywords", &self.keywords.keys().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Dialect {
+    /// Create a dialect from its meta-schema, available at the given URI.
+    ///
+    /// The draft of the dialect is detected from the `$schema` of the meta-schema, falling back
+    /// to the default draft. Other meta-schemas it references are loaded like any other
+    /// external resource.
+    #[must_use]
+    pub fn new(uri: impl Into<String>, meta_schema: Value) -> Self {
+        let uri = uri.into();
+        let draft = Draft::default().detect(&meta_schema).unwrap_or_default();
+        Dialect {
+            uri: Arc::from(normalize(&uri)),
+            draft,
+            meta_schema: Arc::new(meta_schema),
+            keywords: AHashMap::default(),
+        }
+    }
+    /// Register a custom keyword of this dialect.
+    ///
+    /// It is used only in schemas of this dialect, and has the same signature as
+    /// [`ValidationOptions::with_keyword`], which takes precedence for keywords registered in both.
+    #[must_use]
+    pub fn with_keyword<N, F>(mut self, name: N, factory: F) -> Self
+    where
+        N: Into<String>,
+        F: for<'a> Fn(
+                &'a Map<String, Value>,
+                &'a Value,
+                Location,
+            ) -> Result<Box<dyn Keyword>, ValidationError<'a>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.keywords.insert(name.into(), Arc::new(factory));
+        self
+    }
+    pub(crate) fn uri(&self) -> &Arc<str> {
+        &self.uri
+    }
+    pub(crate) fn draft(&self) -> Draft {
+        self.draft
+    }
+    pub(crate) fn meta_schema(&self) -> &Value {
+        &self.meta_schema
+    }
+    pub(crate) fn resource(&self) -> Resource {
+        self.draft.create_resource(Value::clone(&self.meta_schema))
+    }
+    pub(crate) fn keyword(&self, name: &str) -> Option<&Arc<dyn KeywordFactory>> {
+        self.keywords.get(name)
+    }
+}
+
+/// The normalized `$schema` URI of a schema.
+pub(crate) fn declared_uri(contents: &Value) -> Option<&str> {
+    contents
+        .as_object()
+        .and_then(|contents| contents.get("$schema"))
+        .and_then(Value::as_str)
+        .map(normalize)
+}
+
+fn normalize(uri: &str) -> &str {
+    uri.trim_end_matches('#')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dialect;
+    use crate::{paths::LazyLocation, Draft, ErrorIterator, Keyword, Resource, Retrieve, Uri};
+    use serde_json::{json, Map, Value};
+    use test_case::test_case;
+
+    struct Even;
+
+    impl Keyword for Even {
+        fn validate<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
+            if self.is_valid(instance) {
+                Box::new(std::iter::empty())
+            } else {
+                Box::new(std::iter::once(crate::ValidationError::custom(
+                    crate::paths::Location::new(),
+                    location.into(),
+                    instance,
+                    "Number is not even",
+                )))
+            }
+        }
+        fn is_valid(&self, instance: &Value) -> bool {
+            instance.as_u64().map_or(true, |value| value % 2 == 0)
+        }
+    }
+
+    struct Never;
+
+    impl Keyword for Never {
+        fn validate<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
+            Box::new(std::iter::once(crate::ValidationError::custom(
+                crate::paths::Location::new(),
+                location.into(),
+                instance,
+                "Never valid",
+            )))
+        }
+        fn is_valid(&self, _: &Value) -> bool {
+            false
+        }
+    }
+
+    fn dialect(vocabularies: &[&str]) -> Dialect {
+        let vocabularies: Map<String, Value> = vocabularies
+            .iter()
+            .map(|vocabulary| {
+                (
+                    format!("https://json-schema.org/draft/2020-12/vocab/{vocabulary}"),
+                    Value::Bool(true),
+                )
+            })
+            .collect();
+        Dialect::new(
+            "https://example.com/dialect",
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "$id": "https://example.com/dialect",
+                "$vocabulary": vocabularies,
+                "$dynamicAnchor": "meta",
+                "allOf": [{"$ref": "https://json-schema.org/draft/2020-12/schema"}],
+                "properties": {"even": {"type": "boolean"}}
+            }),
+        )
+        .with_keyword("even", |_: &Map<String, Value>, _: &Value, _| {
+            Ok(Box::new(Even) as Box<dyn Keyword>)
+        })
+    }
+
+    const ALL: &[&str] = &["core", "applicator", "validation"];
+
+    #[test_case(&json!({"$schema": "https://example.com/dialect", "even": true}), &json!(3), false)]
+    #[test_case(&json!({"$schema": "https://example.com/dialect#", "even": true}), &json!(3), false)]
+    #[test_case(&json!({"$schema": "https://example.com/dialect", "even": true}), &json!(2), true)]
+    #[test_case(&json!({"even": true}), &json!(3), true ; "other dialect")]
+    fn keywords(schema: &Value, instance: &Value, expected: bool) {
+        let validator = crate::options()
+            .with_dialect(dialect(ALL))
+            .build(schema)
+            .expect("Invalid schema");
+        assert_eq!(validator.is_valid(instance), expected);
+    }
+
+    #[test]
+    fn options_keywords_take_precedence() {
+        let schema = json!({"$schema": "https://example.com/dialect", "even": true});
+        let validator = crate::options()
+            .with_dialect(dialect(ALL))
+            .with_keyword("even", |_, _, _| Ok(Box::new(Never)))
+            .build(&schema)
+            .expect("Invalid schema");
+        assert!(!validator.is_valid(&json!(2)));
+    }
+
+    #[test_case(ALL, false)]
+    #[test_case(&["core", "applicator"], true)]
+    fn vocabularies(vocabularies: &[&str], expected: bool) {
+        let schema = json!({"$schema": "https://example.com/dialect", "minLength": 5});
+        let validator = crate::options()
+            .with_dialect(dialect(vocabularies))
+            .build(&schema)
+            .expect("Invalid schema");
+        assert_eq!(validator.is_valid(&json!("abc")), expected);
+    }
+
+    #[test]
+    fn meta_schema() {
+        let schema = json!({"$schema": "https://example.com/dialect", "even": "yes"});
+        let error = crate::options()
+            .with_dialect(dialect(ALL))
+            .build(&schema)
+            .expect_err("Should fail");
+        assert_eq!(error.instance_path.as_str(), "/even");
+        assert_eq!(error.to_string(), r#""yes" is not of type "boolean""#);
+
+        let errors = crate::options()
+            .with_dialect(dialect(ALL))
+            .check_schema(&schema)
+            .expect_err("Should fail");
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn invalid_meta_schema() {
+        let dialect = Dialect::new(
+            "https://example.com/dialect",
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "minLength": "a"
+            }),
+        );
+        let schema = json!({"$schema": "https://example.com/dialect"});
+        assert!(crate::options()
+            .with_dialect(dialect)
+            .build(&schema)
+            .is_err());
+    }
+
+    #[test]
+    fn draft() {
+        let dialect = Dialect::new(
+            "https://example.com/draft7-dialect",
+            json!({
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "$id": "https://example.com/draft7-dialect",
+                "allOf": [{"$ref": "http://json-schema.org/draft-07/schema#"}]
+            }),
+        );
+        // Draft 7 ignores keywords next to `$ref`
+        let schema = json!({
+            "$schema": "https://example.com/draft7-dialect",
+            "definitions": {"a": {}},
+            "$ref": "#/definitions/a",
+            "type": "string"
+        });
+        let validator = crate::options()
+            .with_dialect(dialect)
+            .build(&schema)
+            .expect("Invalid schema");
+        assert!(validator.is_valid(&json!(42)));
+    }
+
+    #[test_case(&json!({
+        "$schema": "https://example.com/dialect",
+        "$ref": "https://example.com/other"
+    }), true ; "referenced resource of another dialect")]
+    #[test_case(&json!({"$ref": "https://example.com/even"}), false ; "referenced resource of the dialect")]
+    #[test_case(&json!({"even": true}), true ; "root of another dialect")]
+    #[test_case(&json!({
+        "$schema": "https://example.com/dialect",
+        "$ref": "#/$defs/even",
+        "$defs": {"even": {"even": true}}
+    }), false ; "subschema of the dialect")]
+    fn keywords_scoped_to_resources(schema: &Value, expected: bool) {
+        let validator = crate::options()
+            .with_dialect(dialect(ALL))
+            .with_resource(
+                "https://example.com/other",
+                Resource::from_contents(json!({
+                    "$schema": "https://json-schema.org/draft/2020-12/schema",
+                    "even": true
+                }))
+                .expect("Invalid resource"),
+            )
+            .with_resource(
+                "https://example.com/even",
+                Draft::Draft202012.create_resource(json!({
+                    "$schema": "https://example.com/dialect",
+                    "even": true
+                })),
+            )
+            .build(schema)
+            .expect("Invalid schema");
+        assert_eq!(validator.is_valid(&json!(3)), expected);
+    }
+
+    #[test]
+    fn meta_schema_resources() {
+        let dialect = Dialect::new(
+            "https://example.com/dialect",
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "$id": "https://example.com/dialect",
+                "allOf": [{"$ref": "https://example.com/base"}]
+            }),
+        );
+        let base = Resource::from_contents(json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "properties": {"even": {"type": "boolean"}}
+        }))
+        .expect("Invalid resource");
+        let schema = json!({"$schema": "https://example.com/dialect", "even": "yes"});
+        let error = crate::options()
+            .with_dialect(dialect)
+            .with_resource("https://example.com/base", base)
+            .build(&schema)
+            .expect_err("Should fail");
+        assert_eq!(error.instance_path.as_str(), "/even");
+    }
+
+    #[test_case("https://example.com/base" ; "another dialect")]
+    #[test_case("https://example.com/dialect" ; "itself")]
+    fn meta_schema_dialects(declared: &str) {
+        let base = Dialect::new(
+            "https://example.com/base",
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "$id": "https://example.com/base",
+                "$dynamicAnchor": "meta",
+                "allOf": [{"$ref": "https://json-schema.org/draft/2020-12/schema"}],
+                "properties": {"even": {"type": "boolean"}}
+            }),
+        );
+        let dialect = Dialect::new(
+            "https://example.com/dialect",
+            json!({
+                "$schema": declared,
+                "$id": "https://example.com/dialect",
+                "allOf": [{"$ref": "https://example.com/base"}]
+            }),
+        )
+        .with_keyword("even", |_: &Map<String, Value>, _: &Value, _| {
+            Ok(Box::new(Even) as Box<dyn Keyword>)
+        });
+        let build = |schema: &Value| {
+            crate::options()
+                .with_dialect(base.clone())
+                .with_dialect(dialect.clone())
+                .build(schema)
+        };
+        let validator = build(&json!({"$schema": "https://example.com/dialect", "even": true}))
+            .expect("Invalid schema");
+        assert!(!validator.is_valid(&json!(3)));
+        assert!(build(&json!({"$schema": "https://example.com/dialect", "even": "yes"})).is_err());
+    }
+
+    struct Base(&'static str);
+
+    impl Retrieve for Base {
+        fn retrieve(
+            &self,
+            _: &Uri<&str>,
+        ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(json!({"properties": {"even": {"type": self.0}}}))
+        }
+    }
+
+    #[test]
+    fn meta_schema_retrievers() {
+        let dialect = Dialect::new(
+            "https://example.com/dialect",
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "$id": "https://example.com/dialect",
+                "allOf": [{"$ref": "https://example.com/base"}]
+            }),
+        );
+        let schema = json!({"$schema": "https://example.com/dialect", "even": true});
+        assert!(crate::options()
+            .with_dialect(dialect.clone())
+            .with_retriever(Base("boolean"))
+            .build(&schema)
+            .is_ok());
+        assert!(crate::options()
+            .with_dialect(dialect)
+            .with_retriever(Base("string"))
+            .build(&schema)
+            .is_err());
+    }
+}
@@ -25,6 +25,8 @@ pub struct ValidationError<'a> {
     pub instance_path: Location,
     /// Path to the JSON Schema keyword that failed validation.
     pub schema_path: Location,
+    /// Message that replaces the default one, set by `errorMessage` or an error formatter.
+    pub message: Option<Box<str>>,
 }
 
 /// An iterator over instances of [`ValidationError`] that represent validation error for the
@@ -57,6 +59,90 @@ pub(crate) fn error(instance: ValidationError) -> ErrorIterator {
     Box::new(once(instance))
 }
 
+/// All problems found in a schema by [`ValidationOptions::check_schema`].
+///
+/// Contains meta-schema violations together with errors that prevent the schema from being
+/// compiled, such as invalid regular expressions or unresolvable references. The
+/// [`ValidationError::instance_path`] of each error points to the offending location in the
+/// schema.
+///
+/// [`ValidationOptions::check_schema`]: crate::ValidationOptions::check_schema
+#[derive(Debug)]
+pub struct SchemaErrors {
+    errors: Vec<ValidationError<'static>>,
+}
+
+impl SchemaErrors {
+    pub(crate) fn new(errors: Vec<ValidationError<'static>>) -> SchemaErrors {
+        SchemaErrors { errors }
+    }
+    /// An iterator over the errors.
+    pub fn iter(&self) -> std::slice::Iter<'_, ValidationError<'static>> {
+        self.errors.iter()
+    }
+    /// The number of errors.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+    /// Whether there are no errors.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+    /// Consume the container and return the errors.
+    #[must_use]
+    pub fn into_errors(self) -> Vec<ValidationError<'static>> {
+        self.errors
+    }
+}
+
+impl IntoIterator for SchemaErrors {
+    type Item = ValidationError<'static>;
+    type IntoIter = std::vec::IntoIter<ValidationError<'static>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a SchemaErrors {
+    type Item = &'a ValidationError<'static>;
+    type IntoIter = std::slice::Iter<'a, ValidationError<'static>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.iter()
+    }
+}
+
+impl fmt::Display for SchemaErrors {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (idx, error) in self.errors.iter().enumerate() {
+            if idx > 0 {
+                f.write_str("\n")?;
+            }
+            if error.instance_path.as_str().is_empty() {
+                write!(f, "{error}")?;
+            } else {
+                write!(f, "{}: {error}", error.instance_path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for SchemaErrors {}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SchemaErrors {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(&self.errors)
+    }
+}
+
 /// Kinds of errors that may happen during validation
 #[derive(Debug)]
 #[allow(missing_docs)]
@@ -73,10 +159,20 @@ pub enum ValidationErrorKind {
     Constant { expected_value: Value },
     /// The input array doesn't contain items conforming to the specified schema.
     Contains,
+    /// Different `default` values apply to the same location.
+    ConflictingDefaults { defaults: Vec<Value> },
     /// The input value does not respect the defined contentEncoding
-    ContentEncoding { content_encoding: String },
+    ContentEncoding {
+        content_encoding: String,
+        /// Why the value could not be decoded, if the decoder explains it.
+        reason: Option<String>,
+    },
     /// The input value does not respect the defined contentMediaType
-    ContentMediaType { content_media_type: String },
+    ContentMediaType {
+        content_media_type: String,
+        /// Why the value is not valid for the media type, if the check explains it.
+        reason: Option<String>,
+    },
     /// Custom error message for user-defined validation.
     Custom { message: String },
     /// The input value doesn't match any of specified options.
@@ -141,6 +237,8 @@ pub enum ValidationErrorKind {
     UnevaluatedProperties { unexpected: Vec<String> },
     /// When the input array has non-unique elements.
     UniqueItems,
+    /// A valid pattern can't be compiled by the configured regular expression engine.
+    UnsupportedRegex { pattern: String, reason: String },
     /// Error during schema ref resolution.
     Referencing(referencing::Error),
 }
@@ -152,14 +250,133 @@ pub enum TypeKind {
     Multiple(PrimitiveTypesBitMap),
 }
 
+impl ValidationErrorKind {
+    /// A stable, machine-readable code of this error kind.
+    ///
+    /// For keyword errors it is the name of the keyword (e.g. `"minLength"`), while other errors
+    /// use a camel-cased name of the kind (e.g. `"fileNotFound"`).
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"minLength": 5});
+    /// let validator = jsonschema::validator_for(&schema).expect("Invalid schema");
+    /// let instance = json!("foo");
+    /// let error = validator.validate(&instance).expect_err("Invalid instance").next().unwrap();
+    /// assert_eq!(error.kind.code(), "minLength");
+    /// ```
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            ValidationErrorKind::AdditionalItems { .. } => "additionalItems",
+            ValidationErrorKind::AdditionalProperties { .. } => "additionalProperties",
+            ValidationErrorKind::AnyOf => "anyOf",
+            ValidationErrorKind::BacktrackLimitExceeded { .. } => "backtrackLimitExceeded",
+            ValidationErrorKind::Constant { .. } => "const",
+            ValidationErrorKind::Contains => "contains",
+            ValidationErrorKind::ConflictingDefaults { .. } => "conflictingDefaults",
+            ValidationErrorKind::ContentEncoding { .. } => "contentEncoding",
+            ValidationErrorKind::ContentMediaType { .. } => "contentMediaType",
+            ValidationErrorKind::Custom { .. } => "custom",
+            ValidationErrorKind::Enum { .. } => "enum",
+            ValidationErrorKind::ExclusiveMaximum { .. } => "exclusiveMaximum",
+            ValidationErrorKind::ExclusiveMinimum { .. } => "exclusiveMinimum",
+            ValidationErrorKind::FalseSchema => "falseSchema",
+            ValidationErrorKind::FileNotFound { .. } => "fileNotFound",
+            ValidationErrorKind::Format { .. } => "format",
+            ValidationErrorKind::FromUtf8 { .. } => "fromUtf8",
+            ValidationErrorKind::Utf8 { .. } => "utf8",
+            ValidationErrorKind::JSONParse { .. } => "jsonParse",
+            ValidationErrorKind::InvalidReference { .. } => "invalidReference",
+            ValidationErrorKind::MaxItems { .. } => "maxItems",
+            ValidationErrorKind::Maximum { .. } => "maximum",
+            ValidationErrorKind::MaxLength { .. } => "maxLength",
+            ValidationErrorKind::MaxProperties { .. } => "maxProperties",
+            ValidationErrorKind::MinItems { .. } => "minItems",
+            ValidationErrorKind::Minimum { .. } => "minimum",
+            ValidationErrorKind::MinLength { .. } => "minLength",
+            ValidationErrorKind::MinProperties { .. } => "minProperties",
+            ValidationErrorKind::MultipleOf { .. } => "multipleOf",
+            ValidationErrorKind::Not { .. } => "not",
+            ValidationErrorKind::OneOfMultipleValid => "oneOfMultipleValid",
+            ValidationErrorKind::OneOfNotValid => "oneOfNotValid",
+            ValidationErrorKind::Pattern { .. } => "pattern",
+            ValidationErrorKind::PropertyNames { .. } => "propertyNames",
+            ValidationErrorKind::Required { .. } => "required",
+            ValidationErrorKind::Schema => "schema",
+            ValidationErrorKind::Type { .. } => "type",
+            ValidationErrorKind::UnevaluatedItems { .. } => "unevaluatedItems",
+            ValidationErrorKind::UnevaluatedProperties { .. } => "unevaluatedProperties",
+            ValidationErrorKind::UniqueItems => "uniqueItems",
+            ValidationErrorKind::UnsupportedRegex { .. } => "unsupportedRegex",
+            ValidationErrorKind::Referencing(_) => "referencing",
+        }
+    }
+
+    /// The JSON Schema keyword that produced this error, if any.
+    ///
+    /// Errors that are not tied to a single keyword, like reference resolution failures or
+    /// errors from custom keywords, return `None`.
+    #[must_use]
+    pub const fn keyword(&self) -> Option<&'static str> {
+        Some(match self {
+            ValidationErrorKind::AdditionalItems { .. } => "additionalItems",
+            ValidationErrorKind::AdditionalProperties { .. } => "additionalProperties",
+            ValidationErrorKind::AnyOf => "anyOf",
+            ValidationErrorKind::Constant { .. } => "const",
+            ValidationErrorKind::Contains => "contains",
+            ValidationErrorKind::ContentEncoding { .. } => "contentEncoding",
+            ValidationErrorKind::ContentMediaType { .. } => "contentMediaType",
+            ValidationErrorKind::Enum { .. } => "enum",
+            ValidationErrorKind::ExclusiveMaximum { .. } => "exclusiveMaximum",
+            ValidationErrorKind::ExclusiveMinimum { .. } => "exclusiveMinimum",
+            ValidationErrorKind::Format { .. } => "format",
+            ValidationErrorKind::MaxItems { .. } => "maxItems",
+            ValidationErrorKind::Maximum { .. } => "maximum",
+            ValidationErrorKind::MaxLength { .. } => "maxLength",
+            ValidationErrorKind::MaxProperties { .. } => "maxProperties",
+            ValidationErrorKind::MinItems { .. } => "minItems",
+            ValidationErrorKind::Minimum { .. } => "minimum",
+            ValidationErrorKind::MinLength { .. } => "minLength",
+            ValidationErrorKind::MinProperties { .. } => "minProperties",
+            ValidationErrorKind::MultipleOf { .. } => "multipleOf",
+            ValidationErrorKind::Not { .. } => "not",
+            ValidationErrorKind::OneOfMultipleValid | ValidationErrorKind::OneOfNotValid => "oneOf",
+            ValidationErrorKind::Pattern { .. } => "pattern",
+            ValidationErrorKind::PropertyNames { .. } => "propertyNames",
+            ValidationErrorKind::Required { .. } => "required",
+            ValidationErrorKind::Type { .. } => "type",
+            ValidationErrorKind::UnevaluatedItems { .. } => "unevaluatedItems",
+            ValidationErrorKind::UnevaluatedProperties { .. } => "unevaluatedProperties",
+            ValidationErrorKind::UniqueItems => "uniqueItems",
+            ValidationErrorKind::BacktrackLimitExceeded { .. }
+            | ValidationErrorKind::ConflictingDefaults { .. }
+            | ValidationErrorKind::Custom { .. }
+            | ValidationErrorKind::FalseSchema
+            | ValidationErrorKind::FileNotFound { .. }
+            | ValidationErrorKind::FromUtf8 { .. }
+            | ValidationErrorKind::Utf8 { .. }
+            | ValidationErrorKind::JSONParse { .. }
+            | ValidationErrorKind::InvalidReference { .. }
+            | ValidationErrorKind::Schema
+            | ValidationErrorKind::UnsupportedRegex { .. }
+            | ValidationErrorKind::Referencing(_) => return None,
+        })
+    }
+}
+
 /// Shortcuts for creation of specific error kinds.
 impl<'a> ValidationError<'a> {
-    pub(crate) fn into_owned(self) -> ValidationError<'static> {
+    /// Convert the error into one that owns the invalid instance, so it outlives the validated
+    /// document.
+    #[must_use]
+    pub fn into_owned(self) -> ValidationError<'static> {
         ValidationError {
             instance_path: self.instance_path.clone(),
             instance: Cow::Owned(self.instance.into_owned()),
             kind: self.kind,
             schema_path: self.schema_path,
+            message: self.message,
         }
     }
 
@@ -174,6 +391,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::AdditionalItems { limit },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn additional_properties(
@@ -187,6 +405,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::AdditionalProperties { unexpected },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn any_of(
@@ -199,6 +418,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::AnyOf,
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn backtrack_limit(
@@ -212,6 +432,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::BacktrackLimitExceeded { error },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) fn constant_array(
@@ -227,6 +448,7 @@ impl<'a> ValidationError<'a> {
                 expected_value: Value::Array(expected_value.to_vec()),
             },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn constant_boolean(
@@ -242,6 +464,7 @@ impl<'a> ValidationError<'a> {
                 expected_value: Value::Bool(expected_value),
             },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn constant_null(
@@ -256,6 +479,7 @@ impl<'a> ValidationError<'a> {
                 expected_value: Value::Null,
             },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) fn constant_number(
@@ -271,6 +495,7 @@ impl<'a> ValidationError<'a> {
                 expected_value: Value::Number(expected_value.clone()),
             },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) fn constant_object(
@@ -286,6 +511,7 @@ impl<'a> ValidationError<'a> {
                 expected_value: Value::Object(expected_value.clone()),
             },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) fn constant_string(
@@ -301,6 +527,7 @@ impl<'a> ValidationError<'a> {
                 expected_value: Value::String(expected_value.to_string()),
             },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn contains(
@@ -313,6 +540,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Contains,
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) fn content_encoding(
@@ -320,14 +548,17 @@ impl<'a> ValidationError<'a> {
         instance_path: Location,
         instance: &'a Value,
         encoding: &str,
+        reason: Option<String>,
     ) -> ValidationError<'a> {
         ValidationError {
             instance_path,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::ContentEncoding {
                 content_encoding: encoding.to_string(),
+                reason,
             },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) fn content_media_type(
@@ -335,14 +566,17 @@ impl<'a> ValidationError<'a> {
         instance_path: Location,
         instance: &'a Value,
         media_type: &str,
+        reason: Option<String>,
     ) -> ValidationError<'a> {
         ValidationError {
             instance_path,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::ContentMediaType {
                 content_media_type: media_type.to_string(),
+                reason,
             },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) fn enumeration(
@@ -358,6 +592,7 @@ impl<'a> ValidationError<'a> {
                 options: options.clone(),
             },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn exclusive_maximum(
@@ -371,6 +606,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::ExclusiveMaximum { limit },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn exclusive_minimum(
@@ -384,6 +620,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::ExclusiveMinimum { limit },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn false_schema(
@@ -396,6 +633,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::FalseSchema,
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) fn file_not_found(error: io::Error) -> ValidationError<'a> {
@@ -404,6 +642,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::FileNotFound { error },
             schema_path: Location::new(),
+            message: None,
         }
     }
     pub(crate) fn format(
@@ -419,6 +658,7 @@ impl<'a> ValidationError<'a> {
                 format: format.into(),
             },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) fn from_utf8(error: FromUtf8Error) -> ValidationError<'a> {
@@ -427,6 +667,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::FromUtf8 { error },
             schema_path: Location::new(),
+            message: None,
         }
     }
     pub(crate) fn json_parse(error: serde_json::Error) -> ValidationError<'a> {
@@ -435,6 +676,20 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::JSONParse { error },
             schema_path: Location::new(),
+            message: None,
+        }
+    }
+    pub(crate) fn non_finite_number(instance_path: Location, number: f64) -> ValidationError<'a> {
+        ValidationError {
+            instance_path,
+            instance: Cow::Owned(Value::Null),
+            kind: ValidationErrorKind::JSONParse {
+                error: serde::de::Error::custom(format_args!(
+                    "{number} is not a valid JSON number"
+                )),
+            },
+            schema_path: Location::new(),
+            message: None,
         }
     }
     pub(crate) const fn max_items(
@@ -448,6 +703,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MaxItems { limit },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn maximum(
@@ -461,6 +717,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Maximum { limit },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn max_length(
@@ -474,6 +731,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MaxLength { limit },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn max_properties(
@@ -487,6 +745,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MaxProperties { limit },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn min_items(
@@ -500,6 +759,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MinItems { limit },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn minimum(
@@ -513,6 +773,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Minimum { limit },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn min_length(
@@ -526,6 +787,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MinLength { limit },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn min_properties(
@@ -539,6 +801,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MinProperties { limit },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn multiple_of(
@@ -552,6 +815,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MultipleOf { multiple_of },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn not(
@@ -565,6 +829,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Not { schema },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn one_of_multiple_valid(
@@ -577,6 +842,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::OneOfMultipleValid,
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn one_of_not_valid(
@@ -589,6 +855,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::OneOfNotValid,
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn pattern(
@@ -602,6 +869,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Pattern { pattern },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) fn property_names(
@@ -617,6 +885,7 @@ impl<'a> ValidationError<'a> {
                 error: Box::new(error.into_owned()),
             },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn required(
@@ -630,6 +899,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Required { property },
             schema_path: location,
+            message: None,
         }
     }
 
@@ -639,6 +909,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::Schema,
             schema_path: Location::new(),
+            message: None,
         }
     }
 
@@ -655,6 +926,7 @@ impl<'a> ValidationError<'a> {
                 kind: TypeKind::Single(type_name),
             },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn multiple_type_error(
@@ -670,6 +942,7 @@ impl<'a> ValidationError<'a> {
                 kind: TypeKind::Multiple(types),
             },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn unevaluated_items(
@@ -683,6 +956,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::UnevaluatedItems { unexpected },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn unevaluated_properties(
@@ -696,6 +970,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::UnevaluatedProperties { unexpected },
             schema_path: location,
+            message: None,
         }
     }
     pub(crate) const fn unique_items(
@@ -708,6 +983,22 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::UniqueItems,
             schema_path: location,
+            message: None,
+        }
+    }
+    pub(crate) const fn unsupported_regex(
+        location: Location,
+        instance_path: Location,
+        instance: &'a Value,
+        pattern: String,
+        reason: String,
+    ) -> ValidationError<'a> {
+        ValidationError {
+            instance_path,
+            instance: Cow::Borrowed(instance),
+            kind: ValidationErrorKind::UnsupportedRegex { pattern, reason },
+            schema_path: location,
+            message: None,
         }
     }
     pub(crate) fn utf8(error: Utf8Error) -> ValidationError<'a> {
@@ -716,6 +1007,7 @@ impl<'a> ValidationError<'a> {
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::Utf8 { error },
             schema_path: Location::new(),
+            message: None,
         }
     }
     /// Create a new custom validation error.
@@ -732,10 +1024,52 @@ impl<'a> ValidationError<'a> {
                 message: message.into(),
             },
             schema_path: location,
+            message: None,
+        }
+    }
+    pub(crate) fn conflicting_defaults(
+        location: Location,
+        instance_path: Location,
+        defaults: Vec<Value>,
+    ) -> ValidationError<'static> {
+        ValidationError {
+            instance_path,
+            instance: Cow::Owned(defaults[0].clone()),
+            kind: ValidationErrorKind::ConflictingDefaults { defaults },
+            schema_path: location,
+            message: None,
+        }
+    }
+    /// Override the message of this error, keeping its kind.
+    pub(crate) fn with_message(mut self, message: impl Into<Box<str>>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+/// Produces custom messages for validation errors.
+pub(crate) trait ErrorFormatter: Send + Sync + 'static {
+    fn format(&self, error: &ValidationError<'_>) -> Option<String>;
+
+    /// Override the message of `error` if the formatter produces one.
+    fn apply<'a>(&self, error: ValidationError<'a>) -> ValidationError<'a> {
+        match self.format(&error) {
+            Some(message) => error.with_message(message),
+            None => error,
         }
     }
 }
 
+impl<F> ErrorFormatter for F
+where
+    F: Fn(&ValidationError<'_>) -> Option<String> + Send + Sync + 'static,
+{
+    #[inline]
+    fn format(&self, error: &ValidationError<'_>) -> Option<String> {
+        self(error)
+    }
+}
+
 impl error::Error for ValidationError<'_> {}
 impl From<serde_json::Error> for ValidationError<'_> {
     #[inline]
@@ -751,6 +1085,7 @@ impl From<referencing::Error> for ValidationError<'_> {
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::Referencing(err),
             schema_path: Location::new(),
+            message: None,
         }
     }
 }
@@ -778,6 +1113,9 @@ impl fmt::Display for ValidationError<'_> {
     #[allow(clippy::too_many_lines)] // The function is long but it does formatting only
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(message) = &self.message {
+            return f.write_str(message);
+        }
         match &self.kind {
             ValidationErrorKind::Schema => f.write_str("Schema error"),
             ValidationErrorKind::JSONParse { error } => error.fmt(f),
@@ -852,19 +1190,33 @@ impl fmt::Display for ValidationError<'_> {
             ValidationErrorKind::Constant { expected_value } => {
                 write!(f, "{} was expected", expected_value)
             }
-            ValidationErrorKind::ContentEncoding { content_encoding } => {
+            ValidationErrorKind::ContentEncoding {
+                content_encoding,
+                reason,
+            } => {
                 write!(
                     f,
                     r#"{} is not compliant with "{}" content encoding"#,
                     self.instance, content_encoding
-                )
+                )?;
+                if let Some(reason) = reason {
+                    write!(f, ": {reason}")?;
+                }
+                Ok(())
             }
-            ValidationErrorKind::ContentMediaType { content_media_type } => {
+            ValidationErrorKind::ContentMediaType {
+                content_media_type,
+                reason,
+            } => {
                 write!(
                     f,
                     r#"{} is not compliant with "{}" media type"#,
                     self.instance, content_media_type
-                )
+                )?;
+                if let Some(reason) = reason {
+                    write!(f, ": {reason}")?;
+                }
+                Ok(())
             }
             ValidationErrorKind::FromUtf8 { error } => error.fmt(f),
             ValidationErrorKind::Utf8 { error } => error.fmt(f),
@@ -996,6 +1348,9 @@ impl fmt::Display for ValidationError<'_> {
             ValidationErrorKind::UniqueItems => {
                 write!(f, "{} has non-unique elements", self.instance)
             }
+            ValidationErrorKind::UnsupportedRegex { pattern, reason } => {
+                write!(f, "Unsupported regular expression \"{pattern}\": {reason}")
+            }
             ValidationErrorKind::Type {
                 kind: TypeKind::Single(type_),
             } => write!(f, r#"{} is not of type "{}""#, self.instance, type_),
@@ -1011,11 +1366,179 @@ impl fmt::Display for ValidationError<'_> {
                     .collect::<Vec<String>>()
                     .join(", ")
             ),
+            ValidationErrorKind::ConflictingDefaults { defaults } => write!(
+                f,
+                "Conflicting default values: {}",
+                defaults
+                    .iter()
+                    .map(Value::to_string)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
             ValidationErrorKind::Custom { message } => f.write_str(message),
         }
     }
 }
 
+/// Serializes the error into a JSON object with the following members:
+///
+/// - `code`: [`ValidationErrorKind::code`]
+/// - `keyword`: [`ValidationErrorKind::keyword`] or `null`
+/// - `message`: the error message, as produced by the [`Display`](fmt::Display) implementation,
+///   which prefers [`ValidationError::message`]
+/// - `instancePath` and `schemaPath`: JSON Pointers from [`ValidationError::instance_path`]
+///   and [`ValidationError::schema_path`]
+/// - `instance`: the value that failed validation
+/// - `details`: an object with kind-specific data, e.g. `{"limit": 5}` for `minLength`.
+///   Payloads that are not JSON values themselves, like I/O or regex errors, are serialized
+///   as `{"error": "<message>"}`, and `propertyNames` errors nest the serialized inner error.
+#[cfg(feature = "serde")]
+impl serde::Serialize for ValidationError<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+
+        let mut map_ser = serializer.serialize_map(Some(7))?;
+        map_ser.serialize_entry("code", self.kind.code())?;
+        map_ser.serialize_entry("keyword", &self.kind.keyword())?;
+        map_ser.serialize_entry("message", &self.to_string())?;
+        map_ser.serialize_entry("instancePath", self.instance_path.as_str())?;
+        map_ser.serialize_entry("schemaPath", self.schema_path.as_str())?;
+        map_ser.serialize_entry("instance", &self.instance)?;
+        map_ser.serialize_entry("details", &ErrorDetails(&self.kind))?;
+        map_ser.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+struct ErrorDetails<'a>(&'a ValidationErrorKind);
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ErrorDetails<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+
+        let mut map_ser = serializer.serialize_map(None)?;
+        match self.0 {
+            ValidationErrorKind::AnyOf
+            | ValidationErrorKind::Contains
+            | ValidationErrorKind::FalseSchema
+            | ValidationErrorKind::OneOfMultipleValid
+            | ValidationErrorKind::OneOfNotValid
+            | ValidationErrorKind::Schema
+            | ValidationErrorKind::UniqueItems => {}
+            ValidationErrorKind::AdditionalItems { limit } => {
+                map_ser.serialize_entry("limit", limit)?;
+            }
+            ValidationErrorKind::AdditionalProperties { unexpected }
+            | ValidationErrorKind::UnevaluatedItems { unexpected }
+            | ValidationErrorKind::UnevaluatedProperties { unexpected } => {
+                map_ser.serialize_entry("unexpected", unexpected)?;
+            }
+            ValidationErrorKind::BacktrackLimitExceeded { error } => {
+                map_ser.serialize_entry("error", &error.to_string())?;
+            }
+            ValidationErrorKind::FileNotFound { error } => {
+                map_ser.serialize_entry("error", &error.to_string())?;
+            }
+            ValidationErrorKind::FromUtf8 { error } => {
+                map_ser.serialize_entry("error", &error.to_string())?;
+            }
+            ValidationErrorKind::Utf8 { error } => {
+                map_ser.serialize_entry("error", &error.to_string())?;
+            }
+            ValidationErrorKind::JSONParse { error } => {
+                map_ser.serialize_entry("error", &error.to_string())?;
+            }
+            ValidationErrorKind::Referencing(error) => {
+                map_ser.serialize_entry("error", &error.to_string())?;
+            }
+            ValidationErrorKind::PropertyNames { error } => {
+                map_ser.serialize_entry("error", error)?;
+            }
+            ValidationErrorKind::Constant { expected_value } => {
+                map_ser.serialize_entry("expectedValue", expected_value)?;
+            }
+            ValidationErrorKind::ConflictingDefaults { defaults } => {
+                map_ser.serialize_entry("defaults", defaults)?;
+            }
+            ValidationErrorKind::ContentEncoding {
+                content_encoding,
+                reason,
+            } => {
+                map_ser.serialize_entry("contentEncoding", content_encoding)?;
+                if let Some(reason) = reason {
+                    map_ser.serialize_entry("reason", reason)?;
+                }
+            }
+            ValidationErrorKind::ContentMediaType {
+                content_media_type,
+                reason,
+            } => {
+                map_ser.serialize_entry("contentMediaType", content_media_type)?;
+                if let Some(reason) = reason {
+                    map_ser.serialize_entry("reason", reason)?;
+                }
+            }
+            ValidationErrorKind::Custom { message } => {
+                map_ser.serialize_entry("message", message)?;
+            }
+            ValidationErrorKind::Enum { options } => {
+                map_ser.serialize_entry("options", options)?;
+            }
+            ValidationErrorKind::ExclusiveMaximum { limit }
+            | ValidationErrorKind::ExclusiveMinimum { limit }
+            | ValidationErrorKind::Maximum { limit }
+            | ValidationErrorKind::Minimum { limit } => {
+                map_ser.serialize_entry("limit", limit)?;
+            }
+            ValidationErrorKind::MaxItems { limit }
+            | ValidationErrorKind::MaxLength { limit }
+            | ValidationErrorKind::MaxProperties { limit }
+            | ValidationErrorKind::MinItems { limit }
+            | ValidationErrorKind::MinLength { limit }
+            | ValidationErrorKind::MinProperties { limit } => {
+                map_ser.serialize_entry("limit", limit)?;
+            }
+            ValidationErrorKind::Format { format } => {
+                map_ser.serialize_entry("format", format)?;
+            }
+            ValidationErrorKind::InvalidReference { reference } => {
+                map_ser.serialize_entry("reference", reference)?;
+            }
+            ValidationErrorKind::MultipleOf { multiple_of } => {
+                map_ser.serialize_entry("multipleOf", multiple_of)?;
+            }
+            ValidationErrorKind::Not { schema } => {
+                map_ser.serialize_entry("schema", schema)?;
+            }
+            ValidationErrorKind::Pattern { pattern } => {
+                map_ser.serialize_entry("pattern", pattern)?;
+            }
+            ValidationErrorKind::Required { property } => {
+                map_ser.serialize_entry("property", property)?;
+            }
+            ValidationErrorKind::Type { kind } => {
+                let types: Vec<String> = match kind {
+                    TypeKind::Single(type_) => vec![type_.to_string()],
+                    TypeKind::Multiple(types) => types.into_iter().map(|t| t.to_string()).collect(),
+                };
+                map_ser.serialize_entry("types", &types)?;
+            }
+            ValidationErrorKind::UnsupportedRegex { pattern, reason } => {
+                map_ser.serialize_entry("pattern", pattern)?;
+                map_ser.serialize_entry("reason", reason)?;
+            }
+        }
+        map_ser.end()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1046,6 +1569,99 @@ mod tests {
         assert_eq!(err.to_string(), r#"42 is not of types "number", "string""#)
     }
 
+    #[test_case(&json!({"minLength": 5}), &json!("foo"), "minLength", Some("minLength"))]
+    #[test_case(&json!({"type": "string"}), &json!(42), "type", Some("type"))]
+    #[test_case(&json!({"const": 1}), &json!(2), "const", Some("const"))]
+    #[test_case(&json!({"oneOf": [true, true]}), &json!(1), "oneOfMultipleValid", Some("oneOf"))]
+    #[test_case(&json!({"oneOf": [false]}), &json!(1), "oneOfNotValid", Some("oneOf"))]
+    #[test_case(&json!(false), &json!(1), "falseSchema", None)]
+    fn error_code(schema: &Value, instance: &Value, code: &str, keyword: Option<&str>) {
+        let validator = crate::validator_for(schema).expect("Invalid schema");
+        let error = validator
+            .validate(instance)
+            .expect_err("Should fail")
+            .next()
+            .expect("Should have an error");
+        assert_eq!(error.kind.code(), code);
+        assert_eq!(error.kind.keyword(), keyword);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test_case(
+        &json!({"properties": {"name": {"minLength": 5}}}),
+        &json!({"name": "foo"}),
+        &json!({
+            "code": "minLength",
+            "keyword": "minLength",
+            "message": r#""foo" is shorter than 5 characters"#,
+            "instancePath": "/name",
+            "schemaPath": "/properties/name/minLength",
+            "instance": "foo",
+            "details": {"limit": 5}
+        })
+    )]
+    #[test_case(
+        &json!({"type": ["string", "null"]}),
+        &json!(42),
+        &json!({
+            "code": "type",
+            "keyword": "type",
+            "message": r#"42 is not of types "null", "string""#,
+            "instancePath": "",
+            "schemaPath": "/type",
+            "instance": 42,
+            "details": {"types": ["null", "string"]}
+        })
+    )]
+    #[test_case(
+        &json!({"propertyNames": {"maxLength": 1}}),
+        &json!({"ab": 1}),
+        &json!({
+            "code": "propertyNames",
+            "keyword": "propertyNames",
+            "message": r#""ab" is longer than 1 character"#,
+            "instancePath": "",
+            "schemaPath": "/propertyNames/maxLength",
+            "instance": {"ab": 1},
+            "details": {
+                "error": {
+                    "code": "maxLength",
+                    "keyword": "maxLength",
+                    "message": r#""ab" is longer than 1 character"#,
+                    "instancePath": "",
+                    "schemaPath": "/propertyNames/maxLength",
+                    "instance": "ab",
+                    "details": {"limit": 1}
+                }
+            }
+        })
+    )]
+    #[test_case(
+        &json!(false),
+        &json!(1),
+        &json!({
+            "code": "falseSchema",
+            "keyword": null,
+            "message": "False schema does not allow 1",
+            "instancePath": "",
+            "schemaPath": "",
+            "instance": 1,
+            "details": {}
+        })
+    )]
+    fn serialize(schema: &Value, instance: &Value, expected: &Value) {
+        let validator = crate::validator_for(schema).expect("Invalid schema");
+        let error = validator
+            .validate(instance)
+            .expect_err("Should fail")
+            .next()
+            .expect("Should have an error");
+        assert_eq!(
+            &serde_json::to_value(&error).expect("Should serialize"),
+            expected
+        );
+    }
+
     #[test_case(true, &json!({"foo": {"bar": 42}}), "/foo/bar")]
     #[test_case(true, &json!({"foo": "a"}), "/foo")]
     #[test_case(false, &json!({"foo": {"bar": 42}}), "/foo/bar")]
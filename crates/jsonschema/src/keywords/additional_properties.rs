@@ -8,10 +8,11 @@
 //! Each valid combination of these keywords has a validator here.
 use crate::{
     compiler,
+    defaults::Defaults,
     error::{error, no_error, ErrorIterator, ValidationError},
     keywords::CompilationResult,
     node::SchemaNode,
-    output::{Annotations, BasicOutput, OutputUnit},
+    output::{Annotations, BasicOutput, OutputUnit, Trace},
     paths::{LazyLocation, Location},
     properties::*,
     validator::{PartialApplication, Validate},
@@ -110,13 +111,18 @@ impl Validate for AdditionalPropertiesValidator {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(item) = instance {
             let mut matched_props = Vec::with_capacity(item.len());
             let mut output = BasicOutput::default();
             for (name, value) in item {
                 let path = location.push(name.as_str());
-                output += self.node.apply_rooted(value, &path);
+                output += self.node.apply_rooted(value, &path, trace);
                 matched_props.push(name.clone());
             }
             let mut result: PartialApplication = output.into();
@@ -255,14 +261,19 @@ impl<M: PropertiesValidatorsMap> Validate for AdditionalPropertiesNotEmptyFalseV
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(item) = instance {
             let mut unexpected = Vec::with_capacity(item.len());
             let mut output = BasicOutput::default();
             for (property, value) in item {
                 if let Some((_name, node)) = self.properties.get_key_validator(property) {
                     let path = location.push(property.as_str());
-                    output += node.apply_rooted(value, &path);
+                    output += node.apply_rooted(value, &path, trace);
                 } else {
                     unexpected.push(property.clone())
                 }
@@ -284,6 +295,12 @@ impl<M: PropertiesValidatorsMap> Validate for AdditionalPropertiesNotEmptyFalseV
             PartialApplication::valid_empty()
         }
     }
+
+    fn collect_defaults(&self, instance: &Value, location: &LazyLocation, defaults: &mut Defaults) {
+        if let Value::Object(object) = instance {
+            self.properties.collect_defaults(object, location, defaults);
+        }
+    }
 }
 
 impl<M: PropertiesValidatorsMap> core::fmt::Display
@@ -374,7 +391,12 @@ impl<M: PropertiesValidatorsMap> Validate for AdditionalPropertiesNotEmptyValida
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(map) = instance {
             let mut matched_propnames = Vec::with_capacity(map.len());
             let mut output = BasicOutput::default();
@@ -383,9 +405,9 @@ impl<M: PropertiesValidatorsMap> Validate for AdditionalPropertiesNotEmptyValida
                 if let Some((_name, property_validators)) =
                     self.properties.get_key_validator(property)
                 {
-                    output += property_validators.apply_rooted(value, &path);
+                    output += property_validators.apply_rooted(value, &path, trace);
                 } else {
-                    output += self.node.apply_rooted(value, &path);
+                    output += self.node.apply_rooted(value, &path, trace);
                     matched_propnames.push(property.clone());
                 }
             }
@@ -398,6 +420,12 @@ impl<M: PropertiesValidatorsMap> Validate for AdditionalPropertiesNotEmptyValida
             PartialApplication::valid_empty()
         }
     }
+
+    fn collect_defaults(&self, instance: &Value, location: &LazyLocation, defaults: &mut Defaults) {
+        if let Value::Object(object) = instance {
+            self.properties.collect_defaults(object, location, defaults);
+        }
+    }
 }
 
 /// # Schema example
@@ -490,7 +518,12 @@ impl Validate for AdditionalPropertiesWithPatternsValidator {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(item) = instance {
             let mut output = BasicOutput::default();
             let mut pattern_matched_propnames = Vec::with_capacity(item.len());
@@ -502,12 +535,12 @@ impl Validate for AdditionalPropertiesWithPatternsValidator {
                     if pattern.is_match(property).unwrap_or(false) {
                         has_match = true;
                         pattern_matched_propnames.push(property.clone());
-                        output += node.apply_rooted(value, &path)
+                        output += node.apply_rooted(value, &path, trace)
                     }
                 }
                 if !has_match {
                     additional_matched_propnames.push(property.clone());
-                    output += self.node.apply_rooted(value, &path)
+                    output += self.node.apply_rooted(value, &path, trace)
                 }
             }
             if !pattern_matched_propnames.is_empty() {
@@ -615,7 +648,12 @@ impl Validate for AdditionalPropertiesWithPatternsFalseValidator {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(item) = instance {
             let mut output = BasicOutput::default();
             let mut unexpected = Vec::with_capacity(item.len());
@@ -627,7 +665,7 @@ impl Validate for AdditionalPropertiesWithPatternsFalseValidator {
                     if pattern.is_match(property).unwrap_or(false) {
                         has_match = true;
                         pattern_matched_props.push(property.clone());
-                        output += node.apply_rooted(value, &path);
+                        output += node.apply_rooted(value, &path, trace);
                     }
                 }
                 if !has_match {
@@ -800,17 +838,22 @@ impl<M: PropertiesValidatorsMap> Validate for AdditionalPropertiesWithPatternsNo
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(item) = instance {
             let mut output = BasicOutput::default();
             let mut additional_matches = Vec::with_capacity(item.len());
             for (property, value) in item {
                 let path = location.push(property.as_str());
                 if let Some((_name, node)) = self.properties.get_key_validator(property) {
-                    output += node.apply_rooted(value, &path);
+                    output += node.apply_rooted(value, &path, trace);
                     for (pattern, node) in &self.patterns {
                         if pattern.is_match(property).unwrap_or(false) {
-                            output += node.apply_rooted(value, &path);
+                            output += node.apply_rooted(value, &path, trace);
                         }
                     }
                 } else {
@@ -818,12 +861,12 @@ impl<M: PropertiesValidatorsMap> Validate for AdditionalPropertiesWithPatternsNo
                     for (pattern, node) in &self.patterns {
                         if pattern.is_match(property).unwrap_or(false) {
                             has_match = true;
-                            output += node.apply_rooted(value, &path);
+                            output += node.apply_rooted(value, &path, trace);
                         }
                     }
                     if !has_match {
                         additional_matches.push(property.clone());
-                        output += self.node.apply_rooted(value, &path);
+                        output += self.node.apply_rooted(value, &path, trace);
                     }
                 }
             }
@@ -834,6 +877,12 @@ impl<M: PropertiesValidatorsMap> Validate for AdditionalPropertiesWithPatternsNo
             PartialApplication::valid_empty()
         }
     }
+
+    fn collect_defaults(&self, instance: &Value, location: &LazyLocation, defaults: &mut Defaults) {
+        if let Value::Object(object) = instance {
+            self.properties.collect_defaults(object, location, defaults);
+        }
+    }
 }
 
 /// # Schema example
@@ -972,7 +1021,12 @@ impl<M: PropertiesValidatorsMap> Validate
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(item) = instance {
             let mut output = BasicOutput::default();
             let mut unexpected = vec![];
@@ -980,10 +1034,10 @@ impl<M: PropertiesValidatorsMap> Validate
             for (property, value) in item {
                 let path = location.push(property.as_str());
                 if let Some((_name, node)) = self.properties.get_key_validator(property) {
-                    output += node.apply_rooted(value, &path);
+                    output += node.apply_rooted(value, &path, trace);
                     for (pattern, node) in &self.patterns {
                         if pattern.is_match(property).unwrap_or(false) {
-                            output += node.apply_rooted(value, &path);
+                            output += node.apply_rooted(value, &path, trace);
                         }
                     }
                 } else {
@@ -991,7 +1045,7 @@ impl<M: PropertiesValidatorsMap> Validate
                     for (pattern, node) in &self.patterns {
                         if pattern.is_match(property).unwrap_or(false) {
                             has_match = true;
-                            output += node.apply_rooted(value, &path);
+                            output += node.apply_rooted(value, &path, trace);
                         }
                     }
                     if !has_match {
@@ -1016,6 +1070,12 @@ impl<M: PropertiesValidatorsMap> Validate
             PartialApplication::valid_empty()
         }
     }
+
+    fn collect_defaults(&self, instance: &Value, location: &LazyLocation, defaults: &mut Defaults) {
+        if let Value::Object(object) = instance {
+            self.properties.collect_defaults(object, location, defaults);
+        }
+    }
 }
 
 impl<M: PropertiesValidatorsMap> core::fmt::Display
@@ -1035,44 +1095,45 @@ pub(crate) fn compile<'a>(
     if let Some(patterns) = parent.get("patternProperties") {
         if let Value::Object(obj) = patterns {
             // Compile all patterns & their validators to avoid doing work in the `patternProperties` validator
-            if let Ok(compiled_patterns) = compile_patterns(ctx, obj) {
-                match schema {
-                    Value::Bool(true) => None, // "additionalProperties" are "true" by default
-                    Value::Bool(false) => {
-                        if let Some(properties) = properties {
-                            compile_dynamic_prop_map_validator!(
-                                AdditionalPropertiesWithPatternsNotEmptyFalseValidator,
-                                properties,
-                                ctx,
-                                compiled_patterns,
-                            )
-                        } else {
-                            Some(AdditionalPropertiesWithPatternsFalseValidator::compile(
-                                ctx,
-                                compiled_patterns,
-                            ))
+            match compile_patterns(ctx, obj) {
+                Ok(compiled_patterns) => {
+                    match schema {
+                        Value::Bool(true) => None, // "additionalProperties" are "true" by default
+                        Value::Bool(false) => {
+                            if let Some(properties) = properties {
+                                compile_dynamic_prop_map_validator!(
+                                    AdditionalPropertiesWithPatternsNotEmptyFalseValidator,
+                                    properties,
+                                    ctx,
+                                    compiled_patterns,
+                                )
+                            } else {
+                                Some(AdditionalPropertiesWithPatternsFalseValidator::compile(
+                                    ctx,
+                                    compiled_patterns,
+                                ))
+                            }
                         }
-                    }
-                    _ => {
-                        if let Some(properties) = properties {
-                            compile_dynamic_prop_map_validator!(
-                                AdditionalPropertiesWithPatternsNotEmptyValidator,
-                                properties,
-                                ctx,
-                                schema,
-                                compiled_patterns,
-                            )
-                        } else {
-                            Some(AdditionalPropertiesWithPatternsValidator::compile(
-                                ctx,
-                                schema,
-                                compiled_patterns,
-                            ))
+                        _ => {
+                            if let Some(properties) = properties {
+                                compile_dynamic_prop_map_validator!(
+                                    AdditionalPropertiesWithPatternsNotEmptyValidator,
+                                    properties,
+                                    ctx,
+                                    schema,
+                                    compiled_patterns,
+                                )
+                            } else {
+                                Some(AdditionalPropertiesWithPatternsValidator::compile(
+                                    ctx,
+                                    schema,
+                                    compiled_patterns,
+                                ))
+                            }
                         }
                     }
                 }
-            } else {
-                Some(Err(ValidationError::null_schema()))
+                Err(error) => Some(Err(error)),
             }
         } else {
             Some(Err(ValidationError::null_schema()))
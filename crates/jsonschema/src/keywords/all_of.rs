@@ -1,10 +1,12 @@
 use crate::{
     compiler,
+    defaults::Defaults,
     error::{ErrorIterator, ValidationError},
     node::SchemaNode,
-    output::BasicOutput,
+    output::{BasicOutput, Trace},
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
+    stream::Stream,
     validator::{PartialApplication, Validate},
 };
 use serde_json::{Map, Value};
@@ -47,13 +49,28 @@ impl Validate for AllOfValidator {
         Box::new(errors.into_iter())
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         self.schemas
             .iter()
-            .map(move |node| node.apply_rooted(instance, location))
+            .map(move |node| node.apply_rooted(instance, location, trace))
             .sum::<BasicOutput<'_>>()
             .into()
     }
+
+    fn collect_defaults(&self, instance: &Value, location: &LazyLocation, defaults: &mut Defaults) {
+        for node in &self.schemas {
+            node.collect_defaults(instance, location, defaults);
+        }
+    }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::InPlace(&self.schemas)
+    }
 }
 
 pub(crate) struct SingleValueAllOfValidator {
@@ -79,8 +96,21 @@ impl Validate for SingleValueAllOfValidator {
         self.node.validate(instance, location)
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
-        self.node.apply_rooted(instance, location).into()
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
+        self.node.apply_rooted(instance, location, trace).into()
+    }
+
+    fn collect_defaults(&self, instance: &Value, location: &LazyLocation, defaults: &mut Defaults) {
+        self.node.collect_defaults(instance, location, defaults);
+    }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::InPlace(std::slice::from_ref(&self.node))
     }
 }
 
@@ -1,7 +1,9 @@
 use crate::{
     compiler,
+    defaults::Defaults,
     error::{error, no_error, ErrorIterator, ValidationError},
     node::SchemaNode,
+    output::Trace,
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
     validator::{PartialApplication, Validate},
@@ -58,11 +60,16 @@ impl Validate for AnyOfValidator {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         let mut successes = Vec::new();
         let mut failures = Vec::new();
         for node in &self.schemas {
-            let result = node.apply_rooted(instance, location);
+            let result = node.apply_rooted(instance, location, trace);
             if result.is_valid() {
                 successes.push(result);
             } else {
@@ -75,6 +82,16 @@ impl Validate for AnyOfValidator {
             successes.into_iter().collect()
         }
     }
+
+    fn collect_defaults(&self, instance: &Value, location: &LazyLocation, defaults: &mut Defaults) {
+        if defaults.follows_conditionals() {
+            for node in &self.schemas {
+                if node.is_valid(instance) {
+                    node.collect_defaults(instance, location, defaults);
+                }
+            }
+        }
+    }
 }
 
 #[inline]
@@ -3,6 +3,7 @@ use crate::{
     error::{error, no_error, ErrorIterator, ValidationError},
     keywords::CompilationResult,
     node::SchemaNode,
+    output::Trace,
     paths::LazyLocation,
     validator::{PartialApplication, Validate},
     Draft,
@@ -49,13 +50,18 @@ impl Validate for ContainsValidator {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         if let Value::Array(items) = instance {
             let mut results = Vec::with_capacity(items.len());
             let mut indices = Vec::new();
             for (idx, item) in items.iter().enumerate() {
                 let path = location.push(idx);
-                let result = self.node.apply_rooted(item, &path);
+                let result = self.node.apply_rooted(item, &path, trace);
                 if result.is_valid() {
                     indices.push(idx);
                     results.push(result);
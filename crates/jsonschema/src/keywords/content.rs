@@ -1,12 +1,14 @@
 //! Validators for `contentMediaType` and `contentEncoding` keywords.
 use crate::{
     compiler,
-    content_encoding::{ContentEncodingCheckType, ContentEncodingConverterType},
-    content_media_type::ContentMediaTypeCheckType,
+    content_encoding::ContentEncodingHandler,
+    content_media_type::{self, ContentMediaTypeHandler},
     error::{error, no_error, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    node::SchemaNode,
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
+    stream::Stream,
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -14,7 +16,7 @@ use serde_json::{Map, Value};
 /// Validator for `contentMediaType` keyword.
 pub(crate) struct ContentMediaTypeValidator {
     media_type: String,
-    func: ContentMediaTypeCheckType,
+    check: ContentMediaTypeHandler,
     location: Location,
 }
 
@@ -22,22 +24,22 @@ impl ContentMediaTypeValidator {
     #[inline]
     pub(crate) fn compile(
         media_type: &str,
-        func: ContentMediaTypeCheckType,
+        check: ContentMediaTypeHandler,
         location: Location,
     ) -> CompilationResult {
         Ok(Box::new(ContentMediaTypeValidator {
             media_type: media_type.to_string(),
-            func,
+            check,
             location,
         }))
     }
 }
 
-/// Validator delegates validation to the stored function.
+/// Validator delegates validation to the stored check.
 impl Validate for ContentMediaTypeValidator {
     fn is_valid(&self, instance: &Value) -> bool {
         if let Value::String(item) = instance {
-            (self.func)(item)
+            self.check.check(item).is_ok()
         } else {
             true
         }
@@ -45,26 +47,30 @@ impl Validate for ContentMediaTypeValidator {
 
     fn validate<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         if let Value::String(item) = instance {
-            if (self.func)(item) {
-                no_error()
-            } else {
-                error(ValidationError::content_media_type(
+            match self.check.check(item) {
+                Ok(()) => no_error(),
+                Err(err) => error(ValidationError::content_media_type(
                     self.location.clone(),
                     location.into(),
                     instance,
                     &self.media_type,
-                ))
+                    content_media_type::reason(err.as_ref()),
+                )),
             }
         } else {
             no_error()
         }
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Scalar
+    }
 }
 
 /// Validator for `contentEncoding` keyword.
 pub(crate) struct ContentEncodingValidator {
     encoding: String,
-    func: ContentEncodingCheckType,
+    decoder: ContentEncodingHandler,
     location: Location,
 }
 
@@ -72,12 +78,12 @@ impl ContentEncodingValidator {
     #[inline]
     pub(crate) fn compile(
         encoding: &str,
-        func: ContentEncodingCheckType,
+        decoder: ContentEncodingHandler,
         location: Location,
     ) -> CompilationResult {
         Ok(Box::new(ContentEncodingValidator {
             encoding: encoding.to_string(),
-            func,
+            decoder,
             location,
         }))
     }
@@ -86,7 +92,7 @@ impl ContentEncodingValidator {
 impl Validate for ContentEncodingValidator {
     fn is_valid(&self, instance: &Value) -> bool {
         if let Value::String(item) = instance {
-            (self.func)(item)
+            self.decoder.decode(item).is_ok()
         } else {
             true
         }
@@ -94,28 +100,32 @@ impl Validate for ContentEncodingValidator {
 
     fn validate<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         if let Value::String(item) = instance {
-            if (self.func)(item) {
-                no_error()
-            } else {
-                error(ValidationError::content_encoding(
+            match self.decoder.decode(item) {
+                Ok(_) => no_error(),
+                Err(err) => error(ValidationError::content_encoding(
                     self.location.clone(),
                     location.into(),
                     instance,
                     &self.encoding,
-                ))
+                    content_media_type::reason(err.as_ref()),
+                )),
             }
         } else {
             no_error()
         }
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Scalar
+    }
 }
 
 /// Combined validator for both `contentEncoding` and `contentMediaType` keywords.
 pub(crate) struct ContentMediaTypeAndEncodingValidator {
     media_type: String,
     encoding: String,
-    func: ContentMediaTypeCheckType,
-    converter: ContentEncodingConverterType,
+    check: ContentMediaTypeHandler,
+    decoder: ContentEncodingHandler,
     location: Location,
 }
 
@@ -124,27 +134,59 @@ impl ContentMediaTypeAndEncodingValidator {
     pub(crate) fn compile<'a>(
         media_type: &'a str,
         encoding: &'a str,
-        func: ContentMediaTypeCheckType,
-        converter: ContentEncodingConverterType,
+        check: ContentMediaTypeHandler,
+        decoder: ContentEncodingHandler,
         location: Location,
     ) -> CompilationResult<'a> {
         Ok(Box::new(ContentMediaTypeAndEncodingValidator {
             media_type: media_type.to_string(),
             encoding: encoding.to_string(),
-            func,
-            converter,
+            check,
+            decoder,
             location,
         }))
     }
+
+    fn check<'i>(
+        &self,
+        instance: &'i Value,
+        item: &str,
+        location: &LazyLocation,
+    ) -> Result<(), ValidationError<'i>> {
+        let decoded = self.decoder.decode(item).map_err(|err| {
+            ValidationError::content_encoding(
+                self.location.join("contentEncoding"),
+                location.into(),
+                instance,
+                &self.encoding,
+                content_media_type::reason(err.as_ref()),
+            )
+        })?;
+        let media_type_error = |reason| {
+            ValidationError::content_media_type(
+                self.location.join("contentMediaType"),
+                location.into(),
+                instance,
+                &self.media_type,
+                reason,
+            )
+        };
+        let content =
+            std::str::from_utf8(&decoded).map_err(|err| media_type_error(Some(err.to_string())))?;
+        self.check
+            .check(content)
+            .map_err(|err| media_type_error(content_media_type::reason(err.as_ref())))
+    }
 }
 
 /// Decode the input value & check media type
 impl Validate for ContentMediaTypeAndEncodingValidator {
     fn is_valid(&self, instance: &Value) -> bool {
         if let Value::String(item) = instance {
-            match (self.converter)(item) {
-                Ok(None) | Err(_) => false,
-                Ok(Some(converted)) => (self.func)(&converted),
+            match self.decoder.decode(item) {
+                Ok(decoded) => std::str::from_utf8(&decoded)
+                    .is_ok_and(|content| self.check.check(content).is_ok()),
+                Err(_) => false,
             }
         } else {
             true
@@ -153,31 +195,113 @@ impl Validate for ContentMediaTypeAndEncodingValidator {
 
     fn validate<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         if let Value::String(item) = instance {
-            match (self.converter)(item) {
-                Ok(None) => error(ValidationError::content_encoding(
+            match self.check(instance, item, location) {
+                Ok(()) => no_error(),
+                Err(err) => error(err),
+            }
+        } else {
+            no_error()
+        }
+    }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Scalar
+    }
+}
+
+/// Validator for `contentSchema` keyword.
+///
+/// Decodes and parses the input string, then validates the result against the subschema.
+pub(crate) struct ContentSchemaValidator {
+    media_type: String,
+    encoding: Option<(String, ContentEncodingHandler)>,
+    node: SchemaNode,
+    location: Location,
+}
+
+impl ContentSchemaValidator {
+    #[inline]
+    pub(crate) fn compile<'a>(
+        ctx: &compiler::Context,
+        media_type: &str,
+        encoding: Option<(String, ContentEncodingHandler)>,
+        schema: &'a Value,
+    ) -> CompilationResult<'a> {
+        let location = ctx.location().clone();
+        let ctx = ctx.new_at_location("contentSchema");
+        Ok(Box::new(ContentSchemaValidator {
+            media_type: media_type.to_string(),
+            encoding,
+            node: compiler::compile(&ctx, ctx.as_resource_ref(schema))?,
+            location,
+        }))
+    }
+
+    fn decode<'i>(
+        &self,
+        instance: &'i Value,
+        item: &str,
+        location: &LazyLocation,
+    ) -> Result<Value, ValidationError<'i>> {
+        let content = if let Some((encoding, decoder)) = &self.encoding {
+            decoder.decode(item).map_err(|err| {
+                ValidationError::content_encoding(
                     self.location.join("contentEncoding"),
                     location.into(),
                     instance,
-                    &self.encoding,
-                )),
-                Ok(Some(converted)) => {
-                    if (self.func)(&converted) {
-                        no_error()
-                    } else {
-                        error(ValidationError::content_media_type(
-                            self.location.join("contentMediaType"),
-                            location.into(),
-                            instance,
-                            &self.media_type,
-                        ))
-                    }
+                    encoding,
+                    content_media_type::reason(err.as_ref()),
+                )
+            })?
+        } else {
+            item.as_bytes().to_vec()
+        };
+        serde_json::from_slice(&content).map_err(|err| {
+            ValidationError::content_media_type(
+                self.location.join("contentMediaType"),
+                location.into(),
+                instance,
+                &self.media_type,
+                Some(err.to_string()),
+            )
+        })
+    }
+}
+
+impl Validate for ContentSchemaValidator {
+    fn is_valid(&self, instance: &Value) -> bool {
+        if let Value::String(item) = instance {
+            match self.decode(instance, item, &LazyLocation::new()) {
+                Ok(content) => self.node.is_valid(&content),
+                Err(_) => false,
+            }
+        } else {
+            true
+        }
+    }
+
+    fn validate<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
+        if let Value::String(item) = instance {
+            match self.decode(instance, item, location) {
+                Ok(content) => {
+                    // Errors point into the decoded content, which lives only in this function
+                    let errors: Vec<_> = self
+                        .node
+                        .validate(&content, location)
+                        .map(ValidationError::into_owned)
+                        .collect();
+                    Box::new(errors.into_iter())
                 }
-                Err(e) => error(e),
+                Err(err) => error(err),
             }
         } else {
             no_error()
         }
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Scalar
+    }
 }
 
 #[inline]
@@ -188,22 +312,16 @@ pub(crate) fn compile_media_type<'a>(
 ) -> Option<CompilationResult<'a>> {
     match subschema {
         Value::String(media_type) => {
-            let func = match ctx.get_content_media_type_check(media_type.as_str()) {
-                Some(f) => f,
-                None => return None,
-            };
+            let check = ctx.get_content_media_type_check(media_type.as_str())?;
             if let Some(content_encoding) = schema.get("contentEncoding") {
                 match content_encoding {
                     Value::String(content_encoding) => {
-                        let converter = match ctx.get_content_encoding_convert(content_encoding) {
-                            Some(f) => f,
-                            None => return None,
-                        };
+                        let decoder = ctx.get_content_encoding(content_encoding)?;
                         Some(ContentMediaTypeAndEncodingValidator::compile(
                             media_type,
                             content_encoding,
-                            func,
-                            converter,
+                            check,
+                            decoder,
                             ctx.location().clone(),
                         ))
                     }
@@ -217,7 +335,7 @@ pub(crate) fn compile_media_type<'a>(
             } else {
                 Some(ContentMediaTypeValidator::compile(
                     media_type,
-                    func,
+                    check,
                     ctx.location().join("contentMediaType"),
                 ))
             }
@@ -244,13 +362,10 @@ pub(crate) fn compile_content_encoding<'a>(
     }
     match subschema {
         Value::String(content_encoding) => {
-            let func = match ctx.get_content_encoding_check(content_encoding) {
-                Some(f) => f,
-                None => return None,
-            };
+            let decoder = ctx.get_content_encoding(content_encoding)?;
             Some(ContentEncodingValidator::compile(
                 content_encoding,
-                func,
+                decoder,
                 ctx.location().join("contentEncoding"),
             ))
         }
@@ -263,6 +378,30 @@ pub(crate) fn compile_content_encoding<'a>(
     }
 }
 
+#[inline]
+pub(crate) fn compile_content_schema<'a>(
+    ctx: &compiler::Context,
+    schema: &'a Map<String, Value>,
+    subschema: &'a Value,
+) -> Option<CompilationResult<'a>> {
+    // Without a known media type, the content can't be parsed and `contentSchema` is ignored
+    let media_type = schema
+        .get("contentMediaType")
+        .and_then(Value::as_str)
+        .filter(|media_type| content_media_type::is_json_media_type(media_type))?;
+    let encoding = match schema.get("contentEncoding") {
+        Some(Value::String(encoding)) => {
+            let decoder = ctx.get_content_encoding(encoding)?;
+            Some((encoding.clone(), decoder))
+        }
+        Some(_) => return None,
+        None => None,
+    };
+    Some(ContentSchemaValidator::compile(
+        ctx, media_type, encoding, subschema,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use referencing::Draft;
@@ -285,4 +424,238 @@ mod tests {
             .expect("Should be non empty");
         assert_eq!(error.schema_path.as_str(), expected);
     }
+
+    fn content_schema(encoding: Option<&str>, media_type: &str) -> Value {
+        let mut schema = json!({
+            "properties": {
+                "payload": {
+                    "contentMediaType": media_type,
+                    "contentSchema": {
+                        "properties": {
+                            "id": {"type": "integer"},
+                            "items": {"items": {"type": "integer"}}
+                        },
+                        "required": ["id"]
+                    }
+                }
+            }
+        });
+        if let Some(encoding) = encoding {
+            schema["properties"]["payload"]["contentEncoding"] = json!(encoding);
+        }
+        schema
+    }
+
+    // {"id": 1}
+    #[test_case(Some("base64"), "application/json", "eyJpZCI6IDF9", true)]
+    #[test_case(None, "application/json", r#"{"id": 1}"#, true)]
+    #[test_case(None, "application/json; charset=utf-8", r#"{"id": 1}"#, true)]
+    #[test_case(None, "application/vnd.api+json", r#"{"id": 1}"#, true)]
+    // {}
+    #[test_case(Some("base64"), "application/json", "e30=", false)]
+    #[test_case(None, "application/json", "{}", false)]
+    #[test_case(None, "application/vnd.api+json", "{}", false)]
+    // Content of other media types is not parsed
+    #[test_case(None, "text/plain", "{}", true)]
+    #[test_case(Some("base64url"), "application/json", "eyJpZCI6IDF9", true)]
+    #[test_case(Some("base16"), "application/json", "7B7D", false)]
+    // Unknown encodings can't be decoded
+    #[test_case(Some("quoted-printable"), "application/json", "{}", true)]
+    fn content_schema_is_valid(
+        encoding: Option<&str>,
+        media_type: &str,
+        payload: &str,
+        expected: bool,
+    ) {
+        let schema = content_schema(encoding, media_type);
+        let validator = crate::options()
+            .should_validate_content_schema(true)
+            .build(&schema)
+            .expect("Invalid schema");
+        assert_eq!(validator.is_valid(&json!({"payload": payload})), expected);
+    }
+
+    #[test_case(Draft::Draft202012, true)]
+    #[test_case(Draft::Draft201909, true)]
+    #[test_case(Draft::Draft7, false)]
+    fn content_schema_is_opt_in(draft: Draft, enabled: bool) {
+        let schema = content_schema(None, "application/json");
+        let instance = json!({"payload": "{}"});
+        let validator = crate::options()
+            .with_draft(draft)
+            .build(&schema)
+            .expect("Invalid schema");
+        assert!(validator.is_valid(&instance));
+        let validator = crate::options()
+            .with_draft(draft)
+            .should_validate_content_schema(true)
+            .build(&schema)
+            .expect("Invalid schema");
+        assert_eq!(!validator.is_valid(&instance), enabled);
+    }
+
+    // {"items": [1, "a"]}
+    #[test_case(
+        "eyJpdGVtcyI6IFsxLCAiYSJdfQ==",
+        &[
+            ("/payload", "/properties/payload/contentSchema/required"),
+            ("/payload/items/1", "/properties/payload/contentSchema/properties/items/items/type"),
+        ]
+    )]
+    // {"id": "x"}
+    #[test_case(
+        "eyJpZCI6ICJ4In0=",
+        &[("/payload/id", "/properties/payload/contentSchema/properties/id/type")]
+    )]
+    #[test_case("bm90IGpzb24=", &[("/payload", "/properties/payload/contentMediaType")])]
+    #[test_case("not base64", &[("/payload", "/properties/payload/contentEncoding")])]
+    fn content_schema_locations(payload: &str, expected: &[(&str, &str)]) {
+        let schema = content_schema(Some("base64"), "application/json");
+        let validator = crate::options()
+            .should_validate_content_schema(true)
+            .build(&schema)
+            .expect("Invalid schema");
+        let instance = json!({"payload": payload});
+        let mut locations: Vec<_> = validator
+            .validate(&instance)
+            .expect_err("Should fail")
+            .map(|error| {
+                (
+                    error.instance_path.to_string(),
+                    error.schema_path.to_string(),
+                )
+            })
+            .collect();
+        locations.sort();
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|(instance, schema)| (instance.to_string(), schema.to_string()))
+            .collect();
+        assert_eq!(locations, expected);
+    }
+
+    // {"a": "b?"}
+    #[test_case("base64url", "eyJhIjogImI_In0", true)]
+    #[test_case("base64url", "eyJhIjogImI_In0=", true)]
+    #[test_case("base64url", "eyJhIjogImI/In0=", false)]
+    #[test_case("base32", "PMRGCIR2EARGEPZCPU======", true)]
+    #[test_case("base32", "pmrgcir2earGEPZCPU======", false)]
+    #[test_case("base32", "PMRGCIR2EARGEPZCPU", false)]
+    #[test_case("base16", "7B2261223A2022623F227D", true)]
+    #[test_case("base16", "7b2261223a2022623f227d", true)]
+    #[test_case("base16", "7B2261223A2022623F227", false)]
+    #[test_case("base16", "7G", false)]
+    fn builtin_encodings(encoding: &str, instance: &str, expected: bool) {
+        let schema = json!({"contentEncoding": encoding, "contentMediaType": "application/json"});
+        let validator = crate::options()
+            .with_draft(Draft::Draft7)
+            .build(&schema)
+            .expect("Invalid schema");
+        assert_eq!(validator.is_valid(&json!(instance)), expected);
+    }
+
+    #[test_case("application/x-www-form-urlencoded", "a=1&b=%20x+y", true)]
+    #[test_case("application/x-www-form-urlencoded", "", true)]
+    #[test_case("application/x-www-form-urlencoded", "a=%zz", false)]
+    #[test_case("application/x-www-form-urlencoded", "a=%2", false)]
+    #[test_case("application/x-www-form-urlencoded", "a=%FF", false)]
+    #[test_case("application/x-www-form-urlencoded", "a b", false)]
+    #[cfg_attr(feature = "csv", test_case("text/csv", "a,b\n1,2\n", true))]
+    #[cfg_attr(feature = "csv", test_case("text/csv", "a,\"b\"\"\"\n1,2", true))]
+    #[cfg_attr(feature = "csv", test_case("text/csv", "a,b\n1\n", false))]
+    fn builtin_media_types(media_type: &str, instance: &str, expected: bool) {
+        let schema = json!({"contentMediaType": media_type});
+        let validator = crate::options()
+            .with_draft(Draft::Draft7)
+            .build(&schema)
+            .expect("Invalid schema");
+        assert_eq!(validator.is_valid(&json!(instance)), expected);
+    }
+
+    struct Tagged {
+        tag: String,
+    }
+
+    impl crate::ContentMediaType for Tagged {
+        fn check(&self, content: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            if content.starts_with(&self.tag) {
+                Ok(())
+            } else {
+                Err(format!("missing `{}` tag", self.tag).into())
+            }
+        }
+    }
+
+    impl crate::ContentEncoding for Tagged {
+        fn decode(
+            &self,
+            content: &str,
+        ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+            content
+                .strip_prefix(&self.tag)
+                .map(|content| content.as_bytes().to_vec())
+                .ok_or_else(|| format!("missing `{}` tag", self.tag).into())
+        }
+    }
+
+    fn tagged(tag: &str) -> Tagged {
+        Tagged {
+            tag: tag.to_string(),
+        }
+    }
+
+    #[test_case(
+        &json!({"contentMediaType": "text/x-tagged"}),
+        "untagged",
+        r#""untagged" is not compliant with "text/x-tagged" media type: missing `tag:` tag"#
+    )]
+    #[test_case(
+        &json!({"contentEncoding": "x-tagged"}),
+        "untagged",
+        r#""untagged" is not compliant with "x-tagged" content encoding: missing `enc:` tag"#
+    )]
+    #[test_case(
+        &json!({"contentMediaType": "text/x-tagged", "contentEncoding": "x-tagged"}),
+        "enc:untagged",
+        r#""enc:untagged" is not compliant with "text/x-tagged" media type: missing `tag:` tag"#
+    )]
+    #[test_case(
+        &json!({"contentMediaType": "text/x-plain", "contentEncoding": "base64"}),
+        "/w==",
+        r#""/w==" is not compliant with "text/x-plain" media type: invalid utf-8 sequence of 1 bytes from index 0"#
+    )]
+    #[test_case(
+        &json!({"contentMediaType": "text/x-plain"}),
+        "plain",
+        r#""plain" is not compliant with "text/x-plain" media type"#
+    )]
+    fn custom_handlers(schema: &Value, instance: &str, expected: &str) {
+        let name = String::from("text/x-tagged");
+        let validator = crate::options()
+            .with_draft(Draft::Draft7)
+            .with_content_media_type(name, tagged("tag:"))
+            .with_content_encoding("x-tagged", tagged("enc:"))
+            .with_content_media_type("text/x-plain", |content: &str| content.is_empty())
+            .build(schema)
+            .expect("Invalid schema");
+        let instance = json!(instance);
+        let error = validator
+            .validate(&instance)
+            .expect_err("Should fail")
+            .next()
+            .expect("Should be non empty");
+        assert_eq!(error.to_string(), expected);
+        assert!(!validator.is_valid(&instance));
+    }
+
+    #[test]
+    fn without_support() {
+        let schema = json!({"contentEncoding": "base32"});
+        let validator = crate::options()
+            .with_draft(Draft::Draft7)
+            .without_content_encoding_support(String::from("base32"))
+            .build(&schema)
+            .expect("Invalid schema");
+        assert!(validator.is_valid(&json!("not base32")));
+    }
 }
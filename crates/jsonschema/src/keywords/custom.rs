@@ -1,17 +1,23 @@
 use crate::{
+    compiler,
+    output::{ErrorDescription, Trace},
     paths::{LazyLocation, Location},
-    validator::Validate,
+    validator::{PartialApplication, Validate},
     ErrorIterator, ValidationError,
 };
+use ahash::AHashSet;
 use serde_json::{Map, Value};
-use std::fmt::{Display, Formatter};
+use std::{
+    fmt::{Display, Formatter},
+    sync::Arc,
+};
 
 pub(crate) struct CustomKeyword {
-    inner: Box<dyn Keyword>,
+    inner: Arc<dyn Keyword>,
 }
 
 impl CustomKeyword {
-    pub(crate) fn new(inner: Box<dyn Keyword>) -> Self {
+    pub(crate) fn new(inner: Arc<dyn Keyword>) -> Self {
         Self { inner }
     }
 }
@@ -30,6 +36,132 @@ impl Validate for CustomKeyword {
     fn is_valid(&self, instance: &Value) -> bool {
         self.inner.is_valid(instance)
     }
+
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        _trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
+        let errors: Vec<ErrorDescription> = self
+            .validate(instance, location)
+            .map(ErrorDescription::from)
+            .collect();
+        if errors.is_empty() {
+            let mut application = PartialApplication::valid_empty();
+            if let Some(value) = self
+                .inner
+                .apply(instance)
+                .and_then(|annotations| annotations.value)
+            {
+                application.annotate(value.into());
+            }
+            application
+        } else {
+            PartialApplication::invalid_empty(errors)
+        }
+    }
+}
+
+/// Annotations produced by a custom keyword, see [`Keyword::apply`].
+///
+/// Besides the annotation value reported in the "basic", "detailed" and "verbose" output formats,
+/// a keyword may mark object properties or array items as evaluated. Like the ones matched by
+/// `properties` or `items`, they are then skipped by `unevaluatedProperties` and
+/// `unevaluatedItems`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeywordAnnotations {
+    value: Option<Value>,
+    evaluated_properties: Vec<String>,
+    evaluated_items: Vec<usize>,
+}
+
+impl KeywordAnnotations {
+    /// Create annotations with the given annotation value.
+    #[must_use]
+    pub fn new(value: Value) -> Self {
+        Self {
+            value: Some(value),
+            ..Self::default()
+        }
+    }
+    /// Mark the given object properties as evaluated.
+    #[must_use]
+    pub fn with_evaluated_properties<I, S>(mut self, properties: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.evaluated_properties
+            .extend(properties.into_iter().map(Into::into));
+        self
+    }
+    /// Mark array items at the given indexes as evaluated.
+    #[must_use]
+    pub fn with_evaluated_items(mut self, indexes: impl IntoIterator<Item = usize>) -> Self {
+        self.evaluated_items.extend(indexes);
+        self
+    }
+    /// The annotation value.
+    #[must_use]
+    pub fn value(&self) -> Option<&Value> {
+        self.value.as_ref()
+    }
+}
+
+/// Custom keywords present in a schema, used by `unevaluatedProperties` and `unevaluatedItems`
+/// to find what they have evaluated.
+pub(crate) struct CustomKeywords(Vec<Arc<dyn Keyword>>);
+
+impl CustomKeywords {
+    pub(crate) fn new<'a>(
+        ctx: &compiler::Context,
+        parent: &'a Map<String, Value>,
+    ) -> Result<Self, ValidationError<'a>> {
+        let mut keywords = Vec::new();
+        for (keyword, value) in parent {
+            if let Some(factory) = ctx.get_keyword_factory(keyword) {
+                keywords.push(ctx.init_custom_keyword(&**factory, parent, keyword, value)?);
+            }
+        }
+        Ok(Self(keywords))
+    }
+
+    fn annotations<'k>(
+        &'k self,
+        instance: &'k Value,
+    ) -> impl Iterator<Item = KeywordAnnotations> + 'k {
+        self.0
+            .iter()
+            .filter(move |keyword| keyword.is_valid(instance))
+            .filter_map(move |keyword| keyword.apply(instance))
+    }
+
+    pub(crate) fn mark_evaluated_properties<'i>(
+        &self,
+        instance: &'i Value,
+        properties: &mut AHashSet<&'i String>,
+    ) {
+        if let Value::Object(object) = instance {
+            for annotations in self.annotations(instance) {
+                for property in &annotations.evaluated_properties {
+                    if let Some((property, _)) = object.get_key_value(property) {
+                        properties.insert(property);
+                    }
+                }
+            }
+        }
+    }
+
+    pub(crate) fn mark_evaluated_indexes(&self, instance: &Value, indexes: &mut [bool]) {
+        for annotations in self.annotations(instance) {
+            for idx in annotations.evaluated_items {
+                if let Some(is_evaluated) = indexes.get_mut(idx) {
+                    *is_evaluated = true;
+                }
+            }
+        }
+    }
 }
 
 /// Trait that allows implementing custom validation for keywords.
@@ -45,6 +177,17 @@ pub trait Keyword: Send + Sync {
     ///
     /// Could be potentilly faster than [`Keyword::validate`] method.
     fn is_valid(&self, instance: &Value) -> bool;
+    /// Collect annotations for an instance that is valid according to this keyword.
+    ///
+    /// The annotation value is reported in the output formats, see [`crate::Validator::apply`],
+    /// while properties and items marked as evaluated are respected by `unevaluatedProperties`
+    /// and `unevaluatedItems`. Errors are still reported via [`Keyword::validate`].
+    ///
+    /// By default, no annotations are produced.
+    fn apply(&self, instance: &Value) -> Option<KeywordAnnotations> {
+        let _ = instance;
+        None
+    }
 }
 
 pub(crate) trait KeywordFactory: Send + Sync {
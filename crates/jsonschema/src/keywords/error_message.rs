@@ -0,0 +1,545 @@
+//! The `errorMessage` keyword replaces errors produced by a schema with custom messages.
+//!
+//! It is not a part of the JSON Schema specification and is enabled via
+//! [`crate::ValidationOptions::should_use_error_messages`].
+use crate::{
+    compiler,
+    error::{ValidationError, ValidationErrorKind},
+    output::{ErrorDescription, OutputUnit},
+    paths::{LazyLocation, Location},
+    primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
+};
+use ahash::AHashMap;
+use referencing::{uri, Uri};
+use serde_json::{Map, Value};
+use std::{borrow::Cow, collections::VecDeque};
+
+/// Messages declared by an `errorMessage` keyword.
+#[derive(Debug)]
+pub(crate) struct ErrorMessages {
+    location: Location,
+    keywords: AHashMap<String, Template>,
+    required: AHashMap<String, Template>,
+    properties: AHashMap<String, Template>,
+    fallback: Option<Template>,
+}
+
+/// The group of errors replaced by a single message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Group<'m> {
+    Property(&'m str),
+    Required(&'m str),
+    Keyword(&'m str),
+    Fallback,
+}
+
+enum Entry<'m, E> {
+    Original(E),
+    /// A group with its template and first error.
+    Replaced(Group<'m>, &'m Template, E),
+}
+
+impl ErrorMessages {
+    pub(crate) fn compile<'a>(
+        ctx: &compiler::Context,
+        value: &'a Value,
+    ) -> Result<ErrorMessages, ValidationError<'a>> {
+        let location = ctx.location().join("errorMessage");
+        let mut messages = ErrorMessages {
+            location,
+            keywords: AHashMap::new(),
+            required: AHashMap::new(),
+            properties: AHashMap::new(),
+            fallback: None,
+        };
+        match value {
+            Value::String(message) => messages.fallback = Some(Template::new(message)),
+            Value::Object(map) => {
+                for (keyword, value) in map {
+                    let location = messages.location.join(keyword);
+                    match (keyword.as_str(), value) {
+                        ("_", value) => {
+                            messages.fallback = Some(Template::compile(value, &location)?);
+                        }
+                        ("required" | "properties", Value::Object(map)) => {
+                            let templates = compile_map(map, &location)?;
+                            if keyword == "required" {
+                                messages.required = templates;
+                            } else {
+                                messages.properties = templates;
+                            }
+                        }
+                        (keyword, value) => {
+                            messages
+                                .keywords
+                                .insert(keyword.to_string(), Template::compile(value, &location)?);
+                        }
+                    }
+                }
+            }
+            _ => {
+                return Err(ValidationError::multiple_type_error(
+                    Location::new(),
+                    messages.location,
+                    value,
+                    PrimitiveTypesBitMap::new()
+                        .add_type(PrimitiveType::String)
+                        .add_type(PrimitiveType::Object),
+                ))
+            }
+        }
+        Ok(messages)
+    }
+
+    /// Replace errors produced by the schema at `node_location` for `instance`.
+    pub(crate) fn replace_errors<'i>(
+        &self,
+        errors: Vec<ValidationError<'i>>,
+        node_location: &Location,
+        instance: &'i Value,
+        location: &LazyLocation,
+    ) -> Vec<ValidationError<'i>> {
+        let instance_path = Location::from(location);
+        let entries = self.group(errors, |error| {
+            let required = match &error.kind {
+                ValidationErrorKind::Required {
+                    property: Value::String(property),
+                } => Some(property.clone()),
+                _ => None,
+            };
+            (
+                first_segment(error.schema_path.as_str(), node_location.as_str()),
+                first_segment(error.instance_path.as_str(), instance_path.as_str()),
+                required,
+            )
+        });
+        entries
+            .into_iter()
+            .map(|entry| match entry {
+                Entry::Original(error) => error,
+                Entry::Replaced(group, template, error) => {
+                    let message = template.render(instance);
+                    let (instance_path, instance) = match group {
+                        Group::Property(property) => (
+                            instance_path.join(property),
+                            instance.get(property).unwrap_or(instance),
+                        ),
+                        _ => (instance_path.clone(), instance),
+                    };
+                    // The message replaces the group, which keeps the kind of its first error
+                    ValidationError {
+                        instance: Cow::Borrowed(instance),
+                        kind: error.kind,
+                        instance_path,
+                        schema_path: self.location.clone(),
+                        message: Some(message.into()),
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Replace output units produced by the schema at `node_location` for `instance`.
+    pub(crate) fn replace_units(
+        &self,
+        units: VecDeque<OutputUnit<ErrorDescription>>,
+        node_location: &Location,
+        absolute_path: Option<&Uri<String>>,
+        instance: &Value,
+        location: &LazyLocation,
+    ) -> VecDeque<OutputUnit<ErrorDescription>> {
+        let instance_path = Location::from(location);
+        let absolute_location = absolute_path.map(|absolute_path| {
+            let mut buffer = String::new();
+            uri::encode_to(self.location.as_str(), &mut buffer);
+            absolute_path.with_fragment(Some(uri::EncodedString::new_or_panic(&buffer)))
+        });
+        let entries = self.group(units, |unit| {
+            let required = unit
+                .error_description()
+                .missing_property()
+                .map(str::to_string);
+            (
+                first_segment(unit.keyword_location().as_str(), node_location.as_str()),
+                first_segment(unit.instance_location().as_str(), instance_path.as_str()),
+                required,
+            )
+        });
+        entries
+            .into_iter()
+            .map(|entry| match entry {
+                Entry::Original(unit) => unit,
+                Entry::Replaced(group, template, unit) => {
+                    let location = match group {
+                        Group::Property(property) => instance_path.join(property),
+                        _ => instance_path.clone(),
+                    };
+                    OutputUnit::<ErrorDescription>::error(
+                        self.location.clone(),
+                        location,
+                        absolute_location.clone(),
+                        unit.into_error_description()
+                            .with_message(template.render(instance)),
+                    )
+                }
+            })
+            .collect()
+    }
+
+    /// Split errors into the ones kept as is and groups replaced by a message. Each group takes
+    /// the place of its first error.
+    ///
+    /// `classify` returns the keyword that produced an error, the property of the instance it
+    /// refers to and the missing property for `required` errors.
+    fn group<E>(
+        &self,
+        errors: impl IntoIterator<Item = E>,
+        classify: impl Fn(&E) -> (Option<String>, Option<String>, Option<String>),
+    ) -> Vec<Entry<'_, E>> {
+        let mut entries = Vec::new();
+        let mut seen = Vec::new();
+        for error in errors {
+            let (keyword, property, required) = classify(&error);
+            let group = property
+                .and_then(|property| self.properties.get_key_value(&property))
+                .map(|(property, template)| (Group::Property(property), template))
+                .or_else(|| {
+                    required
+                        .filter(|_| keyword.as_deref() == Some("required"))
+                        .and_then(|property| self.required.get_key_value(&property))
+                        .map(|(property, template)| (Group::Required(property), template))
+                })
+                .or_else(|| {
+                    keyword
+                        .and_then(|keyword| self.keywords.get_key_value(&keyword))
+                        .map(|(keyword, template)| (Group::Keyword(keyword), template))
+                })
+                .or_else(|| {
+                    self.fallback
+                        .as_ref()
+                        .map(|template| (Group::Fallback, template))
+                });
+            match group {
+                Some((group, template)) => {
+                    if !seen.contains(&group) {
+                        seen.push(group);
+                        entries.push(Entry::Replaced(group, template, error));
+                    }
+                }
+                None => entries.push(Entry::Original(error)),
+            }
+        }
+        entries
+    }
+}
+
+fn compile_map<'a>(
+    map: &'a Map<String, Value>,
+    location: &Location,
+) -> Result<AHashMap<String, Template>, ValidationError<'a>> {
+    map.iter()
+        .map(|(key, value)| Ok((key.clone(), Template::compile(value, &location.join(key))?)))
+        .collect()
+}
+
+/// The first segment of `path` after `prefix`, unescaped.
+fn first_segment(path: &str, prefix: &str) -> Option<String> {
+    let rest = path.strip_prefix(prefix)?.strip_prefix('/')?;
+    let segment = rest.split('/').next()?;
+    Some(segment.replace("~1", "/").replace("~0", "~"))
+}
+
+/// A message with `${0}` and `${0/<JSON pointer>}` placeholders.
+#[derive(Debug)]
+struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug)]
+enum Part {
+    Text(String),
+    Pointer(String),
+}
+
+impl Template {
+    fn compile<'a>(value: &'a Value, location: &Location) -> Result<Template, ValidationError<'a>> {
+        if let Value::String(message) = value {
+            Ok(Template::new(message))
+        } else {
+            Err(ValidationError::single_type_error(
+                Location::new(),
+                location.clone(),
+                value,
+                PrimitiveType::String,
+            ))
+        }
+    }
+
+    fn new(message: &str) -> Template {
+        let mut parts = Vec::new();
+        let mut rest = message;
+        while let Some(start) = rest.find("${0") {
+            let placeholder = &rest[start + 3..];
+            let end = match placeholder.find('}') {
+                Some(end) if end == 0 || placeholder.starts_with('/') => end,
+                // Not a placeholder, keep it as is
+                _ => {
+                    parts.push(Part::Text(rest[..start + 3].to_string()));
+                    rest = placeholder;
+                    continue;
+                }
+            };
+            if start > 0 {
+                parts.push(Part::Text(rest[..start].to_string()));
+            }
+            parts.push(Part::Pointer(placeholder[..end].to_string()));
+            rest = &placeholder[end + 1..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Text(rest.to_string()));
+        }
+        Template { parts }
+    }
+
+    fn render(&self, instance: &Value) -> String {
+        let mut message = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => message.push_str(text),
+                Part::Pointer(pointer) => match instance.pointer(pointer) {
+                    Some(Value::String(value)) => message.push_str(value),
+                    Some(value) => message.push_str(&value.to_string()),
+                    None => {}
+                },
+            }
+        }
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Template;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn errors(schema: &Value, instance: &Value) -> Vec<(String, String, String)> {
+        let validator = crate::options()
+            .should_use_error_messages(true)
+            .build(schema)
+            .expect("Invalid schema");
+        let result = validator.validate(instance);
+        result
+            .expect_err("Should fail")
+            .map(|error| {
+                (
+                    error.to_string(),
+                    error.instance_path.to_string(),
+                    error.schema_path.to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[test_case(
+        &json!({"type": "object", "required": ["a"], "errorMessage": "Invalid"}),
+        &json!(1),
+        &[("Invalid", "", "/errorMessage")];
+        "all errors"
+    )]
+    #[test_case(
+        &json!({"type": "string", "minLength": 3, "errorMessage": {"type": "Not a string", "minLength": "Too short: ${0}"}}),
+        &json!("ab"),
+        &[("Too short: ab", "", "/errorMessage")];
+        "keyword"
+    )]
+    #[test_case(
+        &json!({"type": "string", "minLength": 3, "errorMessage": {"minLength": "Too short"}}),
+        &json!(1),
+        &[("1 is not of type \"string\"", "", "/type")];
+        "other keywords are kept"
+    )]
+    #[test_case(
+        &json!({"required": ["a", "b"], "errorMessage": {"required": {"a": "A is required"}}}),
+        &json!({}),
+        &[("A is required", "", "/errorMessage"), ("\"b\" is a required property", "", "/required")];
+        "required property"
+    )]
+    #[test_case(
+        &json!({"properties": {"age": {"type": "integer", "minimum": 18}}, "errorMessage": {"properties": {"age": "Invalid age: ${0/age}"}}}),
+        &json!({"age": "x"}),
+        &[("Invalid age: x", "/age", "/errorMessage")];
+        "property"
+    )]
+    #[test_case(
+        &json!({"type": "string", "maxLength": 1, "pattern": "^a", "errorMessage": {"pattern": "Should start with a", "_": "Invalid"}}),
+        &json!("bcd"),
+        &[("Invalid", "", "/errorMessage"), ("Should start with a", "", "/errorMessage")];
+        "fallback"
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"minimum": 2, "errorMessage": "Too small"}}, "errorMessage": {"type": "Unused"}}),
+        &json!({"a": 1}),
+        &[("Too small", "/a", "/properties/a/errorMessage")];
+        "nested"
+    )]
+    #[test_case(
+        &json!({"$ref": "#/$defs/name", "$defs": {"name": {"type": "string", "errorMessage": "Not a name"}}}),
+        &json!(1),
+        &[("Not a name", "", "/$ref/errorMessage")];
+        "reference"
+    )]
+    fn replaced(schema: &Value, instance: &Value, expected: &[(&str, &str, &str)]) {
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|(message, instance_path, schema_path)| {
+                (
+                    (*message).to_string(),
+                    (*instance_path).to_string(),
+                    (*schema_path).to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(errors(schema, instance), expected);
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let schema = json!({"type": "string", "errorMessage": "Invalid"});
+        let validator = crate::validator_for(&schema).expect("Invalid schema");
+        let instance = json!(1);
+        let error = validator
+            .validate(&instance)
+            .expect_err("Should fail")
+            .next()
+            .expect("One error");
+        assert_eq!(error.to_string(), "1 is not of type \"string\"");
+    }
+
+    #[test]
+    fn valid_instance() {
+        let schema = json!({"type": "string", "errorMessage": "Invalid"});
+        let validator = crate::options()
+            .should_use_error_messages(true)
+            .build(&schema)
+            .expect("Invalid schema");
+        assert!(validator.validate(&json!("a")).is_ok());
+        assert!(validator.is_valid(&json!("a")));
+        assert!(!validator.is_valid(&json!(1)));
+    }
+
+    #[test]
+    fn basic_output() {
+        let schema = json!({
+            "properties": {"age": {"type": "integer"}},
+            "required": ["name"],
+            "errorMessage": {
+                "properties": {"age": "Invalid age"},
+                "required": {"name": "Name is required"}
+            }
+        });
+        let validator = crate::options()
+            .should_use_error_messages(true)
+            .build(&schema)
+            .expect("Invalid schema");
+        let output = serde_json::to_value(validator.apply(&json!({"age": "x"})).basic())
+            .expect("Serializable");
+        assert_eq!(
+            output,
+            json!({
+                "valid": false,
+                "errors": [
+                    {
+                        "keywordLocation": "/errorMessage",
+                        "instanceLocation": "/age",
+                        "error": "Invalid age"
+                    },
+                    {
+                        "keywordLocation": "/errorMessage",
+                        "instanceLocation": "",
+                        "error": "Name is required"
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn detailed_output() {
+        let schema = json!({
+            "properties": {"age": {"type": "integer"}},
+            "errorMessage": {"properties": {"age": "Invalid age"}}
+        });
+        let validator = crate::options()
+            .should_use_error_messages(true)
+            .build(&schema)
+            .expect("Invalid schema");
+        let output = serde_json::to_value(validator.apply(&json!({"age": "x"})).detailed())
+            .expect("Serializable");
+        assert_eq!(
+            output,
+            json!({
+                "valid": false,
+                "keywordLocation": "",
+                "instanceLocation": "",
+                "errors": [
+                    {
+                        "valid": false,
+                        "keywordLocation": "/errorMessage",
+                        "instanceLocation": "/age",
+                        "error": "Invalid age"
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn kind_is_kept() {
+        let schema = json!({
+            "properties": {"age": {"type": "integer"}},
+            "errorMessage": {"properties": {"age": "Invalid age"}}
+        });
+        let validator = crate::options()
+            .should_use_error_messages(true)
+            .build(&schema)
+            .expect("Invalid schema");
+        let instance = json!({"age": "x"});
+        let error = validator
+            .validate(&instance)
+            .expect_err("Should fail")
+            .next()
+            .expect("One error");
+        assert_eq!(error.to_string(), "Invalid age");
+        assert_eq!(error.message.as_deref(), Some("Invalid age"));
+        assert_eq!(error.kind.code(), "type");
+        assert_eq!(error.kind.keyword(), Some("type"));
+        let crate::BasicOutput::Invalid(units) = validator.apply(&instance).basic() else {
+            panic!("Should fail");
+        };
+        let description = units[0].error_description();
+        assert_eq!(description.to_string(), "Invalid age");
+        assert_eq!(description.code(), "type");
+    }
+
+    #[test_case(&json!({"errorMessage": 1}), "/errorMessage"; "number")]
+    #[test_case(&json!({"errorMessage": {"type": []}}), "/errorMessage/type"; "keyword")]
+    #[test_case(&json!({"errorMessage": {"required": {"a": null}}}), "/errorMessage/required/a"; "required")]
+    fn invalid_schema(schema: &Value, location: &str) {
+        let error = crate::options()
+            .should_use_error_messages(true)
+            .build(schema)
+            .expect_err("Should fail");
+        assert_eq!(error.instance_path.as_str(), location);
+    }
+
+    #[test_case("Plain", &json!(1), "Plain")]
+    #[test_case("Got ${0}", &json!("a"), "Got a")]
+    #[test_case("Got ${0}", &json!({"a": 1}), "Got {\"a\":1}")]
+    #[test_case("${0/a/0} and ${0/b}", &json!({"a": [true], "b": "x"}), "true and x")]
+    #[test_case("Missing: ${0/c}.", &json!({}), "Missing: .")]
+    #[test_case("Not a placeholder: ${1} ${0a} ${0", &json!(1), "Not a placeholder: ${1} ${0a} ${0")]
+    fn template(template: &str, instance: &Value, expected: &str) {
+        assert_eq!(Template::new(template).render(instance), expected);
+    }
+}
@@ -4,6 +4,7 @@ use crate::{
     keywords::CompilationResult,
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
+    stream::Stream,
     validator::Validate,
 };
 use num_cmp::NumCmp;
@@ -59,6 +60,10 @@ macro_rules! validate {
                     true
                 }
             }
+
+            fn stream(&self) -> Stream<'_> {
+                Stream::Scalar
+            }
         }
     };
 }
@@ -94,6 +99,10 @@ impl Validate for ExclusiveMaximumF64Validator {
             ))
         }
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Scalar
+    }
 }
 
 #[inline]
@@ -12,11 +12,13 @@ use serde_json::{Map, Value};
 use uuid_simd::{parse_hyphenated, Out};
 
 use crate::{
-    compiler, ecma,
+    compiler,
     error::{error, no_error, ErrorIterator, ValidationError},
     keywords::CompilationResult,
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
+    regex::{PatternCache, PatternError},
+    stream::Stream,
     validator::Validate,
     Draft,
 };
@@ -598,10 +600,6 @@ fn is_valid_uri_reference(uri_reference: &str) -> bool {
     referencing::UriRef::parse(uri_reference).is_ok()
 }
 
-fn is_valid_regex(regex: &str) -> bool {
-    ecma::to_rust_regex(regex).is_ok()
-}
-
 fn is_valid_uri_template(uri_template: &str) -> bool {
     URI_TEMPLATE_RE
         .is_match(uri_template)
@@ -653,6 +651,10 @@ macro_rules! format_validators {
                     }
                     no_error()
                 }
+
+                fn stream(&self) -> Stream<'_> {
+                    Stream::Scalar
+                }
             }
         )+
     };
@@ -674,7 +676,6 @@ format_validators!(
         is_valid_iri_reference
     ),
     (JsonPointerValidator, "json-pointer", is_valid_json_pointer),
-    (RegexValidator, "regex", is_valid_regex),
     (
         RelativeJsonPointerValidator,
         "relative-json-pointer",
@@ -691,6 +692,52 @@ format_validators!(
     (UuidValidator, "uuid", is_valid_uuid),
 );
 
+/// Checks that a string is a valid ECMA 262 regular expression.
+/// Compiled patterns are cached together with the ones from `pattern` and `patternProperties`.
+struct RegexValidator {
+    location: Location,
+    patterns: Arc<PatternCache>,
+}
+
+impl RegexValidator {
+    pub(crate) fn compile<'a>(ctx: &compiler::Context) -> CompilationResult<'a> {
+        Ok(Box::new(RegexValidator {
+            location: ctx.location().join("format"),
+            patterns: Arc::clone(ctx.patterns()),
+        }))
+    }
+}
+
+impl Validate for RegexValidator {
+    fn validate<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
+        if !self.is_valid(instance) {
+            return error(ValidationError::format(
+                self.location.clone(),
+                location.into(),
+                instance,
+                "regex",
+            ));
+        }
+        no_error()
+    }
+
+    fn is_valid(&self, instance: &Value) -> bool {
+        if let Value::String(item) = instance {
+            // Patterns the configured engine can't compile are still valid regular expressions
+            !matches!(
+                self.patterns.get_or_compile(item),
+                Err(PatternError::Invalid)
+            )
+        } else {
+            true
+        }
+    }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Scalar
+    }
+}
+
 struct CustomFormatValidator {
     location: Location,
     format_name: String,
@@ -731,6 +778,10 @@ impl Validate for CustomFormatValidator {
             true
         }
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Scalar
+    }
 }
 
 pub(crate) trait Format: Send + Sync + 'static {
@@ -817,7 +868,7 @@ mod tests {
     use serde_json::json;
     use test_case::test_case;
 
-    use crate::{error::ValidationErrorKind, tests_util};
+    use crate::{error::ValidationErrorKind, tests_util, PatternOptions};
 
     use super::*;
 
@@ -859,6 +910,24 @@ mod tests {
         assert!(validator.is_valid(&instance))
     }
 
+    #[test_case(PatternOptions::fancy_regex())]
+    #[test_case(PatternOptions::regex())]
+    #[test_case(PatternOptions::regex().cache_capacity(0))]
+    fn regex_format_with_pattern_options(options: PatternOptions) {
+        let schema = json!({"format": "regex", "type": "string", "pattern": "^\\^"});
+        let validator = crate::options()
+            .should_validate_formats(true)
+            .with_pattern_options(options)
+            .build(&schema)
+            .expect("Valid schema");
+        // Look-around is a valid ECMA 262 regex even if the engine doesn't support it
+        for _ in 0..2 {
+            assert!(validator.is_valid(&json!("^(?!a)")));
+            assert!(validator.is_valid(&json!("^\\d+")));
+            assert!(!validator.is_valid(&json!("^[")));
+        }
+    }
+
     #[test]
     fn location() {
         tests_util::assert_schema_location(&json!({"format": "date"}), &json!("bla"), "/format")
@@ -1,8 +1,10 @@
 use crate::{
     compiler,
+    defaults::Defaults,
     error::{no_error, ErrorIterator},
     keywords::CompilationResult,
     node::SchemaNode,
+    output::Trace,
     paths::LazyLocation,
     validator::{PartialApplication, Validate},
 };
@@ -52,16 +54,28 @@ impl Validate for IfThenValidator {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
-        let mut if_result = self.schema.apply_rooted(instance, location);
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
+        let mut if_result = self.schema.apply_rooted(instance, location, trace);
         if if_result.is_valid() {
-            let then_result = self.then_schema.apply_rooted(instance, location);
+            let then_result = self.then_schema.apply_rooted(instance, location, trace);
             if_result += then_result;
             if_result.into()
         } else {
             PartialApplication::valid_empty()
         }
     }
+
+    fn collect_defaults(&self, instance: &Value, location: &LazyLocation, defaults: &mut Defaults) {
+        if defaults.follows_conditionals() && self.schema.is_valid(instance) {
+            self.then_schema
+                .collect_defaults(instance, location, defaults);
+        }
+    }
 }
 
 pub(crate) struct IfElseValidator {
@@ -108,12 +122,26 @@ impl Validate for IfElseValidator {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
-        let if_result = self.schema.apply_rooted(instance, location);
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
+        let if_result = self.schema.apply_rooted(instance, location, trace);
         if if_result.is_valid() {
             if_result.into()
         } else {
-            self.else_schema.apply_rooted(instance, location).into()
+            self.else_schema
+                .apply_rooted(instance, location, trace)
+                .into()
+        }
+    }
+
+    fn collect_defaults(&self, instance: &Value, location: &LazyLocation, defaults: &mut Defaults) {
+        if defaults.follows_conditionals() && !self.schema.is_valid(instance) {
+            self.else_schema
+                .collect_defaults(instance, location, defaults);
         }
     }
 }
@@ -169,13 +197,32 @@ impl Validate for IfThenElseValidator {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
-        let mut if_result = self.schema.apply_rooted(instance, location);
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
+        let mut if_result = self.schema.apply_rooted(instance, location, trace);
         if if_result.is_valid() {
-            if_result += self.then_schema.apply_rooted(instance, location);
+            if_result += self.then_schema.apply_rooted(instance, location, trace);
             if_result.into()
         } else {
-            self.else_schema.apply_rooted(instance, location).into()
+            self.else_schema
+                .apply_rooted(instance, location, trace)
+                .into()
+        }
+    }
+
+    fn collect_defaults(&self, instance: &Value, location: &LazyLocation, defaults: &mut Defaults) {
+        if defaults.follows_conditionals() {
+            if self.schema.is_valid(instance) {
+                self.then_schema
+                    .collect_defaults(instance, location, defaults);
+            } else {
+                self.else_schema
+                    .collect_defaults(instance, location, defaults);
+            }
         }
     }
 }
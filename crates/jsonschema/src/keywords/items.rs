@@ -1,9 +1,12 @@
 use crate::{
     compiler,
+    defaults::Defaults,
     error::{no_error, ErrorIterator},
     keywords::CompilationResult,
     node::SchemaNode,
+    output::Trace,
     paths::LazyLocation,
+    stream::Stream,
     validator::{PartialApplication, Validate},
 };
 use serde_json::{Map, Value};
@@ -53,6 +56,19 @@ impl Validate for ItemsArrayValidator {
             no_error()
         }
     }
+
+    fn collect_defaults(&self, instance: &Value, location: &LazyLocation, defaults: &mut Defaults) {
+        if let Value::Array(items) = instance {
+            defaults.collect_items(&self.items, items, location);
+        }
+    }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Items {
+            prefix: &self.items,
+            rest: None,
+        }
+    }
 }
 
 pub(crate) struct ItemsObjectValidator {
@@ -90,12 +106,17 @@ impl Validate for ItemsObjectValidator {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         if let Value::Array(items) = instance {
             let mut results = Vec::with_capacity(items.len());
             for (idx, item) in items.iter().enumerate() {
                 let path = location.push(idx);
-                results.push(self.node.apply_rooted(item, &path));
+                results.push(self.node.apply_rooted(item, &path, trace));
             }
             let mut output: PartialApplication = results.into_iter().collect();
             // Per draft 2020-12 section https://json-schema.org/draft/2020-12/json-schema-core.html#rfc.section.10.3.1.2
@@ -110,6 +131,22 @@ impl Validate for ItemsObjectValidator {
             PartialApplication::valid_empty()
         }
     }
+
+    fn collect_defaults(&self, instance: &Value, location: &LazyLocation, defaults: &mut Defaults) {
+        if let Value::Array(items) = instance {
+            for (idx, item) in items.iter().enumerate() {
+                self.node
+                    .collect_defaults(item, &location.push(idx), defaults);
+            }
+        }
+    }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Items {
+            prefix: &[],
+            rest: Some((0, &self.node)),
+        }
+    }
 }
 
 pub(crate) struct ItemsObjectSkipPrefixValidator {
@@ -163,12 +200,17 @@ impl Validate for ItemsObjectSkipPrefixValidator {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         if let Value::Array(items) = instance {
             let mut results = Vec::with_capacity(items.len().saturating_sub(self.skip_prefix));
             for (idx, item) in items.iter().enumerate().skip(self.skip_prefix) {
                 let path = location.push(idx);
-                results.push(self.node.apply_rooted(item, &path));
+                results.push(self.node.apply_rooted(item, &path, trace));
             }
             let mut output: PartialApplication = results.into_iter().collect();
             // Per draft 2020-12 section https://json-schema.org/draft/2020-12/json-schema-core.html#rfc.section.10.3.1.2
@@ -181,6 +223,22 @@ impl Validate for ItemsObjectSkipPrefixValidator {
             PartialApplication::valid_empty()
         }
     }
+
+    fn collect_defaults(&self, instance: &Value, location: &LazyLocation, defaults: &mut Defaults) {
+        if let Value::Array(items) = instance {
+            for (idx, item) in items.iter().enumerate().skip(self.skip_prefix) {
+                self.node
+                    .collect_defaults(item, &location.push(idx), defaults);
+            }
+        }
+    }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Items {
+            prefix: &[],
+            rest: Some((self.skip_prefix, &self.node)),
+        }
+    }
 }
 
 #[inline]
@@ -4,6 +4,7 @@ use crate::{
     keywords::{type_, CompilationResult},
     paths::{LazyLocation, Location},
     primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
+    stream::Stream,
     validator::Validate,
 };
 use serde_json::{json, Map, Number, Value};
@@ -74,6 +75,10 @@ impl Validate for MultipleTypesValidator {
             ))
         }
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Type
+    }
 }
 
 pub(crate) struct IntegerTypeValidator {
@@ -108,6 +113,10 @@ impl Validate for IntegerTypeValidator {
             ))
         }
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Type
+    }
 }
 
 fn is_integer(num: &Number) -> bool {
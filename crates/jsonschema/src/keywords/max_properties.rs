@@ -3,6 +3,7 @@ use crate::{
     error::{error, no_error, ErrorIterator, ValidationError},
     keywords::{helpers::fail_on_non_positive_integer, CompilationResult},
     paths::{LazyLocation, Location},
+    stream::Stream,
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -61,6 +62,10 @@ impl Validate for MaxPropertiesValidator {
         }
         no_error()
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Shallow
+    }
 }
 
 #[inline]
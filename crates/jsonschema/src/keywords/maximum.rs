@@ -4,6 +4,7 @@ use crate::{
     keywords::CompilationResult,
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
+    stream::Stream,
     validator::Validate,
 };
 use num_cmp::NumCmp;
@@ -58,6 +59,10 @@ macro_rules! validate {
                 }
                 true
             }
+
+            fn stream(&self) -> Stream<'_> {
+                Stream::Scalar
+            }
         }
     };
 }
@@ -92,6 +97,10 @@ impl Validate for MaximumF64Validator {
             ))
         }
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Scalar
+    }
 }
 
 #[inline]
@@ -9,6 +9,7 @@ pub(crate) mod content;
 pub(crate) mod custom;
 pub(crate) mod dependencies;
 pub(crate) mod enum_;
+pub(crate) mod error_message;
 pub(crate) mod exclusive_maximum;
 pub(crate) mod exclusive_minimum;
 pub(crate) mod format;
@@ -92,6 +93,7 @@ pub(crate) enum BuiltinKeyword {
     PropertyNames,
     ContentMediaType,
     ContentEncoding,
+    ContentSchema,
     If,
     RecursiveRef,
     DependentRequired,
@@ -138,6 +140,7 @@ impl BuiltinKeyword {
             Self::PropertyNames => "propertyNames",
             Self::ContentMediaType => "contentMediaType",
             Self::ContentEncoding => "contentEncoding",
+            Self::ContentSchema => "contentSchema",
             Self::If => "if",
             Self::RecursiveRef => "$recursiveRef",
             Self::DependentRequired => "dependentRequired",
@@ -323,6 +326,14 @@ pub(crate) fn get_for_draft<'a>(
             BuiltinKeyword::ContentEncoding.into(),
             content::compile_content_encoding,
         )),
+        (Draft::Draft201909 | Draft::Draft202012, "contentSchema")
+            if ctx.validates_content_schema() && ctx.has_vocabulary(&Vocabulary::Content) =>
+        {
+            Some((
+                BuiltinKeyword::ContentSchema.into(),
+                content::compile_content_schema,
+            ))
+        }
         (Draft::Draft7 | Draft::Draft201909 | Draft::Draft202012, "if")
             if ctx.has_vocabulary(&Vocabulary::Applicator) =>
         {
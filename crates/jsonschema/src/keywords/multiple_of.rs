@@ -4,6 +4,7 @@ use crate::{
     keywords::CompilationResult,
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
+    stream::Stream,
     validator::Validate,
 };
 use fraction::{BigFraction, BigUint};
@@ -56,6 +57,10 @@ impl Validate for MultipleOfFloatValidator {
         }
         no_error()
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Scalar
+    }
 }
 
 pub(crate) struct MultipleOfIntegerValidator {
@@ -96,6 +101,10 @@ impl Validate for MultipleOfIntegerValidator {
         }
         no_error()
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Scalar
+    }
 }
 
 #[inline]
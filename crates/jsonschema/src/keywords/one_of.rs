@@ -1,9 +1,10 @@
 use crate::{
     compiler,
+    defaults::Defaults,
     error::{error, no_error, ErrorIterator, ValidationError},
     keywords::CompilationResult,
     node::SchemaNode,
-    output::BasicOutput,
+    output::{BasicOutput, ErrorDescription, Trace},
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
     validator::{PartialApplication, Validate},
@@ -87,11 +88,16 @@ impl Validate for OneOfValidator {
             ))
         }
     }
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         let mut failures = Vec::new();
         let mut successes = Vec::new();
         for node in &self.schemas {
-            match node.apply_rooted(instance, location) {
+            match node.apply_rooted(instance, location, trace) {
                 output @ BasicOutput::Valid(..) => successes.push(output),
                 output @ BasicOutput::Invalid(..) => failures.push(output),
             };
@@ -100,13 +106,26 @@ impl Validate for OneOfValidator {
             let success = successes.remove(0);
             success.into()
         } else if successes.len() > 1 {
-            PartialApplication::invalid_empty(vec!["more than one subschema succeeded".into()])
+            PartialApplication::invalid_empty(vec![ErrorDescription::new(
+                "oneOfMultipleValid",
+                "more than one subschema succeeded",
+            )])
         } else if !failures.is_empty() {
             failures.into_iter().sum::<BasicOutput<'_>>().into()
         } else {
             unreachable!("compilation should fail for oneOf with no subschemas")
         }
     }
+
+    fn collect_defaults(&self, instance: &Value, location: &LazyLocation, defaults: &mut Defaults) {
+        if defaults.follows_conditionals() {
+            if let Some(idx) = self.get_first_valid(instance) {
+                if !self.are_others_valid(instance, idx) {
+                    self.schemas[idx].collect_defaults(instance, location, defaults);
+                }
+            }
+        }
+    }
 }
 
 #[inline]
@@ -1,65 +1,18 @@
 use crate::{
-    compiler, ecma,
+    compiler,
     error::{error, no_error, ErrorIterator, ValidationError},
     keywords::CompilationResult,
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
+    regex::Regex,
+    stream::Stream,
     validator::Validate,
 };
-use ahash::AHashMap;
-use once_cell::sync::Lazy;
 use serde_json::{Map, Value};
 
-use std::{collections::VecDeque, sync::Mutex};
-
-static REGEX_CACHE: Lazy<Mutex<LruCache>> = Lazy::new(|| Mutex::new(LruCache::new(10)));
-
-struct LruCache {
-    map: AHashMap<String, fancy_regex::Regex>,
-    queue: VecDeque<String>,
-    capacity: usize,
-}
-
-impl LruCache {
-    fn new(capacity: usize) -> Self {
-        LruCache {
-            map: AHashMap::new(),
-            queue: VecDeque::new(),
-            capacity,
-        }
-    }
-
-    fn get(&mut self, key: &str) -> Option<&fancy_regex::Regex> {
-        if let Some(value) = self.map.get(key) {
-            let index = self.queue.iter().position(|x| x == key).unwrap();
-            let k = self.queue.remove(index).unwrap();
-            self.queue.push_back(k);
-            Some(value)
-        } else {
-            None
-        }
-    }
-
-    fn insert(&mut self, key: String, value: fancy_regex::Regex) -> Option<fancy_regex::Regex> {
-        if self.map.len() >= self.capacity && !self.map.contains_key(&key) {
-            if let Some(lru_key) = self.queue.pop_front() {
-                self.map.remove(&lru_key);
-            }
-        }
-
-        let old_value = self.map.insert(key.clone(), value);
-        if old_value.is_some() {
-            let index = self.queue.iter().position(|x| x == &key).unwrap();
-            self.queue.remove(index);
-        }
-        self.queue.push_back(key);
-        old_value
-    }
-}
-
 pub(crate) struct PatternValidator {
     original: String,
-    pattern: fancy_regex::Regex,
+    pattern: Regex,
     location: Location,
 }
 
@@ -71,26 +24,9 @@ impl PatternValidator {
     ) -> CompilationResult<'a> {
         match pattern {
             Value::String(item) => {
-                let mut cache = REGEX_CACHE.lock().expect("Lock is poisoned");
-                let pattern = if let Some(regex) = cache.get(item) {
-                    regex.clone()
-                } else {
-                    let regex = match ecma::to_rust_regex(item)
-                        .map(|pattern| fancy_regex::Regex::new(&pattern))
-                    {
-                        Ok(Ok(r)) => r,
-                        _ => {
-                            return Err(ValidationError::format(
-                                Location::new(),
-                                ctx.location().clone(),
-                                pattern,
-                                "regex",
-                            ))
-                        }
-                    };
-                    cache.insert(item.clone(), regex.clone());
-                    regex
-                };
+                let pattern = ctx.compile_pattern(item).map_err(|error| {
+                    error.into_validation_error(ctx.location().join("pattern"), pattern, item)
+                })?;
                 Ok(Box::new(PatternValidator {
                     original: item.clone(),
                     pattern,
@@ -140,6 +76,10 @@ impl Validate for PatternValidator {
         }
         true
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Scalar
+    }
 }
 
 #[inline]
@@ -153,8 +93,8 @@ pub(crate) fn compile<'a>(
 
 #[cfg(test)]
 mod tests {
-    use crate::tests_util;
-    use serde_json::json;
+    use crate::{error::ValidationErrorKind, tests_util, PatternOptions};
+    use serde_json::{json, Value};
     use test_case::test_case;
 
     #[test_case("^(?!eo:)", "eo:bands", false)]
@@ -170,4 +110,82 @@ mod tests {
     fn location() {
         tests_util::assert_schema_location(&json!({"pattern": "^f"}), &json!("b"), "/pattern")
     }
+
+    #[test_case(&json!({"pattern": "^(?!eo:)"}), "/pattern")]
+    #[test_case(&json!({"patternProperties": {"(a)\\1": {}}}), "/patternProperties")]
+    #[test_case(&json!({"patternProperties": {"^(?!eo:)": {}, "^x": {}}}), "/patternProperties")]
+    #[test_case(&json!({"patternProperties": {"^(?!eo:)": {}}, "additionalProperties": false}), "/patternProperties")]
+    #[test_case(&json!({"patternProperties": {"^(?!eo:)": {}}, "unevaluatedProperties": false}), "/patternProperties")]
+    fn regex_engine_rejects_unsupported(schema: &Value, expected: &str) {
+        let error = crate::options()
+            .with_pattern_options(PatternOptions::regex())
+            .build(schema)
+            .expect_err("Should fail");
+        assert!(
+            matches!(error.kind, ValidationErrorKind::UnsupportedRegex { .. }),
+            "{error}"
+        );
+        assert!(error.instance_path.as_str().starts_with(expected));
+        // The default engine supports these patterns
+        assert!(crate::validator_for(schema).is_ok());
+    }
+
+    #[test]
+    fn regex_engine_error_message() {
+        let error = crate::options()
+            .with_pattern_options(PatternOptions::regex())
+            .build(&json!({"pattern": "^(?!eo:)"}))
+            .expect_err("Should fail");
+        assert_eq!(
+            error.to_string(),
+            "Unsupported regular expression \"^(?!eo:)\": look-around assertions are not supported by the `regex` engine"
+        );
+    }
+
+    #[test_case(PatternOptions::fancy_regex())]
+    #[test_case(PatternOptions::regex())]
+    fn size_limit_error(options: PatternOptions) {
+        let error = crate::options()
+            .with_pattern_options(options.size_limit(10))
+            .build(&json!({"pattern": "a{100}"}))
+            .expect_err("Should fail");
+        assert!(
+            matches!(error.kind, ValidationErrorKind::UnsupportedRegex { .. }),
+            "{error}"
+        );
+        assert_eq!(
+            error.to_string(),
+            "Unsupported regular expression \"a{100}\": compiled pattern exceeds the size limit of 10 bytes"
+        );
+    }
+
+    #[test_case(&json!({"pattern": "^[a-z]+$"}), &json!("abc"), &json!("ab1"))]
+    #[test_case(&json!({"patternProperties": {"^\\d+$": {"type": "string"}}}), &json!({"1": "a"}), &json!({"1": 1}))]
+    #[test_case(&json!({"patternProperties": {"^\\d+$": {"type": "string"}}, "additionalProperties": false}), &json!({"1": "a"}), &json!({"a": "a"}))]
+    fn regex_engine(schema: &Value, valid: &Value, invalid: &Value) {
+        let validator = crate::options()
+            .with_pattern_options(PatternOptions::regex())
+            .build(schema)
+            .expect("Valid schema");
+        assert!(validator.is_valid(valid));
+        assert!(!validator.is_valid(invalid));
+    }
+
+    #[test]
+    fn backtrack_limit() {
+        let validator = crate::options()
+            .with_pattern_options(PatternOptions::fancy_regex().backtrack_limit(10))
+            .build(&json!({"pattern": "^(a|a)*(?=b)"}))
+            .expect("Valid schema");
+        let instance = json!("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaac");
+        let error = validator
+            .validate(&instance)
+            .expect_err("Should fail")
+            .next()
+            .expect("Not empty");
+        assert!(matches!(
+            error.kind,
+            ValidationErrorKind::BacktrackLimitExceeded { .. }
+        ));
+    }
 }
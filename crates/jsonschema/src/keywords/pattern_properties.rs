@@ -1,14 +1,14 @@
 use crate::{
-    compiler, ecma,
+    compiler,
     error::{no_error, ErrorIterator, ValidationError},
     keywords::CompilationResult,
     node::SchemaNode,
-    output::BasicOutput,
+    output::{BasicOutput, Trace},
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
+    regex::Regex,
     validator::{PartialApplication, Validate},
 };
-use fancy_regex::Regex;
 use serde_json::{Map, Value};
 
 pub(crate) struct PatternPropertiesValidator {
@@ -26,17 +26,9 @@ impl PatternPropertiesValidator {
         for (pattern, subschema) in map {
             let pctx = ctx.new_at_location(pattern.as_str());
             patterns.push((
-                match ecma::to_rust_regex(pattern).map(|pattern| Regex::new(&pattern)) {
-                    Ok(Ok(r)) => r,
-                    _ => {
-                        return Err(ValidationError::format(
-                            Location::new(),
-                            ctx.location().clone(),
-                            subschema,
-                            "regex",
-                        ))
-                    }
-                },
+                ctx.compile_pattern(pattern).map_err(|error| {
+                    error.into_validation_error(ctx.location().clone(), subschema, pattern)
+                })?,
                 compiler::compile(&pctx, pctx.as_resource_ref(subschema))?,
             ));
         }
@@ -78,7 +70,12 @@ impl Validate for PatternPropertiesValidator {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(item) = instance {
             let mut matched_propnames = Vec::with_capacity(item.len());
             let mut sub_results = BasicOutput::default();
@@ -87,7 +84,7 @@ impl Validate for PatternPropertiesValidator {
                     if pattern.is_match(key).unwrap_or(false) {
                         let path = location.push(key.as_str());
                         matched_propnames.push(key.clone());
-                        sub_results += node.apply_rooted(value, &path);
+                        sub_results += node.apply_rooted(value, &path, trace);
                     }
                 }
             }
@@ -115,19 +112,9 @@ impl SingleValuePatternPropertiesValidator {
         let kctx = ctx.new_at_location("patternProperties");
         let pctx = kctx.new_at_location(pattern);
         Ok(Box::new(SingleValuePatternPropertiesValidator {
-            pattern: {
-                match ecma::to_rust_regex(pattern).map(|pattern| Regex::new(&pattern)) {
-                    Ok(Ok(r)) => r,
-                    _ => {
-                        return Err(ValidationError::format(
-                            Location::new(),
-                            kctx.location().clone(),
-                            schema,
-                            "regex",
-                        ))
-                    }
-                }
-            },
+            pattern: kctx.compile_pattern(pattern).map_err(|error| {
+                error.into_validation_error(kctx.location().clone(), schema, pattern)
+            })?,
             node: compiler::compile(&pctx, pctx.as_resource_ref(schema))?,
         }))
     }
@@ -161,7 +148,12 @@ impl Validate for SingleValuePatternPropertiesValidator {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(item) = instance {
             let mut matched_propnames = Vec::with_capacity(item.len());
             let mut outputs = BasicOutput::default();
@@ -169,7 +161,7 @@ impl Validate for SingleValuePatternPropertiesValidator {
                 if self.pattern.is_match(key).unwrap_or(false) {
                     let path = location.push(key.as_str());
                     matched_propnames.push(key.clone());
-                    outputs += self.node.apply_rooted(value, &path);
+                    outputs += self.node.apply_rooted(value, &path, trace);
                 }
             }
             let mut result: PartialApplication = outputs.into();
@@ -1,9 +1,12 @@
 use crate::{
     compiler,
+    defaults::Defaults,
     error::{no_error, ErrorIterator, ValidationError},
     node::SchemaNode,
+    output::Trace,
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
+    stream::Stream,
     validator::{PartialApplication, Validate},
 };
 use serde_json::{Map, Value};
@@ -59,7 +62,12 @@ impl Validate for PrefixItemsValidator {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         if let Value::Array(items) = instance {
             if !items.is_empty() {
                 let validate_total = self.schemas.len();
@@ -68,7 +76,7 @@ impl Validate for PrefixItemsValidator {
                 for (idx, (schema_node, item)) in self.schemas.iter().zip(items.iter()).enumerate()
                 {
                     let path = location.push(idx);
-                    results.push(schema_node.apply_rooted(item, &path));
+                    results.push(schema_node.apply_rooted(item, &path, trace));
                     max_index_applied = idx;
                 }
                 // Per draft 2020-12 section https://json-schema.org/draft/2020-12/json-schema-core.html#rfc.section.10.3.1.1
@@ -87,6 +95,19 @@ impl Validate for PrefixItemsValidator {
         }
         PartialApplication::valid_empty()
     }
+
+    fn collect_defaults(&self, instance: &Value, location: &LazyLocation, defaults: &mut Defaults) {
+        if let Value::Array(items) = instance {
+            defaults.collect_items(&self.schemas, items, location);
+        }
+    }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Items {
+            prefix: &self.schemas,
+            rest: None,
+        }
+    }
 }
 
 #[inline]
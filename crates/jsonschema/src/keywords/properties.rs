@@ -1,11 +1,13 @@
 use crate::{
     compiler,
+    defaults::Defaults,
     error::{no_error, ErrorIterator, ValidationError},
     keywords::CompilationResult,
     node::SchemaNode,
-    output::BasicOutput,
+    output::{BasicOutput, Trace},
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
+    stream::Stream,
     validator::{PartialApplication, Validate},
 };
 use serde_json::{Map, Value};
@@ -72,7 +74,12 @@ impl Validate for PropertiesValidator {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(props) = instance {
             let mut result = BasicOutput::default();
             let mut matched_props = Vec::with_capacity(props.len());
@@ -80,7 +87,7 @@ impl Validate for PropertiesValidator {
                 if let Some(prop) = props.get(prop_name) {
                     let path = location.push(prop_name.as_str());
                     matched_props.push(prop_name.clone());
-                    result += node.apply_rooted(prop, &path);
+                    result += node.apply_rooted(prop, &path, trace);
                 }
             }
             let mut application: PartialApplication = result.into();
@@ -90,6 +97,20 @@ impl Validate for PropertiesValidator {
             PartialApplication::valid_empty()
         }
     }
+
+    fn collect_defaults(&self, instance: &Value, location: &LazyLocation, defaults: &mut Defaults) {
+        if let Value::Object(object) = instance {
+            defaults.collect_properties(
+                self.properties.iter().map(|(name, node)| (name, node)),
+                object,
+                location,
+            );
+        }
+    }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Properties(&self.properties)
+    }
 }
 
 #[inline]
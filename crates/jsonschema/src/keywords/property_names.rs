@@ -3,7 +3,9 @@ use crate::{
     error::{error, no_error, ErrorIterator, ValidationError},
     keywords::CompilationResult,
     node::SchemaNode,
+    output::Trace,
     paths::{LazyLocation, Location},
+    stream::Stream,
     validator::{PartialApplication, Validate},
 };
 use serde_json::{Map, Value};
@@ -62,18 +64,27 @@ impl Validate for PropertyNamesObjectValidator {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         if let Value::Object(item) = instance {
             item.keys()
                 .map(|key| {
                     let wrapper = Value::String(key.to_string());
-                    self.node.apply_rooted(&wrapper, location)
+                    self.node.apply_rooted(&wrapper, location, trace)
                 })
                 .collect()
         } else {
             PartialApplication::valid_empty()
         }
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Shallow
+    }
 }
 
 pub(crate) struct PropertyNamesBooleanValidator {
@@ -109,6 +120,10 @@ impl Validate for PropertyNamesBooleanValidator {
             ))
         }
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Shallow
+    }
 }
 
 #[inline]
@@ -2,11 +2,15 @@ use std::{rc::Rc, sync::Arc};
 
 use crate::{
     compiler,
+    defaults::Defaults,
     error::ErrorIterator,
     keywords::CompilationResult,
     node::SchemaNode,
+    output::Trace,
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
+    regex::PatternCache,
+    stream::Stream,
     validator::{PartialApplication, Validate},
     ValidationError, ValidationOptions,
 };
@@ -52,6 +56,7 @@ impl RefValidator {
                     location,
                     vocabularies: ctx.vocabularies().clone(),
                     draft: ctx.draft(),
+                    patterns: Arc::clone(ctx.patterns()),
                     inner: OnceCell::default(),
                 })))
             } else {
@@ -95,6 +100,7 @@ pub(crate) struct LazyRefValidator {
     vocabularies: VocabularySet,
     location: Location,
     draft: Draft,
+    patterns: Arc<PatternCache>,
     inner: OnceCell<SchemaNode>,
 }
 
@@ -118,6 +124,7 @@ impl LazyRefValidator {
             vocabularies: ctx.vocabularies().clone(),
             location: ctx.location().join("$recursiveRef"),
             draft: ctx.draft(),
+            patterns: Arc::clone(ctx.patterns()),
             inner: OnceCell::default(),
         }))
     }
@@ -134,6 +141,7 @@ impl LazyRefValidator {
                 self.vocabularies.clone(),
                 self.draft,
                 self.location.clone(),
+                Arc::clone(&self.patterns),
             );
             // INVARIANT: This schema was already used during compilation before detecting a
             // reference cycle that lead to building this validator.
@@ -149,8 +157,24 @@ impl Validate for LazyRefValidator {
     fn validate<'i>(&self, instance: &'i Value, location: &LazyLocation) -> ErrorIterator<'i> {
         self.lazy_compile().validate(instance, location)
     }
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
-        self.lazy_compile().apply(instance, location)
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
+        self.lazy_compile().apply(instance, location, trace)
+    }
+
+    fn collect_defaults(&self, instance: &Value, location: &LazyLocation, defaults: &mut Defaults) {
+        if !defaults.is_expanding() {
+            self.lazy_compile()
+                .collect_defaults(instance, location, defaults);
+        }
+    }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::InPlace(std::slice::from_ref(self.lazy_compile()))
     }
 }
 
@@ -168,10 +192,29 @@ impl Validate for RefValidator {
             RefValidator::Lazy(lazy) => lazy.validate(instance, location),
         }
     }
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
+        match self {
+            RefValidator::Default { inner } => inner.apply(instance, location, trace),
+            RefValidator::Lazy(lazy) => lazy.apply(instance, location, trace),
+        }
+    }
+
+    fn collect_defaults(&self, instance: &Value, location: &LazyLocation, defaults: &mut Defaults) {
+        match self {
+            RefValidator::Default { inner } => inner.collect_defaults(instance, location, defaults),
+            RefValidator::Lazy(lazy) => lazy.collect_defaults(instance, location, defaults),
+        }
+    }
+
+    fn stream(&self) -> Stream<'_> {
         match self {
-            RefValidator::Default { inner } => inner.apply(instance, location),
-            RefValidator::Lazy(lazy) => lazy.apply(instance, location),
+            RefValidator::Default { inner } => Stream::InPlace(std::slice::from_ref(inner)),
+            RefValidator::Lazy(lazy) => lazy.stream(),
         }
     }
 }
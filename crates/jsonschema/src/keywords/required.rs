@@ -4,6 +4,7 @@ use crate::{
     keywords::CompilationResult,
     paths::{LazyLocation, Location},
     primitive_type::PrimitiveType,
+    stream::Stream,
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -65,6 +66,10 @@ impl Validate for RequiredValidator {
         }
         no_error()
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Shallow
+    }
 }
 
 pub(crate) struct SingleItemRequiredValidator {
@@ -103,6 +108,10 @@ impl Validate for SingleItemRequiredValidator {
             true
         }
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Shallow
+    }
 }
 
 #[inline]
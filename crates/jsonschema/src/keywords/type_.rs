@@ -4,6 +4,7 @@ use crate::{
     keywords::CompilationResult,
     paths::Location,
     primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
+    stream::Stream,
     validator::Validate,
 };
 use serde_json::{json, Map, Number, Value};
@@ -76,6 +77,10 @@ impl Validate for MultipleTypesValidator {
             ))
         }
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Type
+    }
 }
 
 pub(crate) struct NullTypeValidator {
@@ -105,6 +110,10 @@ impl Validate for NullTypeValidator {
             ))
         }
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Type
+    }
 }
 
 pub(crate) struct BooleanTypeValidator {
@@ -134,6 +143,10 @@ impl Validate for BooleanTypeValidator {
             ))
         }
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Type
+    }
 }
 
 pub(crate) struct StringTypeValidator {
@@ -164,6 +177,10 @@ impl Validate for StringTypeValidator {
             ))
         }
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Type
+    }
 }
 
 pub(crate) struct ArrayTypeValidator {
@@ -194,6 +211,10 @@ impl Validate for ArrayTypeValidator {
             ))
         }
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Type
+    }
 }
 
 pub(crate) struct ObjectTypeValidator {
@@ -223,6 +244,10 @@ impl Validate for ObjectTypeValidator {
             ))
         }
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Type
+    }
 }
 
 pub(crate) struct NumberTypeValidator {
@@ -252,6 +277,10 @@ impl Validate for NumberTypeValidator {
             ))
         }
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Type
+    }
 }
 
 pub(crate) struct IntegerTypeValidator {
@@ -285,6 +314,10 @@ impl Validate for IntegerTypeValidator {
             ))
         }
     }
+
+    fn stream(&self) -> Stream<'_> {
+        Stream::Type
+    }
 }
 
 fn is_integer(num: &Number) -> bool {
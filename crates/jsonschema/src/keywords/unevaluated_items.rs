@@ -4,6 +4,7 @@ use serde_json::{Map, Value};
 use crate::{
     compiler,
     error::no_error,
+    keywords::custom::CustomKeywords,
     node::SchemaNode,
     paths::{LazyLocation, Location},
     validator::Validate,
@@ -100,6 +101,7 @@ struct Draft2019ItemsFilter {
     all_of: Option<CombinatorFilter<Self>>,
     any_of: Option<CombinatorFilter<Self>>,
     one_of: Option<CombinatorFilter<Self>>,
+    custom: CustomKeywords,
 }
 
 impl ItemsFilter for Draft2019ItemsFilter {
@@ -188,6 +190,7 @@ impl ItemsFilter for Draft2019ItemsFilter {
             all_of,
             any_of,
             one_of,
+            custom: CustomKeywords::new(ctx, parent)?,
         })
     }
     fn unevaluated(&self) -> Option<&SchemaNode> {
@@ -208,6 +211,8 @@ impl ItemsFilter for Draft2019ItemsFilter {
             recursive_ref.mark_evaluated_indexes(instance, indexes);
         }
 
+        self.custom.mark_evaluated_indexes(instance, indexes);
+
         if let Some(conditional) = &self.conditional {
             conditional.mark_evaluated_indexes(instance, indexes);
         }
@@ -275,6 +280,7 @@ struct DefaultItemsFilter {
     all_of: Option<CombinatorFilter<Self>>,
     any_of: Option<CombinatorFilter<Self>>,
     one_of: Option<CombinatorFilter<Self>>,
+    custom: CustomKeywords,
 }
 
 impl ItemsFilter for DefaultItemsFilter {
@@ -359,6 +365,7 @@ impl ItemsFilter for DefaultItemsFilter {
             all_of,
             any_of,
             one_of,
+            custom: CustomKeywords::new(ctx, parent)?,
         })
     }
     fn unevaluated(&self) -> Option<&SchemaNode> {
@@ -381,6 +388,8 @@ impl ItemsFilter for DefaultItemsFilter {
             dynamic_ref.mark_evaluated_indexes(instance, indexes);
         }
 
+        self.custom.mark_evaluated_indexes(instance, indexes);
+
         if let Some(limit) = self.prefix_items {
             for idx in indexes.iter_mut().take(limit) {
                 *idx = true;
@@ -1,16 +1,17 @@
 use std::{rc::Rc, sync::Arc};
 
 use ahash::AHashSet;
-use fancy_regex::Regex;
 use once_cell::sync::OnceCell;
 use referencing::{Draft, List, Registry, Resource, Uri, VocabularySet};
 use serde_json::{Map, Value};
 
 use crate::{
-    compiler, ecma,
+    compiler,
     error::no_error,
+    keywords::custom::CustomKeywords,
     node::SchemaNode,
     paths::{LazyLocation, Location},
+    regex::{PatternCache, Regex},
     validator::Validate,
     ErrorIterator, ValidationError, ValidationOptions,
 };
@@ -105,13 +106,14 @@ struct Draft2019PropertiesFilter {
     additional: Option<SchemaNode>,
     properties: Vec<(String, SchemaNode)>,
     dependent: Vec<(String, Self)>,
-    pattern_properties: Vec<(fancy_regex::Regex, SchemaNode)>,
+    pattern_properties: Vec<(Regex, SchemaNode)>,
     ref_: Option<Box<Self>>,
     recursive_ref: Option<LazyReference<Self>>,
     conditional: Option<Box<ConditionalFilter<Self>>>,
     all_of: Option<CombinatorFilter<Self>>,
     any_of: Option<CombinatorFilter<Self>>,
     one_of: Option<CombinatorFilter<Self>>,
+    custom: CustomKeywords,
 }
 
 enum ReferenceFilter<T> {
@@ -145,6 +147,7 @@ struct LazyReference<T> {
     vocabularies: VocabularySet,
     location: Location,
     draft: Draft,
+    patterns: Arc<PatternCache>,
     inner: OnceCell<Box<T>>,
 }
 
@@ -168,6 +171,7 @@ impl<T: PropertiesFilter> LazyReference<T> {
             vocabularies: ctx.vocabularies().clone(),
             location: ctx.location().clone(),
             draft: ctx.draft(),
+            patterns: Arc::clone(ctx.patterns()),
             inner: OnceCell::default(),
         })
     }
@@ -185,6 +189,7 @@ impl<T: PropertiesFilter> LazyReference<T> {
                 self.vocabularies.clone(),
                 self.draft,
                 self.location.clone(),
+                Arc::clone(&self.patterns),
             );
 
             Box::new(
@@ -269,17 +274,9 @@ impl PropertiesFilter for Draft2019PropertiesFilter {
         if let Some(Value::Object(patterns)) = parent.get("patternProperties") {
             for (pattern, schema) in patterns {
                 pattern_properties.push((
-                    match ecma::to_rust_regex(pattern).map(|pattern| Regex::new(&pattern)) {
-                        Ok(Ok(r)) => r,
-                        _ => {
-                            return Err(ValidationError::format(
-                                Location::new(),
-                                ctx.location().clone(),
-                                schema,
-                                "regex",
-                            ))
-                        }
-                    },
+                    ctx.compile_pattern(pattern).map_err(|error| {
+                        error.into_validation_error(ctx.location().clone(), schema, pattern)
+                    })?,
                     compiler::compile(ctx, ctx.as_resource_ref(schema))?,
                 ));
             }
@@ -316,6 +313,7 @@ impl PropertiesFilter for Draft2019PropertiesFilter {
             all_of,
             any_of,
             one_of,
+            custom: CustomKeywords::new(ctx, parent)?,
         })
     }
 
@@ -334,6 +332,8 @@ impl PropertiesFilter for Draft2019PropertiesFilter {
                 .mark_evaluated_properties(instance, properties);
         }
 
+        self.custom.mark_evaluated_properties(instance, properties);
+
         if let Value::Object(obj) = instance {
             for (property, value) in obj {
                 for (p, node) in &self.properties {
@@ -419,13 +419,14 @@ struct DefaultPropertiesFilter {
     additional: Option<SchemaNode>,
     properties: Vec<(String, SchemaNode)>,
     dependent: Vec<(String, Self)>,
-    pattern_properties: Vec<(fancy_regex::Regex, SchemaNode)>,
+    pattern_properties: Vec<(Regex, SchemaNode)>,
     ref_: Option<ReferenceFilter<Self>>,
     dynamic_ref: Option<Box<Self>>,
     conditional: Option<Box<ConditionalFilter<Self>>>,
     all_of: Option<CombinatorFilter<Self>>,
     any_of: Option<CombinatorFilter<Self>>,
     one_of: Option<CombinatorFilter<Self>>,
+    custom: CustomKeywords,
 }
 
 impl PropertiesFilter for DefaultPropertiesFilter {
@@ -455,6 +456,7 @@ impl PropertiesFilter for DefaultPropertiesFilter {
                     vocabularies: ctx.vocabularies().clone(),
                     location: ctx.location().clone(),
                     draft: ctx.draft(),
+                    patterns: Arc::clone(ctx.patterns()),
                     inner: OnceCell::default(),
                 }));
             } else {
@@ -526,17 +528,9 @@ impl PropertiesFilter for DefaultPropertiesFilter {
         if let Some(Value::Object(patterns)) = parent.get("patternProperties") {
             for (pattern, schema) in patterns {
                 pattern_properties.push((
-                    match ecma::to_rust_regex(pattern).map(|pattern| Regex::new(&pattern)) {
-                        Ok(Ok(r)) => r,
-                        _ => {
-                            return Err(ValidationError::format(
-                                Location::new(),
-                                ctx.location().clone(),
-                                schema,
-                                "regex",
-                            ))
-                        }
-                    },
+                    ctx.compile_pattern(pattern).map_err(|error| {
+                        error.into_validation_error(ctx.location().clone(), schema, pattern)
+                    })?,
                     compiler::compile(ctx, ctx.as_resource_ref(schema))?,
                 ));
             }
@@ -573,6 +567,7 @@ impl PropertiesFilter for DefaultPropertiesFilter {
             all_of,
             any_of,
             one_of,
+            custom: CustomKeywords::new(ctx, parent)?,
         })
     }
 
@@ -589,6 +584,8 @@ impl PropertiesFilter for DefaultPropertiesFilter {
             recursive_ref.mark_evaluated_properties(instance, properties);
         }
 
+        self.custom.mark_evaluated_properties(instance, properties);
+
         if let Value::Object(obj) = instance {
             for (property, value) in obj {
                 for (p, node) in &self.properties {
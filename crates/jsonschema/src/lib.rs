@@ -3,7 +3,7 @@
 //! - 📚 Support for popular JSON Schema drafts
 //! - 🔧 Custom keywords and format validators
 //! - 🌐 Remote reference fetching (network/file)
-//! - 🎨 `Basic` output style as per JSON Schema spec
+//! - 🎨 `Basic`, `Detailed` and `Verbose` output styles as per JSON Schema spec
 //! - 🚀 WebAssembly support
 //!
 //! ## Supported drafts
@@ -232,10 +232,42 @@
 //! #    Ok(())
 //! # }
 //! ```
+//!
+//! ## Asynchronous Retrieval
+//!
+//! The default retriever blocks the current thread, which is not suitable inside async runtimes
+//! like Tokio. With the `resolve-async` feature enabled, [`ValidationOptions::build_async`] collects
+//! all external references upfront, retrieves them concurrently and then compiles the validator.
+//! Custom retrievers implement the [`AsyncRetrieve`](referencing::AsyncRetrieve) trait and are set
+//! via [`ValidationOptions::with_async_retriever`]:
+//!
+//! ```rust,ignore
+//! use jsonschema::{AsyncRetrieve, Uri};
+//! use serde_json::{json, Value};
+//!
+//! struct HttpRetriever {
+//!     client: reqwest::Client,
+//! }
+//!
+//! #[async_trait::async_trait]
+//! impl AsyncRetrieve for HttpRetriever {
+//!     async fn retrieve(
+//!         &self,
+//!         uri: &Uri<&str>,
+//!     ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+//!         Ok(self.client.get(uri.as_str()).send().await?.json().await?)
+//!     }
+//! }
+//!
+//! let validator = jsonschema::options()
+//!     .with_async_retriever(HttpRetriever { client: reqwest::Client::new() })
+//!     .build_async(&json!({"$ref": "https://example.com/person.json"}))
+//!     .await?;
+//! ```
 //! # Output Styles
 //!
-//! `jsonschema` supports the `basic` output style as defined in JSON Schema Draft 2019-09.
-//! This styles allow you to serialize validation results in a standardized format using `serde`.
+//! `jsonschema` supports the `basic`, `detailed` and `verbose` output styles as defined in JSON Schema Draft 2019-09.
+//! These styles allow you to serialize validation results in a standardized format using `serde`.
 //!
 //! ```rust
 //! # fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -269,6 +301,24 @@
 //! # }
 //! ```
 //!
+//! The `detailed` and `verbose` styles keep the structure of the schema, so errors from different
+//! `anyOf` / `oneOf` branches stay grouped under their branch:
+//!
+//! ```rust
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use serde_json::json;
+//!
+//! let schema = json!({"anyOf": [{"type": "string"}, {"minimum": 5}]});
+//! let validator = jsonschema::validator_for(&schema)?;
+//!
+//! let output = validator.apply(&json!(1)).detailed();
+//! assert!(!output.is_valid());
+//! assert_eq!(output.children()[0].keyword_location().as_str(), "/anyOf");
+//! assert_eq!(output.children()[0].children().len(), 2);
+//! #    Ok(())
+//! # }
+//! ```
+//!
 //! # Custom Keywords
 //!
 //! `jsonschema` allows you to extend its functionality by implementing custom validation logic through custom keywords.
@@ -400,6 +450,47 @@
 //! # }
 //! ```
 //!
+//! Custom keywords may also produce annotations by implementing [`Keyword::apply`]. The annotation
+//! value is included in the structured output, and properties or items marked as evaluated via
+//! [`KeywordAnnotations`] are respected by `unevaluatedProperties` and `unevaluatedItems`:
+//!
+//! ```rust
+//! use jsonschema::{
+//!     paths::LazyLocation, ErrorIterator, Keyword, KeywordAnnotations,
+//! };
+//! use serde_json::{json, Value};
+//!
+//! struct Deprecated(Vec<String>);
+//!
+//! impl Keyword for Deprecated {
+//!     fn validate<'i>(&self, _: &'i Value, _: &LazyLocation) -> ErrorIterator<'i> {
+//!         Box::new(std::iter::empty())
+//!     }
+//!
+//!     fn is_valid(&self, _: &Value) -> bool {
+//!         true
+//!     }
+//!
+//!     fn apply(&self, instance: &Value) -> Option<KeywordAnnotations> {
+//!         let object = instance.as_object()?;
+//!         let present: Vec<&String> =
+//!             self.0.iter().filter(|name| object.contains_key(*name)).collect();
+//!         Some(KeywordAnnotations::new(json!(present)).with_evaluated_properties(present))
+//!     }
+//! }
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let schema = json!({"x-deprecated": ["legacy"], "unevaluatedProperties": false});
+//! let validator = jsonschema::options()
+//!     .with_keyword("x-deprecated", |_, value, _| {
+//!         Ok(Box::new(Deprecated(serde_json::from_value(value.clone()).unwrap_or_default())))
+//!     })
+//!     .build(&schema)?;
+//! assert!(validator.is_valid(&json!({"legacy": 1})));
+//! assert!(!validator.is_valid(&json!({"unknown": 1})));
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! # Custom Formats
 //!
 //! JSON Schema allows for format validation through the `format` keyword. While `jsonschema`
@@ -447,6 +538,80 @@
 //! - Format validation can be disabled globally or per-draft using [`ValidationOptions`].
 //!   Ensure format validation is enabled if you're using custom formats.
 //!
+//! # Regular Expressions
+//!
+//! Patterns in `pattern` and `patternProperties` are compiled with the backtracking `fancy-regex`
+//! engine by default, which supports look-around assertions and backreferences. For schemas from
+//! untrusted sources, the linear-time `regex` engine can be used instead, or the backtracking can
+//! be bounded. See [`PatternOptions`] for details.
+//!
+//! ```rust
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use jsonschema::PatternOptions;
+//! use serde_json::json;
+//!
+//! let schema = json!({"pattern": "^[a-z]+$"});
+//! let validator = jsonschema::options()
+//!     .with_pattern_options(PatternOptions::regex().size_limit(1 << 20))
+//!     .build(&schema)?;
+//!
+//! assert!(validator.is_valid(&json!("abc")));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Error Messages
+//!
+//! Error messages can be customized with the `errorMessage` keyword, enabled via
+//! [`ValidationOptions::should_use_error_messages`], or with a function set via
+//! [`ValidationOptions::with_error_formatter`], e.g. to localize them.
+//!
+//! ```rust
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use serde_json::json;
+//!
+//! let schema = json!({"type": "string", "errorMessage": {"type": "Please enter a text"}});
+//! let validator = jsonschema::options()
+//!     .should_use_error_messages(true)
+//!     .build(&schema)?;
+//!
+//! let instance = json!(42);
+//! for error in validator.validate(&instance).expect_err("Invalid instance") {
+//!     assert_eq!(error.to_string(), "Please enter a text");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! To handle errors programmatically, match on [`ValidationErrorKind::code`], a stable string
+//! such as `"minLength"`, instead of parsing messages. With the `serde` feature enabled,
+//! [`ValidationError`] implements `serde::Serialize`; see its documentation for the JSON shape.
+//!
+//! # Serialized Validators
+//!
+//! Building a validator for a large schema involves retrieving external resources and
+//! validating the schema against its meta-schema. [`Validator::to_bytes`] stores the schema
+//! together with the resolved resources, so [`Validator::from_bytes`] can skip these steps, for
+//! example on a cold start. Compiled validators are not stored: the schema is compiled again
+//! when loading, so the savings depend on how much time the skipped steps take for a given
+//! schema: they are large when external resources come from the network, while for small
+//! self-contained schemas loading may be no faster than building. The format is versioned, and input from an incompatible version is rejected with
+//! [`SnapshotError::UnsupportedVersion`].
+//!
+//! ```rust
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use jsonschema::Validator;
+//! use serde_json::json;
+//!
+//! let bytes = jsonschema::validator_for(&json!({"minimum": 1}))?.to_bytes()?;
+//! // Later, possibly in another process
+//! let validator = Validator::from_bytes(&bytes)?;
+//!
+//! assert!(validator.is_valid(&json!(2)));
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! # WebAssembly support
 //!
 //! When using `jsonschema` in WASM environments, be aware that external references are
@@ -466,6 +631,8 @@
 pub(crate) mod compiler;
 mod content_encoding;
 mod content_media_type;
+mod defaults;
+mod dialect;
 mod ecma;
 pub mod error;
 mod keywords;
@@ -475,15 +642,29 @@ pub mod output;
 pub mod paths;
 pub mod primitive_type;
 pub(crate) mod properties;
+mod regex;
 mod retriever;
+mod snapshot;
+mod stream;
 mod validator;
+mod value;
 
-pub use error::{ErrorIterator, ValidationError};
-pub use keywords::custom::Keyword;
+pub use crate::regex::PatternOptions;
+pub use content_encoding::ContentEncoding;
+pub use content_media_type::ContentMediaType;
+pub use defaults::ConditionalDefaults;
+pub use dialect::Dialect;
+pub use error::{ErrorIterator, SchemaErrors, ValidationError};
+pub use keywords::custom::{Keyword, KeywordAnnotations};
 pub use options::ValidationOptions;
-pub use output::BasicOutput;
+pub use output::{BasicOutput, HierarchicalOutput};
+#[cfg(feature = "resolve-async")]
+pub use referencing::AsyncRetrieve;
 pub use referencing::{Draft, Resource, Retrieve, Uri};
+pub use retriever::DefaultRetriever;
+pub use snapshot::SnapshotError;
 pub use validator::Validator;
+pub use value::{Json, JsonValue};
 
 use serde_json::Value;
 
@@ -536,6 +717,23 @@ pub fn validator_for(schema: &Value) -> Result<Validator, ValidationError<'stati
     Validator::new(schema)
 }
 
+/// Check a schema and report all problems found in it.
+///
+/// See [`ValidationOptions::check_schema`] for details.
+///
+/// # Examples
+///
+/// ```rust
+/// use serde_json::json;
+///
+/// let schema = json!({"type": "text", "minLength": -1});
+/// let errors = jsonschema::check_schema(&schema).expect_err("Invalid schema");
+/// assert_eq!(errors.len(), 2);
+/// ```
+pub fn check_schema(schema: &Value) -> Result<(), SchemaErrors> {
+    options().check_schema(schema)
+}
+
 /// Create a builder for configuring JSON Schema validation options.
 ///
 /// This function returns a [`ValidationOptions`] struct, which allows you to set various
@@ -1206,6 +1404,102 @@ mod tests {
         assert!(crate::validator_for(&schema).is_err())
     }
 
+    #[test_case(&json!({"type": "string"}), &[] ; "valid")]
+    #[test_case(
+        &json!({"type": "text", "minLength": -1}),
+        &["/minLength", "/type"] ;
+        "meta-schema errors"
+    )]
+    #[test_case(
+        &json!({
+            "properties": {
+                "a": {"$ref": "#/$defs/missing"},
+                "b": {"pattern": "[", "items": 5}
+            },
+            "allOf": [{"maximum": "x"}]
+        }),
+        &["/allOf/0/maximum", "/properties/a/$ref", "/properties/b/items", "/properties/b/pattern"] ;
+        "meta-schema and compilation errors"
+    )]
+    #[test_case(
+        &json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "properties": {"a": {"$ref": "#/definitions/missing"}, "b": {"pattern": "["}}
+        }),
+        &["/properties/a/$ref", "/properties/b/pattern"] ;
+        "draft 7"
+    )]
+    fn check_schema(schema: &serde_json::Value, expected: &[&str]) {
+        let mut locations = match crate::check_schema(schema) {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors
+                .iter()
+                .map(|error| error.instance_path.to_string())
+                .collect(),
+        };
+        locations.sort();
+        assert_eq!(locations, expected);
+    }
+
+    #[test]
+    fn check_schema_display() {
+        let schema = json!({"minLength": -1, "properties": {"a": {"$ref": "#/$defs/missing"}}});
+        let errors = crate::check_schema(&schema).expect_err("Invalid schema");
+        assert_eq!(
+            errors.to_string(),
+            "/minLength: -1 is less than the minimum of 0\n/properties/a/$ref: Pointer '/$defs/missing' does not exist"
+        );
+    }
+
+    #[test]
+    fn check_schema_unresolvable_remote() {
+        struct Unavailable;
+
+        impl crate::Retrieve for Unavailable {
+            fn retrieve(
+                &self,
+                _: &crate::Uri<&str>,
+            ) -> Result<serde_json::Value, Box<dyn std::error::Error + Send + Sync>> {
+                Err("Unavailable".into())
+            }
+        }
+
+        let schema = json!({"minLength": -1, "$ref": "https://example.com/schema"});
+        let errors = crate::options()
+            .with_retriever(Unavailable)
+            .check_schema(&schema)
+            .expect_err("Invalid schema");
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors.iter().next().unwrap().instance_path.as_str(),
+            "/minLength"
+        );
+    }
+
+    #[test_case(json!({"$ref": "common.json"}); "no id")]
+    #[test_case(json!({"$id": "nested/../root.json", "$ref": "common.json"}); "relative id")]
+    fn base_uri(schema: serde_json::Value) {
+        let common = crate::Resource::from_contents(json!({"type": "string"})).expect("Valid");
+        let validator = crate::options()
+            .with_base_uri("file:///schemas/root.json")
+            .with_resource("file:///schemas/common.json", common)
+            .build(&schema)
+            .expect("Valid schema");
+        assert!(validator.is_valid(&json!("a")));
+        assert!(!validator.is_valid(&json!(1)));
+    }
+
+    #[test]
+    fn base_uri_with_absolute_id() {
+        let common = crate::Resource::from_contents(json!({"type": "string"})).expect("Valid");
+        let validator = crate::options()
+            .with_base_uri("file:///schemas/root.json")
+            .with_resource("https://example.com/common.json", common)
+            .build(&json!({"$id": "https://example.com/root.json", "$ref": "common.json"}))
+            .expect("Valid schema");
+        assert!(!validator.is_valid(&json!(1)));
+    }
+
     #[test]
     fn validation_error_propagation() {
         fn foo() -> Result<(), Box<dyn std::error::Error>> {
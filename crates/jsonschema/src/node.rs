@@ -1,8 +1,9 @@
 use crate::{
     compiler::Context,
+    defaults::Defaults,
     error::ErrorIterator,
-    keywords::{BoxedValidator, Keyword},
-    output::{Annotations, BasicOutput, ErrorDescription, OutputUnit},
+    keywords::{error_message::ErrorMessages, BoxedValidator, Keyword},
+    output::{Annotations, BasicOutput, ErrorDescription, OutputUnit, Trace},
     paths::{LazyLocation, Location, LocationSegment},
     validator::{PartialApplication, Validate},
     ValidationError,
@@ -53,6 +54,8 @@ struct KeywordValidators {
     /// The keywords on this node which were not recognized by any vocabularies. These are
     /// stored so we can later produce them as annotations
     unmatched_keywords: Option<AHashMap<String, Value>>,
+    /// Messages from the `errorMessage` keyword that replace errors of this node
+    error_messages: Option<ErrorMessages>,
     // We should probably use AHashMap here but it breaks a bunch of test which assume
     // validators are in a particular order
     validators: Vec<(Keyword, BoxedValidator)>,
@@ -71,12 +74,14 @@ impl SchemaNode {
         ctx: &Context<'_>,
         validators: Vec<(Keyword, BoxedValidator)>,
         unmatched_keywords: Option<AHashMap<String, Value>>,
+        error_messages: Option<ErrorMessages>,
     ) -> SchemaNode {
         SchemaNode {
             location: ctx.location().clone(),
             absolute_path: ctx.base_uri(),
             validators: NodeValidators::Keyword(Box::new(KeywordValidators {
                 unmatched_keywords,
+                error_messages,
                 validators,
             })),
         }
@@ -112,8 +117,25 @@ impl SchemaNode {
     /// validator tree and so rather than returning a `PartialApplication` it is able to return a
     /// complete `BasicOutput`. This is the mechanism which compositional validators use to combine
     /// results from sub-schemas
-    pub(crate) fn apply_rooted(&self, instance: &Value, location: &LazyLocation) -> BasicOutput {
-        match self.apply(instance, location) {
+    pub(crate) fn apply_rooted<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> BasicOutput<'a> {
+        if let Some(trace) = trace {
+            trace.enter();
+        }
+        let result = self.apply(instance, location, trace);
+        if let Some(trace) = trace {
+            trace.exit(
+                self.location.clone(),
+                location.into(),
+                self.absolute_location(&self.location),
+                &result,
+            );
+        }
+        match result {
             PartialApplication::Valid {
                 annotations,
                 mut child_results,
@@ -163,6 +185,15 @@ impl SchemaNode {
         )
     }
 
+    /// The absolute location of a keyword of this node
+    fn absolute_location(&self, location: &Location) -> Option<Uri<String>> {
+        self.absolute_path.as_ref().map(|absolute_path| {
+            let mut buffer = String::new();
+            uri::encode_to(location.as_str(), &mut buffer);
+            absolute_path.with_fragment(Some(uri::EncodedString::new_or_panic(&buffer)))
+        })
+    }
+
     /// Here we return a `NodeValidatorsErrIter` to avoid allocating in some situations. This isn't
     /// always possible but for a lot of common cases (e.g nodes with a single child) we can do it.
     /// This is wrapped in a `Box` by `SchemaNode::validate`
@@ -172,16 +203,25 @@ impl SchemaNode {
         location: &LazyLocation,
     ) -> NodeValidatorsErrIter<'a> {
         match &self.validators {
-            NodeValidators::Keyword(kvs) if kvs.validators.len() == 1 => {
+            NodeValidators::Keyword(kvs)
+                if kvs.validators.len() == 1 && kvs.error_messages.is_none() =>
+            {
                 NodeValidatorsErrIter::Single(kvs.validators[0].1.validate(instance, location))
             }
-            NodeValidators::Keyword(kvs) => NodeValidatorsErrIter::Multiple(
-                kvs.validators
+            NodeValidators::Keyword(kvs) => {
+                let errors = kvs
+                    .validators
                     .iter()
                     .flat_map(|(_, v)| v.validate(instance, location))
-                    .collect::<Vec<_>>()
-                    .into_iter(),
-            ),
+                    .collect::<Vec<_>>();
+                let errors = match &kvs.error_messages {
+                    Some(messages) => {
+                        messages.replace_errors(errors, &self.location, instance, location)
+                    }
+                    None => errors,
+                };
+                NodeValidatorsErrIter::Multiple(errors.into_iter())
+            }
             NodeValidators::Boolean {
                 validator: Some(v), ..
             } => NodeValidatorsErrIter::Single(v.validate(instance, location)),
@@ -208,6 +248,7 @@ impl SchemaNode {
         location: &LazyLocation,
         path_and_validators: I,
         annotations: Option<Annotations<'a>>,
+        trace: Option<&Trace<'a>>,
     ) -> PartialApplication<'a>
     where
         I: Iterator<Item = (P, &'a Box<dyn Validate + Send + Sync + 'a>)> + 'a,
@@ -236,13 +277,36 @@ impl SchemaNode {
                     })
                 };
             }
-            match validator.apply(instance, location) {
+            // The location of the keyword is only built if it is needed
+            let mut path = Some(path);
+            let keyword_location: OnceCell<Location> = OnceCell::new();
+            macro_rules! keyword_location {
+                () => {
+                    keyword_location
+                        .get_or_init(|| self.location.join(path.take().expect("Used once")))
+                        .clone()
+                };
+            }
+            if let Some(trace) = trace {
+                trace.enter();
+            }
+            let result = validator.apply(instance, location, trace);
+            if let Some(trace) = trace {
+                let location = keyword_location!();
+                trace.exit(
+                    location.clone(),
+                    instance_location!(),
+                    make_absolute_location!(location),
+                    &result,
+                );
+            }
+            match result {
                 PartialApplication::Valid {
                     annotations,
                     child_results,
                 } => {
                     if let Some(annotations) = annotations {
-                        let location = self.location.join(path);
+                        let location = keyword_location!();
                         let absolute_location = make_absolute_location!(location);
                         success_results.push_front(OutputUnit::<Annotations<'a>>::annotations(
                             location,
@@ -257,7 +321,7 @@ impl SchemaNode {
                     errors: these_errors,
                     child_results,
                 } => {
-                    let location = self.location.join(path);
+                    let location = keyword_location!();
                     error_results.reserve(child_results.len() + these_errors.len());
                     error_results.extend(child_results);
                     error_results.extend(these_errors.into_iter().map(|error| {
@@ -286,9 +350,31 @@ impl SchemaNode {
         }
     }
 
+    /// Validators of this node for streaming validation, if it can be streamed at all.
+    ///
+    /// Nodes with error messages and `false` schemas need the whole instance to report errors.
+    pub(crate) fn streamed_validators(&self) -> Option<Vec<&BoxedValidator>> {
+        match &self.validators {
+            NodeValidators::Boolean { validator: None } => Some(Vec::new()),
+            NodeValidators::Keyword(kvs) if kvs.error_messages.is_none() => {
+                Some(kvs.validators.iter().map(|(_, v)| v).collect())
+            }
+            _ => None,
+        }
+    }
+
     pub(crate) fn location(&self) -> &Location {
         &self.location
     }
+
+    /// The value of the `default` keyword of this schema, if any.
+    pub(crate) fn default_value(&self) -> Option<&Value> {
+        if let NodeValidators::Keyword(kvs) = &self.validators {
+            kvs.unmatched_keywords.as_ref()?.get("default")
+        } else {
+            None
+        }
+    }
 }
 
 impl Validate for SchemaNode {
@@ -314,14 +400,23 @@ impl Validate for SchemaNode {
         }
     }
 
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         match self.validators {
-            NodeValidators::Array { ref validators } => {
-                self.apply_subschemas(instance, location, validators.iter().enumerate(), None)
-            }
+            NodeValidators::Array { ref validators } => self.apply_subschemas(
+                instance,
+                location,
+                validators.iter().enumerate(),
+                None,
+                trace,
+            ),
             NodeValidators::Boolean { ref validator } => {
                 if let Some(validator) = validator {
-                    validator.apply(instance, location)
+                    validator.apply(instance, location, trace)
                 } else {
                     PartialApplication::Valid {
                         annotations: None,
@@ -332,19 +427,55 @@ impl Validate for SchemaNode {
             NodeValidators::Keyword(ref kvals) => {
                 let KeywordValidators {
                     ref unmatched_keywords,
+                    ref error_messages,
                     ref validators,
                 } = **kvals;
                 let annotations: Option<Annotations<'a>> =
                     unmatched_keywords.as_ref().map(Annotations::from);
-                self.apply_subschemas(
+                let result = self.apply_subschemas(
                     instance,
                     location,
                     validators.iter().map(|(p, v)| (p, v)),
                     annotations,
-                )
+                    trace,
+                );
+                match (error_messages, result) {
+                    (
+                        Some(messages),
+                        PartialApplication::Invalid {
+                            errors,
+                            child_results,
+                        },
+                    ) => {
+                        let replace = |units| {
+                            messages.replace_units(
+                                units,
+                                &self.location,
+                                self.absolute_path.as_ref(),
+                                instance,
+                                location,
+                            )
+                        };
+                        let child_results = match trace {
+                            Some(trace) => trace.replace(child_results, replace),
+                            None => replace(child_results),
+                        };
+                        PartialApplication::Invalid {
+                            errors,
+                            child_results,
+                        }
+                    }
+                    (_, result) => result,
+                }
             }
         }
     }
+
+    fn collect_defaults(&self, instance: &Value, location: &LazyLocation, defaults: &mut Defaults) {
+        for validator in self.validators() {
+            validator.collect_defaults(instance, location, defaults);
+        }
+    }
 }
 
 enum NodeValidatorsIter<'a> {
@@ -1,16 +1,22 @@
 use crate::{
     compiler,
-    content_encoding::{
-        ContentEncodingCheckType, ContentEncodingConverterType,
-        DEFAULT_CONTENT_ENCODING_CHECKS_AND_CONVERTERS,
+    content_encoding::{ContentEncoding, ContentEncodingHandler, DEFAULT_CONTENT_ENCODINGS},
+    content_media_type::{
+        ContentMediaType, ContentMediaTypeHandler, DEFAULT_CONTENT_MEDIA_TYPE_CHECKS,
     },
-    content_media_type::{ContentMediaTypeCheckType, DEFAULT_CONTENT_MEDIA_TYPE_CHECKS},
+    defaults::ConditionalDefaults,
+    dialect::{self, Dialect},
+    error::ErrorFormatter,
     keywords::{custom::KeywordFactory, format::Format},
     paths::Location,
+    regex::PatternOptions,
     retriever::DefaultRetriever,
-    Keyword, ValidationError, Validator,
+    snapshot::{self, SnapshotError},
+    Keyword, SchemaErrors, ValidationError, Validator,
 };
 use ahash::AHashMap;
+#[cfg(feature = "resolve-async")]
+use referencing::AsyncRetrieve;
 use referencing::{uri, Draft, Resource, Retrieve};
 use serde_json::Value;
 use std::{fmt, sync::Arc};
@@ -19,18 +25,28 @@ use std::{fmt, sync::Arc};
 #[derive(Clone)]
 pub struct ValidationOptions {
     pub(crate) draft: Option<Draft>,
-    content_media_type_checks: AHashMap<&'static str, Option<ContentMediaTypeCheckType>>,
-    content_encoding_checks_and_converters:
-        AHashMap<&'static str, Option<(ContentEncodingCheckType, ContentEncodingConverterType)>>,
+    content_media_type_checks: AHashMap<String, Option<ContentMediaTypeHandler>>,
+    content_encodings: AHashMap<String, Option<ContentEncodingHandler>>,
     /// Retriever for external resources
     pub(crate) retriever: Arc<dyn Retrieve>,
+    /// Retriever for external resources used by [`ValidationOptions::build_async`]
+    #[cfg(feature = "resolve-async")]
+    pub(crate) async_retriever: Arc<dyn AsyncRetrieve>,
     /// Additional resources that should be addressable during validation.
     pub(crate) resources: AHashMap<String, Resource>,
+    /// Base URI of the root schema, unless it has an absolute `$id`.
+    pub(crate) base_uri: Option<String>,
     formats: AHashMap<String, Arc<dyn Format>>,
     validate_formats: Option<bool>,
+    validate_content_schema: bool,
     pub(crate) validate_schema: bool,
     ignore_unknown_formats: bool,
     keywords: AHashMap<String, Arc<dyn KeywordFactory>>,
+    dialects: AHashMap<String, Dialect>,
+    conditional_defaults: ConditionalDefaults,
+    pattern_options: PatternOptions,
+    error_messages: bool,
+    error_formatter: Option<Arc<dyn ErrorFormatter>>,
 }
 
 impl Default for ValidationOptions {
@@ -38,14 +54,23 @@ impl Default for ValidationOptions {
         ValidationOptions {
             draft: None,
             content_media_type_checks: AHashMap::default(),
-            content_encoding_checks_and_converters: AHashMap::default(),
+            content_encodings: AHashMap::default(),
             retriever: Arc::new(DefaultRetriever),
+            #[cfg(feature = "resolve-async")]
+            async_retriever: Arc::new(DefaultRetriever),
             resources: AHashMap::default(),
+            base_uri: None,
             formats: AHashMap::default(),
             validate_formats: None,
+            validate_content_schema: false,
             validate_schema: true,
             ignore_unknown_formats: true,
             keywords: AHashMap::default(),
+            dialects: AHashMap::default(),
+            conditional_defaults: ConditionalDefaults::default(),
+            pattern_options: PatternOptions::default(),
+            error_messages: false,
+            error_formatter: None,
         }
     }
 }
@@ -62,6 +87,8 @@ impl ValidationOptions {
         //  - Default
         if let Some(draft) = self.draft {
             draft
+        } else if let Some(dialect) = self.dialect_for(contents) {
+            dialect.draft()
         } else {
             let default = Draft::default();
             match default.detect(contents) {
@@ -80,6 +107,29 @@ impl ValidationOptions {
             }
         }
     }
+    /// The same as [`ValidationOptions::draft_for`], but uses the asynchronous retriever.
+    #[cfg(feature = "resolve-async")]
+    pub(crate) async fn draft_for_async(&self, contents: &Value) -> Draft {
+        if let Some(draft) = self.draft {
+            draft
+        } else if let Some(dialect) = self.dialect_for(contents) {
+            dialect.draft()
+        } else {
+            let default = Draft::default();
+            match default.detect(contents) {
+                Ok(draft) => draft,
+                Err(referencing::Error::UnknownSpecification { specification }) => {
+                    if let Ok(uri) = uri::from_str(&specification) {
+                        if let Ok(retrieved) = self.async_retriever.retrieve(&uri.borrow()).await {
+                            return default.detect(&retrieved).unwrap_or_default();
+                        }
+                    }
+                    default
+                }
+                _ => default,
+            }
+        }
+    }
     /// Build a JSON Schema validator using the current options.
     ///
     /// # Example
@@ -98,6 +148,99 @@ impl ValidationOptions {
     pub fn build(&self, schema: &Value) -> Result<Validator, ValidationError<'static>> {
         compiler::build_validator(self.clone(), schema)
     }
+    /// Check a schema and report all problems found in it, instead of the first one as
+    /// [`ValidationOptions::build`] does.
+    ///
+    /// The schema is validated against its meta-schema regardless of
+    /// [`ValidationOptions::without_schema_validation`] and then compiled with the current
+    /// options, so errors like invalid regular expressions or unresolvable references are
+    /// reported as well.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({
+    ///     "properties": {
+    ///         "name": {"minLength": "one"},
+    ///         "code": {"pattern": "^[a-z"}
+    ///     }
+    /// });
+    /// let errors = jsonschema::options()
+    ///     .check_schema(&schema)
+    ///     .expect_err("Invalid schema");
+    ///
+    /// let locations: Vec<_> = errors
+    ///     .iter()
+    ///     .map(|error| error.instance_path.as_str())
+    ///     .collect();
+    /// assert_eq!(locations.len(), 2);
+    /// assert!(locations.contains(&"/properties/name/minLength"));
+    /// assert!(locations.contains(&"/properties/code/pattern"));
+    /// ```
+    pub fn check_schema(&self, schema: &Value) -> Result<(), SchemaErrors> {
+        compiler::check_schema(self.clone(), schema)
+    }
+    /// Load a validator serialized with [`Validator::to_bytes`] using the current options.
+    ///
+    /// Use this to register custom formats, keywords, content media types, encodings and error
+    /// formatters, which are not serialized. The draft, format and pattern settings stored in the
+    /// input take precedence over the current options. The schema is compiled again when loading.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is not a serialized validator, was produced by an
+    /// incompatible version of the format, is malformed, or if the schema can't be compiled
+    /// with the current options.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"format": "even"});
+    /// let bytes = jsonschema::options()
+    ///     .with_format("even", |value: &str| value.len() % 2 == 0)
+    ///     .should_validate_formats(true)
+    ///     .build(&schema)
+    ///     .expect("Invalid schema")
+    ///     .to_bytes()
+    ///     .expect("Unsupported draft");
+    ///
+    /// let validator = jsonschema::options()
+    ///     .with_format("even", |value: &str| value.len() % 2 == 0)
+    ///     .from_bytes(&bytes)
+    ///     .expect("Invalid input");
+    /// assert!(!validator.is_valid(&json!("odd")));
+    /// ```
+    pub fn from_bytes(&self, bytes: &[u8]) -> Result<Validator, SnapshotError> {
+        snapshot::from_bytes(self.clone(), bytes)
+    }
+    /// Build a JSON Schema validator using the current options, retrieving external resources
+    /// asynchronously.
+    ///
+    /// All external references are collected upfront and retrieved concurrently with the
+    /// retriever set via [`ValidationOptions::with_async_retriever`]. The synchronous retriever is
+    /// not used, so this method is safe to call inside an async runtime.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"$ref": "https://example.com/person.json"});
+    /// let validator = jsonschema::options()
+    ///     .build_async(&schema)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "resolve-async")]
+    pub async fn build_async(&self, schema: &Value) -> Result<Validator, ValidationError<'static>> {
+        compiler::build_validator_async(self.clone(), schema).await
+    }
     /// Sets the JSON Schema draft version.
     ///
     /// ```rust
@@ -115,15 +258,18 @@ impl ValidationOptions {
     pub(crate) fn get_content_media_type_check(
         &self,
         media_type: &str,
-    ) -> Option<ContentMediaTypeCheckType> {
+    ) -> Option<ContentMediaTypeHandler> {
         if let Some(value) = self.content_media_type_checks.get(media_type) {
-            *value
+            value.clone()
         } else {
-            DEFAULT_CONTENT_MEDIA_TYPE_CHECKS.get(media_type).copied()
+            DEFAULT_CONTENT_MEDIA_TYPE_CHECKS.get(media_type).cloned()
         }
     }
     /// Add support for a custom content media type validation.
     ///
+    /// The check is either a `Fn(&str) -> bool` closure or a [`ContentMediaType`] implementation,
+    /// which can hold its own configuration and explain why the content is invalid.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -134,13 +280,13 @@ impl ValidationOptions {
     /// let options = jsonschema::options()
     ///     .with_content_media_type("application/custom", check_custom_media_type);
     /// ```
-    pub fn with_content_media_type(
-        &mut self,
-        media_type: &'static str,
-        media_type_check: ContentMediaTypeCheckType,
-    ) -> &mut Self {
+    pub fn with_content_media_type<N, C>(&mut self, media_type: N, check: C) -> &mut Self
+    where
+        N: Into<String>,
+        C: ContentMediaType,
+    {
         self.content_media_type_checks
-            .insert(media_type, Some(media_type_check));
+            .insert(media_type.into(), Some(Arc::new(check)));
         self
     }
     /// Set a retriever to fetch external resources.
@@ -148,89 +294,75 @@ impl ValidationOptions {
         self.retriever = Arc::new(retriever);
         self
     }
-    /// Remove support for a specific content media type validation.
-    pub fn without_content_media_type_support(&mut self, media_type: &'static str) -> &mut Self {
-        self.content_media_type_checks.insert(media_type, None);
+    /// Set a retriever to fetch external resources in [`ValidationOptions::build_async`].
+    ///
+    /// ```rust
+    /// # use serde_json::Value;
+    /// use jsonschema::{AsyncRetrieve, Uri};
+    ///
+    /// struct StaticRetriever;
+    ///
+    /// #[async_trait::async_trait]
+    /// impl AsyncRetrieve for StaticRetriever {
+    ///     async fn retrieve(
+    ///         &self,
+    ///         uri: &Uri<&str>,
+    ///     ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+    ///         Ok(serde_json::json!({"type": "string"}))
+    ///     }
+    /// }
+    ///
+    /// let options = jsonschema::options()
+    ///     .with_async_retriever(StaticRetriever);
+    /// ```
+    #[cfg(feature = "resolve-async")]
+    pub fn with_async_retriever(&mut self, retriever: impl AsyncRetrieve + 'static) -> &mut Self {
+        self.async_retriever = Arc::new(retriever);
         self
     }
-
-    #[inline]
-    fn content_encoding_check_and_converter(
-        &self,
-        content_encoding: &str,
-    ) -> Option<(ContentEncodingCheckType, ContentEncodingConverterType)> {
-        if let Some(value) = self
-            .content_encoding_checks_and_converters
-            .get(content_encoding)
-        {
-            *value
-        } else {
-            DEFAULT_CONTENT_ENCODING_CHECKS_AND_CONVERTERS
-                .get(content_encoding)
-                .copied()
-        }
-    }
-
-    pub(crate) fn content_encoding_check(
-        &self,
-        content_encoding: &str,
-    ) -> Option<ContentEncodingCheckType> {
-        if let Some((check, _)) = self.content_encoding_check_and_converter(content_encoding) {
-            Some(check)
-        } else {
-            None
-        }
+    /// Remove support for a specific content media type validation.
+    pub fn without_content_media_type_support(
+        &mut self,
+        media_type: impl Into<String>,
+    ) -> &mut Self {
+        self.content_media_type_checks
+            .insert(media_type.into(), None);
+        self
     }
 
-    pub(crate) fn get_content_encoding_convert(
+    pub(crate) fn get_content_encoding(
         &self,
         content_encoding: &str,
-    ) -> Option<ContentEncodingConverterType> {
-        if let Some((_, converter)) = self.content_encoding_check_and_converter(content_encoding) {
-            Some(converter)
+    ) -> Option<ContentEncodingHandler> {
+        if let Some(value) = self.content_encodings.get(content_encoding) {
+            value.clone()
         } else {
-            None
+            DEFAULT_CONTENT_ENCODINGS.get(content_encoding).cloned()
         }
     }
     /// Add support for a custom content encoding.
     ///
-    /// # Arguments
-    ///
-    /// * `encoding`: Name of the content encoding (e.g., "base64")
-    /// * `check`: Validates the input string (return `true` if valid)
-    /// * `converter`: Converts the input string, returning:
-    ///   - `Err(ValidationError)`: For supported errors
-    ///   - `Ok(None)`: If input is invalid
-    ///   - `Ok(Some(content))`: If valid, with decoded content
+    /// The decoder is either a `Fn(&str) -> Option<Vec<u8>>` closure or a [`ContentEncoding`]
+    /// implementation, which can hold its own configuration and explain why the content is
+    /// invalid. With `contentMediaType`, the decoded content is checked against the media type.
     ///
     /// # Example
     ///
     /// ```rust
-    /// use jsonschema::ValidationError;
-    ///
-    /// fn check(s: &str) -> bool {
-    ///     s.starts_with("valid:")
-    /// }
-    ///
-    /// fn convert(s: &str) -> Result<Option<String>, ValidationError<'static>> {
-    ///     if s.starts_with("valid:") {
-    ///         Ok(Some(s[6..].to_string()))
-    ///     } else {
-    ///         Ok(None)
-    ///     }
+    /// fn decode(s: &str) -> Option<Vec<u8>> {
+    ///     s.strip_prefix("valid:").map(|s| s.as_bytes().to_vec())
     /// }
     ///
     /// let options = jsonschema::options()
-    ///     .with_content_encoding("custom", check, convert);
+    ///     .with_content_encoding("custom", decode);
     /// ```
-    pub fn with_content_encoding(
-        &mut self,
-        encoding: &'static str,
-        check: ContentEncodingCheckType,
-        converter: ContentEncodingConverterType,
-    ) -> &mut Self {
-        self.content_encoding_checks_and_converters
-            .insert(encoding, Some((check, converter)));
+    pub fn with_content_encoding<N, D>(&mut self, encoding: N, decoder: D) -> &mut Self
+    where
+        N: Into<String>,
+        D: ContentEncoding,
+    {
+        self.content_encodings
+            .insert(encoding.into(), Some(Arc::new(decoder)));
         self
     }
     /// Remove support for a specific content encoding.
@@ -243,10 +375,9 @@ impl ValidationOptions {
     /// ```
     pub fn without_content_encoding_support(
         &mut self,
-        content_encoding: &'static str,
+        content_encoding: impl Into<String>,
     ) -> &mut Self {
-        self.content_encoding_checks_and_converters
-            .insert(content_encoding, None);
+        self.content_encodings.insert(content_encoding.into(), None);
         self
     }
     /// Add a custom schema, allowing it to be referenced by the specified URI during validation.
@@ -274,6 +405,33 @@ impl ValidationOptions {
         self.resources.insert(uri.into(), resource);
         self
     }
+    /// Set the URI of the root schema, against which its relative references are resolved.
+    ///
+    /// A relative `$id` of the root schema is resolved against this URI, while an absolute one
+    /// takes precedence. Defaults to `json-schema:///`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use serde_json::json;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use jsonschema::Resource;
+    ///
+    /// let common = Resource::from_contents(json!({"type": "string"}))?;
+    ///
+    /// let validator = jsonschema::options()
+    ///     .with_base_uri("https://example.com/schemas/root.json")
+    ///     .with_resource("https://example.com/schemas/common.json", common)
+    ///     .build(&json!({"$ref": "common.json"}))?;
+    /// assert!(validator.is_valid(&json!("a")));
+    /// assert!(!validator.is_valid(&json!(1)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_base_uri(&mut self, base_uri: impl Into<String>) -> &mut Self {
+        self.base_uri = Some(base_uri.into());
+        self
+    }
     /// Add custom schemas, allowing them to be referenced by the specified URI during validation.
     ///
     /// This enables the use of additional in-memory schemas alongside the main schema being validated.
@@ -364,6 +522,50 @@ impl ValidationOptions {
     pub(crate) fn validate_formats(&self) -> Option<bool> {
         self.validate_formats
     }
+    /// Set whether to validate decoded string content against `contentSchema` (Draft 2019-09
+    /// and later).
+    ///
+    /// When enabled, a string is decoded with the converter registered for its `contentEncoding`
+    /// and parsed according to its `contentMediaType`, which has to be JSON-based, such as
+    /// `application/json` or `application/geo+json`. The result is validated against
+    /// `contentSchema`, and errors point into the decoded document, continuing the location of
+    /// the string. Strings that can't be decoded or parsed are reported as `contentEncoding` or
+    /// `contentMediaType` errors. Disabled by default, as the specification treats these
+    /// keywords as annotations.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({
+    ///     "properties": {
+    ///         "payload": {
+    ///             "contentEncoding": "base64",
+    ///             "contentMediaType": "application/json",
+    ///             "contentSchema": {"required": ["id"]}
+    ///         }
+    ///     }
+    /// });
+    /// let validator = jsonschema::options()
+    ///     .should_validate_content_schema(true)
+    ///     .build(&schema)?;
+    ///
+    /// // {"id": 1}
+    /// assert!(validator.is_valid(&json!({"payload": "eyJpZCI6IDF9"})));
+    /// // {}
+    /// assert!(!validator.is_valid(&json!({"payload": "e30="})));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn should_validate_content_schema(&mut self, yes: bool) -> &mut Self {
+        self.validate_content_schema = yes;
+        self
+    }
+    pub(crate) const fn validates_content_schema(&self) -> bool {
+        self.validate_content_schema
+    }
     /// Set whether to ignore unknown formats.
     ///
     /// By default, unknown formats are silently ignored. Set to `false` to report
@@ -451,6 +653,218 @@ impl ValidationOptions {
     pub(crate) fn get_keyword_factory(&self, name: &str) -> Option<&Arc<dyn KeywordFactory>> {
         self.keywords.get(name)
     }
+    /// Register a custom dialect.
+    ///
+    /// Schemas declaring the dialect's URI in `$schema` are validated against its meta-schema
+    /// and compiled with its keywords. See [`Dialect`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use jsonschema::Dialect;
+    /// use serde_json::json;
+    ///
+    /// let dialect = Dialect::new(
+    ///     "https://example.com/strict",
+    ///     json!({
+    ///         "$schema": "https://json-schema.org/draft/2020-12/schema",
+    ///         "$id": "https://example.com/strict",
+    ///         "$dynamicAnchor": "meta",
+    ///         "allOf": [{"$ref": "https://json-schema.org/draft/2020-12/schema"}],
+    ///         "required": ["title"]
+    ///     }),
+    /// );
+    /// let mut options = jsonschema::options();
+    /// options.with_dialect(dialect);
+    ///
+    /// let schema = json!({"$schema": "https://example.com/strict", "type": "string"});
+    /// assert!(options.build(&schema).is_err());
+    ///
+    /// let schema = json!({"$schema": "https://example.com/strict", "title": "Name", "type": "string"});
+    /// let validator = options.build(&schema)?;
+    /// assert!(validator.is_valid(&json!("Alice")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_dialect(&mut self, dialect: Dialect) -> &mut Self {
+        self.dialects.insert(dialect.uri().to_string(), dialect);
+        self
+    }
+    pub(crate) fn dialect_for(&self, contents: &Value) -> Option<&Dialect> {
+        dialect::declared_uri(contents).and_then(|uri| self.dialects.get(uri))
+    }
+    pub(crate) fn dialects(&self) -> impl Iterator<Item = &Dialect> {
+        self.dialects.values()
+    }
+    pub(crate) fn dialect(&self, uri: &str) -> Option<&Dialect> {
+        self.dialects.get(uri)
+    }
+    pub(crate) fn has_dialects(&self) -> bool {
+        !self.dialects.is_empty()
+    }
+    /// Options to compile the meta-schema of a dialect with.
+    ///
+    /// The dialect itself is left out, so meta-schemas declaring their own dialect, directly or
+    /// through other dialects, are checked against the draft meta-schema instead.
+    pub(crate) fn for_meta_schema(&self, dialect: &Dialect) -> ValidationOptions {
+        let mut dialects = self.dialects.clone();
+        dialects.remove(&**dialect.uri());
+        ValidationOptions {
+            draft: Some(dialect.draft()),
+            dialects,
+            ..ValidationOptions::default()
+        }
+    }
+    /// Set how `default` values inside conditional subschemas are handled by
+    /// [`Validator::validate_and_apply_defaults`].
+    ///
+    /// By default, only defaults from the branches that match the instance are applied.
+    ///
+    /// ```rust
+    /// # use serde_json::json;
+    /// use jsonschema::ConditionalDefaults;
+    ///
+    /// let schema = json!({
+    ///     "if": {"required": ["tls"]},
+    ///     "then": {"properties": {"port": {"default": 443}}}
+    /// });
+    /// let validator = jsonschema::options()
+    ///     .with_conditional_defaults(ConditionalDefaults::Ignore)
+    ///     .build(&schema)
+    ///     .expect("A valid schema");
+    ///
+    /// let mut instance = json!({"tls": true});
+    /// assert!(validator.validate_and_apply_defaults(&mut instance).is_ok());
+    /// assert_eq!(instance, json!({"tls": true}));
+    /// ```
+    #[inline]
+    pub fn with_conditional_defaults(&mut self, policy: ConditionalDefaults) -> &mut Self {
+        self.conditional_defaults = policy;
+        self
+    }
+    pub(crate) const fn conditional_defaults(&self) -> ConditionalDefaults {
+        self.conditional_defaults
+    }
+    /// Configure the regular expression engine used by `pattern` and `patternProperties`.
+    ///
+    /// By default, patterns are compiled with the backtracking `fancy-regex` engine.
+    /// See [`PatternOptions`] for details.
+    ///
+    /// ```rust
+    /// # use serde_json::json;
+    /// use jsonschema::PatternOptions;
+    ///
+    /// let schema = json!({"pattern": "^[a-z]+$"});
+    /// let validator = jsonschema::options()
+    ///     .with_pattern_options(PatternOptions::regex())
+    ///     .build(&schema)
+    ///     .expect("A valid schema");
+    ///
+    /// assert!(validator.is_valid(&json!("abc")));
+    /// ```
+    #[inline]
+    pub fn with_pattern_options(&mut self, options: PatternOptions) -> &mut Self {
+        self.pattern_options = options;
+        self
+    }
+    pub(crate) const fn pattern_options(&self) -> PatternOptions {
+        self.pattern_options
+    }
+    /// Set whether to support the `errorMessage` keyword, which replaces errors produced by a
+    /// schema with custom messages.
+    ///
+    /// The keyword is disabled by default and treated as an annotation, as it is not a part of
+    /// the JSON Schema specification. Its value is either a message for all errors of the
+    /// schema, or an object with messages for specific keywords:
+    ///
+    /// - `"<keyword>": "..."` replaces errors of the keyword, e.g. `type` or `minLength`.
+    /// - `"required": {"<property>": "..."}` replaces `required` errors for specific properties.
+    /// - `"properties": {"<property>": "..."}` replaces all errors within specific properties.
+    /// - `"_": "..."` replaces all other errors.
+    ///
+    /// Messages may contain `${0}` placeholders, which are replaced with the instance, and
+    /// `${0/<JSON pointer>}` placeholders, which are replaced with a part of the instance.
+    ///
+    /// Each group of replaced errors becomes a single error with the kind of its first error and
+    /// the message in [`ValidationError::message`].
+    ///
+    /// ```rust
+    /// # use serde_json::json;
+    /// let schema = json!({
+    ///     "properties": {
+    ///         "age": {"type": "integer", "minimum": 18}
+    ///     },
+    ///     "required": ["age"],
+    ///     "errorMessage": {
+    ///         "required": {"age": "Please enter your age"},
+    ///         "properties": {"age": "You must be an adult, not ${0/age}"}
+    ///     }
+    /// });
+    /// let validator = jsonschema::options()
+    ///     .should_use_error_messages(true)
+    ///     .build(&schema)
+    ///     .expect("A valid schema");
+    ///
+    /// let messages = |instance| -> Vec<String> {
+    ///     match validator.validate(&instance) {
+    ///         Ok(()) => Vec::new(),
+    ///         Err(errors) => errors.map(|error| error.to_string()).collect(),
+    ///     }
+    /// };
+    /// assert_eq!(messages(json!({})), ["Please enter your age"]);
+    /// assert_eq!(messages(json!({"age": 15})), ["You must be an adult, not 15"]);
+    /// ```
+    #[inline]
+    pub fn should_use_error_messages(&mut self, yes: bool) -> &mut Self {
+        self.error_messages = yes;
+        self
+    }
+    pub(crate) const fn are_error_messages_used(&self) -> bool {
+        self.error_messages
+    }
+    /// Set a function that produces messages for errors returned by [`Validator::validate`],
+    /// [`Validator::validate_and_apply_defaults`], [`Validator::validate_reader`],
+    /// [`Validator::validate_slice`] and [`Validator::validate_value`].
+    ///
+    /// The function receives an error with its kind, instance path and schema path. It returns a
+    /// message that replaces the default one, or `None` to keep the error as is. The message is
+    /// stored in [`ValidationError::message`], and the error keeps its kind, instance and paths.
+    /// Errors replaced by `errorMessage` carry its message there, so it can be translated too.
+    ///
+    /// The function is also applied to the errors of the "basic", "detailed" and "verbose"
+    /// outputs of [`Validator::apply`].
+    ///
+    /// ```rust
+    /// # use serde_json::json;
+    /// use jsonschema::error::ValidationErrorKind;
+    ///
+    /// let schema = json!({"properties": {"name": {"type": "string"}}});
+    /// let validator = jsonschema::options()
+    ///     .with_error_formatter(|error| match &error.kind {
+    ///         ValidationErrorKind::Type { .. } => {
+    ///             Some(format!("Type invalide à « {} »", error.instance_path))
+    ///         }
+    ///         _ => None,
+    ///     })
+    ///     .build(&schema)
+    ///     .expect("A valid schema");
+    ///
+    /// let instance = json!({"name": 42});
+    /// for error in validator.validate(&instance).expect_err("Invalid instance") {
+    ///     assert_eq!(error.to_string(), "Type invalide à « /name »");
+    /// }
+    /// ```
+    pub fn with_error_formatter<F>(&mut self, formatter: F) -> &mut Self
+    where
+        F: Fn(&ValidationError<'_>) -> Option<String> + Send + Sync + 'static,
+    {
+        self.error_formatter = Some(Arc::new(formatter));
+        self
+    }
+    pub(crate) fn error_formatter(&self) -> Option<&dyn ErrorFormatter> {
+        self.error_formatter.as_deref()
+    }
 }
 
 impl fmt::Debug for ValidationOptions {
@@ -458,10 +872,7 @@ impl fmt::Debug for ValidationOptions {
         fmt.debug_struct("CompilationConfig")
             .field("draft", &self.draft)
             .field("content_media_type", &self.content_media_type_checks.keys())
-            .field(
-                "content_encoding",
-                &self.content_encoding_checks_and_converters.keys(),
-            )
+            .field("content_encoding", &self.content_encodings.keys())
             .finish()
     }
 }
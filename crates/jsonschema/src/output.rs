@@ -1,17 +1,25 @@
 //! Implementation of json schema output formats specified in <https://json-schema.org/draft/2020-12/json-schema-core.html#rfc.section.12.2>
 //!
-//! Currently the "basic" formats is supported. The main contribution of this module is [`Output::basic`].
-//! See the documentation of that method for more information.
+//! The "flag", "basic", "detailed" and "verbose" formats are supported. The main contribution of
+//! this module is [`Output::basic`] and [`Output::detailed`]. See the documentation of these methods
+//! for more information.
 
 use std::{
     borrow::Cow,
+    cell::RefCell,
     collections::VecDeque,
     fmt,
     iter::{FromIterator, Sum},
     ops::AddAssign,
+    sync::Arc,
 };
 
-use crate::{paths::Location, validator::PartialApplication, ValidationError};
+use crate::{
+    error::{ErrorFormatter, ValidationErrorKind},
+    paths::Location,
+    validator::PartialApplication,
+    ValidationError,
+};
 use ahash::AHashMap;
 use referencing::Uri;
 use serde::ser::SerializeMap;
@@ -22,7 +30,7 @@ use crate::{node::SchemaNode, paths::LazyLocation, Validator};
 /// converted into various representations based on the definitions in
 /// <https://json-schema.org/draft/2020-12/json-schema-core.html#rfc.section.12.2>
 ///
-/// Currently only the "flag" and "basic" output formats are supported
+/// The "flag", "basic", "detailed" and "verbose" output formats are supported
 #[derive(Debug, Clone)]
 pub struct Output<'a, 'b> {
     schema: &'a Validator,
@@ -102,8 +110,95 @@ impl<'a, 'b> Output<'a, 'b> {
     /// ```
     #[must_use]
     pub fn basic(&self) -> BasicOutput<'a> {
+        let mut output = self
+            .root_node
+            .apply_rooted(self.instance, &LazyLocation::new(), None);
+        if let BasicOutput::Invalid(units) = &mut output {
+            let formatter = self.schema.config.error_formatter();
+            for unit in units {
+                unit.value.format(formatter);
+            }
+        }
+        output
+    }
+
+    /// Output a hierarchy of errors and annotations according to the "detailed" output format.
+    ///
+    /// Unlike [`Output::basic`], the results are nested following the structure of the schema, so
+    /// it is possible to see which `anyOf` / `oneOf` branch or which `$ref` target produced
+    /// an error. The root output unit is always present, while intermediate output units which have
+    /// only a single child are condensed into that child, as the specification suggests.
+    /// Use [`Output::verbose`] to keep every level.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({
+    ///     "anyOf": [
+    ///         {"type": "string"},
+    ///         {"type": "integer", "minimum": 5}
+    ///     ]
+    /// });
+    /// let validator = jsonschema::validator_for(&schema)?;
+    ///
+    /// let output = validator.apply(&json!(1)).detailed();
+    /// assert_eq!(
+    ///     serde_json::to_value(output)?,
+    ///     json!({
+    ///         "valid": false,
+    ///         "keywordLocation": "",
+    ///         "instanceLocation": "",
+    ///         "errors": [
+    ///             {
+    ///                 "valid": false,
+    ///                 "keywordLocation": "/anyOf",
+    ///                 "instanceLocation": "",
+    ///                 "errors": [
+    ///                     {
+    ///                         "valid": false,
+    ///                         "keywordLocation": "/anyOf/0/type",
+    ///                         "instanceLocation": "",
+    ///                         "error": "1 is not of type \"string\""
+    ///                     },
+    ///                     {
+    ///                         "valid": false,
+    ///                         "keywordLocation": "/anyOf/1/minimum",
+    ///                         "instanceLocation": "",
+    ///                         "error": "1 is less than the minimum of 5"
+    ///                     }
+    ///                 ]
+    ///             }
+    ///         ]
+    ///     })
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn detailed(&self) -> HierarchicalOutput<'a> {
+        self.hierarchical().condensed()
+    }
+
+    /// Output a hierarchy of errors and annotations according to the "verbose" output format.
+    ///
+    /// Every applied subschema and every evaluated keyword has its own output unit with its own
+    /// validity, including the valid ones next to failures. Unlike [`Output::detailed`], no output
+    /// units are condensed or left out.
+    #[must_use]
+    pub fn verbose(&self) -> HierarchicalOutput<'a> {
+        self.hierarchical()
+    }
+
+    fn hierarchical(&self) -> HierarchicalOutput<'a> {
+        let trace = Trace::new();
         self.root_node
-            .apply_rooted(self.instance, &LazyLocation::new())
+            .apply_rooted(self.instance, &LazyLocation::new(), Some(&trace));
+        let mut output = trace.into_output();
+        output.format(self.schema.config.error_formatter());
+        output
     }
 }
 
@@ -265,6 +360,9 @@ impl OutputUnit<ErrorDescription> {
     pub const fn error_description(&self) -> &ErrorDescription {
         &self.value
     }
+    pub(crate) fn into_error_description(self) -> ErrorDescription {
+        self.value
+    }
 }
 
 /// Annotations associated with an output unit.
@@ -313,32 +411,322 @@ impl<'a> From<serde_json::Value> for Annotations<'a> {
 }
 
 /// An error associated with an [`OutputUnit`]
-#[derive(serde::Serialize, Debug, Clone, PartialEq, Eq)]
-pub struct ErrorDescription(String);
+#[derive(Debug, Clone)]
+pub struct ErrorDescription {
+    message: String,
+    code: &'static str,
+    /// The missing property of `required` errors.
+    missing_property: Option<String>,
+    /// The error this description was created from, passed to the error formatter.
+    source: Option<Arc<ValidationError<'static>>>,
+}
 
 impl ErrorDescription {
+    pub(crate) fn new(code: &'static str, message: impl Into<String>) -> ErrorDescription {
+        ErrorDescription {
+            message: message.into(),
+            code,
+            missing_property: None,
+            source: None,
+        }
+    }
+    /// Override the message, keeping the code.
+    pub(crate) fn with_message(mut self, message: String) -> ErrorDescription {
+        self.source = self
+            .source
+            .take()
+            .and_then(|source| Arc::try_unwrap(source).ok())
+            .map(|source| Arc::new(source.with_message(message.as_str())));
+        self.message = message;
+        self
+    }
+    /// Replace the message with the one produced by `formatter`, if any.
+    ///
+    /// The source error is released, as it is not needed once the output is built.
+    pub(crate) fn format(&mut self, formatter: Option<&dyn ErrorFormatter>) {
+        if let Some(source) = self.source.take() {
+            if let Some(message) = formatter.and_then(|formatter| formatter.format(&source)) {
+                self.message = message;
+            }
+        }
+    }
     /// Returns the inner [`String`] of the error description.
     #[inline]
     pub fn into_inner(self) -> String {
-        self.0
+        self.message
+    }
+    /// The code of the error kind this description was created from.
+    ///
+    /// See [`ValidationErrorKind::code`](crate::error::ValidationErrorKind::code) for the
+    /// possible values. Descriptions created from plain text use `"custom"`.
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        self.code
+    }
+    pub(crate) fn missing_property(&self) -> Option<&str> {
+        self.missing_property.as_deref()
     }
 }
 
+impl PartialEq for ErrorDescription {
+    fn eq(&self, other: &Self) -> bool {
+        self.message == other.message
+            && self.code == other.code
+            && self.missing_property == other.missing_property
+    }
+}
+
+impl Eq for ErrorDescription {}
+
 impl fmt::Display for ErrorDescription {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(&self.0)
+        f.write_str(&self.message)
+    }
+}
+
+impl serde::Serialize for ErrorDescription {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.message)
     }
 }
 
 impl From<ValidationError<'_>> for ErrorDescription {
     fn from(e: ValidationError<'_>) -> Self {
-        ErrorDescription(e.to_string())
+        let mut description = ErrorDescription::new(e.kind.code(), e.to_string());
+        if let ValidationErrorKind::Required {
+            property: serde_json::Value::String(property),
+        } = &e.kind
+        {
+            description.missing_property = Some(property.clone());
+        }
+        description.source = Some(Arc::new(e.into_owned()));
+        description
     }
 }
 
 impl<'a> From<&'a str> for ErrorDescription {
     fn from(s: &'a str) -> Self {
-        ErrorDescription(s.to_string())
+        ErrorDescription::new("custom", s)
+    }
+}
+
+/// The "detailed" and "verbose" output formats. See the documentation for [`Output::detailed`]
+/// for examples of how to use this.
+///
+/// Every node of the hierarchy is an output unit which describes a place in the schema and a
+/// place in the instance. A unit may carry an error (for invalid units) or annotations (for valid
+/// ones) produced at that place, and the nested results of the subschemas below it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HierarchicalOutput<'a> {
+    valid: bool,
+    keyword_location: Location,
+    instance_location: Location,
+    absolute_keyword_location: Option<Uri<String>>,
+    value: Option<UnitValue<'a>>,
+    children: Vec<HierarchicalOutput<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum UnitValue<'a> {
+    Annotations(Annotations<'a>),
+    Error(ErrorDescription),
+}
+
+impl<'a> HierarchicalOutput<'a> {
+    /// Leave out the output units that are not needed to explain the result, as the "detailed"
+    /// output format suggests. Only units that agree with the validity of their parent are kept,
+    /// and units without their own error or annotation are replaced by their children if they have
+    /// at most one. The root unit is always kept.
+    fn condensed(mut self) -> HierarchicalOutput<'a> {
+        self.children = condense(self.children, self.valid);
+        self
+    }
+
+    fn format(&mut self, formatter: Option<&dyn ErrorFormatter>) {
+        if let Some(UnitValue::Error(error)) = &mut self.value {
+            error.format(formatter);
+        }
+        for child in &mut self.children {
+            child.format(formatter);
+        }
+    }
+
+    /// Whether the schema at this output unit was valid.
+    #[must_use]
+    pub const fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    /// The location in the schema of the keyword
+    pub const fn keyword_location(&self) -> &Location {
+        &self.keyword_location
+    }
+
+    /// The absolute location in the schema of the keyword. This will be
+    /// different to `keyword_location` if the schema is a resolved reference.
+    pub fn absolute_keyword_location(&self) -> Option<Uri<&str>> {
+        self.absolute_keyword_location
+            .as_ref()
+            .map(|uri| uri.borrow())
+    }
+
+    /// The location in the instance
+    pub const fn instance_location(&self) -> &Location {
+        &self.instance_location
+    }
+
+    /// The annotations produced at this output unit, if any.
+    #[must_use]
+    pub fn annotations(&self) -> Option<&Annotations<'a>> {
+        match &self.value {
+            Some(UnitValue::Annotations(annotations)) => Some(annotations),
+            _ => None,
+        }
+    }
+
+    /// The error produced at this output unit, if any.
+    #[must_use]
+    pub fn error_description(&self) -> Option<&ErrorDescription> {
+        match &self.value {
+            Some(UnitValue::Error(error)) => Some(error),
+            _ => None,
+        }
+    }
+
+    /// Nested output units of the subschemas below this one.
+    #[must_use]
+    pub fn children(&self) -> &[HierarchicalOutput<'a>] {
+        &self.children
+    }
+}
+
+fn condense(children: Vec<HierarchicalOutput<'_>>, valid: bool) -> Vec<HierarchicalOutput<'_>> {
+    children
+        .into_iter()
+        .filter(|child| child.valid == valid)
+        .flat_map(|mut child| {
+            child.children = condense(child.children, child.valid);
+            if child.value.is_none() && child.children.len() <= 1 {
+                child.children
+            } else {
+                vec![child]
+            }
+        })
+        .collect()
+}
+
+/// Output units of the schemas and keywords applied to an instance, recorded during the
+/// evaluation to build the "detailed" and "verbose" output formats.
+///
+/// Every application starts a new level with [`Trace::enter`]. The units recorded while it is
+/// evaluated become the children of its own unit once it is finished with [`Trace::exit`].
+pub(crate) struct Trace<'a> {
+    levels: RefCell<Vec<Vec<HierarchicalOutput<'a>>>>,
+}
+
+impl<'a> Trace<'a> {
+    fn new() -> Trace<'a> {
+        Trace {
+            levels: RefCell::new(vec![Vec::new()]),
+        }
+    }
+
+    pub(crate) fn enter(&self) {
+        self.levels.borrow_mut().push(Vec::new());
+    }
+
+    /// Record the unit of the finished application at the level of its parent.
+    pub(crate) fn exit(
+        &self,
+        keyword_location: Location,
+        instance_location: Location,
+        absolute_keyword_location: Option<Uri<String>>,
+        result: &PartialApplication<'a>,
+    ) {
+        let mut levels = self.levels.borrow_mut();
+        let mut children = levels.pop().expect("Trace levels are balanced");
+        let (valid, mut values): (_, Vec<_>) = match result {
+            PartialApplication::Valid { annotations, .. } => (
+                true,
+                annotations
+                    .iter()
+                    .cloned()
+                    .map(UnitValue::Annotations)
+                    .collect(),
+            ),
+            PartialApplication::Invalid { errors, .. } => (
+                false,
+                errors.iter().cloned().map(UnitValue::Error).collect(),
+            ),
+        };
+        let value = if values.len() == 1 {
+            values.pop()
+        } else {
+            // Every error of a keyword that fails in several ways gets its own unit
+            children.splice(
+                0..0,
+                values.into_iter().map(|value| HierarchicalOutput {
+                    valid,
+                    keyword_location: keyword_location.clone(),
+                    instance_location: instance_location.clone(),
+                    absolute_keyword_location: absolute_keyword_location.clone(),
+                    value: Some(value),
+                    children: Vec::new(),
+                }),
+            );
+            None
+        };
+        levels
+            .last_mut()
+            .expect("Trace levels are balanced")
+            .push(HierarchicalOutput {
+                valid,
+                keyword_location,
+                instance_location,
+                absolute_keyword_location,
+                value,
+                children,
+            });
+    }
+
+    /// Replace the units recorded at the current level with the ones produced by `replace`, e.g.
+    /// with errors from `errorMessage`.
+    pub(crate) fn replace(
+        &self,
+        units: VecDeque<OutputUnit<ErrorDescription>>,
+        replace: impl FnOnce(
+            VecDeque<OutputUnit<ErrorDescription>>,
+        ) -> VecDeque<OutputUnit<ErrorDescription>>,
+    ) -> VecDeque<OutputUnit<ErrorDescription>> {
+        // Recorded copies are released first, so the replaced errors are not shared with them
+        self.levels
+            .borrow_mut()
+            .last_mut()
+            .expect("Trace levels are balanced")
+            .clear();
+        let units = replace(units);
+        let mut levels = self.levels.borrow_mut();
+        *levels.last_mut().expect("Trace levels are balanced") = units
+            .iter()
+            .map(|unit| HierarchicalOutput {
+                valid: false,
+                keyword_location: unit.keyword_location.clone(),
+                instance_location: unit.instance_location.clone(),
+                absolute_keyword_location: unit.absolute_keyword_location.clone(),
+                value: Some(UnitValue::Error(unit.value.clone())),
+                children: Vec::new(),
+            })
+            .collect();
+        units
+    }
+
+    fn into_output(self) -> HierarchicalOutput<'a> {
+        let mut levels = self.levels.into_inner();
+        let mut root = levels.pop().expect("Trace levels are balanced");
+        root.pop().expect("The root schema is applied")
     }
 }
 
@@ -406,3 +794,32 @@ impl serde::Serialize for OutputUnit<ErrorDescription> {
         map_ser.end()
     }
 }
+
+impl<'a> serde::Serialize for HierarchicalOutput<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map_ser = serializer.serialize_map(None)?;
+        map_ser.serialize_entry("valid", &self.valid)?;
+        map_ser.serialize_entry("keywordLocation", self.keyword_location.as_str())?;
+        if let Some(absolute) = &self.absolute_keyword_location {
+            map_ser.serialize_entry("absoluteKeywordLocation", &absolute)?;
+        }
+        map_ser.serialize_entry("instanceLocation", self.instance_location.as_str())?;
+        match &self.value {
+            Some(UnitValue::Annotations(annotations)) => {
+                map_ser.serialize_entry("annotation", annotations)?;
+            }
+            Some(UnitValue::Error(error)) => {
+                map_ser.serialize_entry("error", error)?;
+            }
+            None => {}
+        }
+        if !self.children.is_empty() {
+            let key = if self.valid { "annotations" } else { "errors" };
+            map_ser.serialize_entry(key, &self.children)?;
+        }
+        map_ser.end()
+    }
+}
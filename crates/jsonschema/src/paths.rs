@@ -161,6 +161,10 @@ impl Location {
             }
         }
     }
+    /// Create a `Location` from a JSON pointer whose segments are already escaped.
+    pub(crate) fn from_escaped(pointer: String) -> Self {
+        Self(Arc::new(pointer))
+    }
     /// Get a string slice representing the location.
     pub fn as_str(&self) -> &str {
         &self.0
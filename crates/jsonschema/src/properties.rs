@@ -1,6 +1,8 @@
-use crate::{compiler, ecma, node::SchemaNode, paths::Location, validator::Validate as _};
+use crate::{
+    compiler, defaults::Defaults, node::SchemaNode, paths::LazyLocation, regex::Regex,
+    validator::Validate as _,
+};
 use ahash::AHashMap;
-use fancy_regex::Regex;
 use serde_json::{Map, Value};
 
 use crate::ValidationError;
@@ -11,6 +13,12 @@ pub(crate) type PatternedValidators = Vec<(Regex, SchemaNode)>;
 pub(crate) trait PropertiesValidatorsMap: Send + Sync {
     fn get_validator(&self, property: &str) -> Option<&SchemaNode>;
     fn get_key_validator(&self, property: &str) -> Option<(&String, &SchemaNode)>;
+    fn collect_defaults(
+        &self,
+        object: &Map<String, Value>,
+        location: &LazyLocation,
+        defaults: &mut Defaults,
+    );
 }
 
 // We're defining two different property validator map implementations, one for small map sizes and
@@ -42,6 +50,18 @@ impl PropertiesValidatorsMap for SmallValidatorsMap {
         }
         None
     }
+    fn collect_defaults(
+        &self,
+        object: &Map<String, Value>,
+        location: &LazyLocation,
+        defaults: &mut Defaults,
+    ) {
+        defaults.collect_properties(
+            self.iter().map(|(prop, node)| (prop, node)),
+            object,
+            location,
+        );
+    }
 }
 
 impl PropertiesValidatorsMap for BigValidatorsMap {
@@ -54,6 +74,15 @@ impl PropertiesValidatorsMap for BigValidatorsMap {
     fn get_key_validator(&self, property: &str) -> Option<(&String, &SchemaNode)> {
         self.get_key_value(property)
     }
+
+    fn collect_defaults(
+        &self,
+        object: &Map<String, Value>,
+        location: &LazyLocation,
+        defaults: &mut Defaults,
+    ) {
+        defaults.collect_properties(self.iter(), object, location);
+    }
 }
 
 pub(crate) fn compile_small_map<'a>(
@@ -112,19 +141,11 @@ pub(crate) fn compile_patterns<'a>(
     let mut compiled_patterns = Vec::with_capacity(obj.len());
     for (pattern, subschema) in obj {
         let pctx = kctx.new_at_location(pattern.as_str());
-        if let Ok(Ok(compiled_pattern)) =
-            ecma::to_rust_regex(pattern).map(|pattern| Regex::new(&pattern))
-        {
-            let node = compiler::compile(&pctx, pctx.as_resource_ref(subschema))?;
-            compiled_patterns.push((compiled_pattern, node));
-        } else {
-            return Err(ValidationError::format(
-                Location::new(),
-                kctx.location().clone(),
-                subschema,
-                "regex",
-            ));
-        }
+        let compiled_pattern = kctx.compile_pattern(pattern).map_err(|error| {
+            error.into_validation_error(kctx.location().clone(), subschema, pattern)
+        })?;
+        let node = compiler::compile(&pctx, pctx.as_resource_ref(subschema))?;
+        compiled_patterns.push((compiled_pattern, node));
     }
     Ok(compiled_patterns)
 }
@@ -0,0 +1,394 @@
+//! Regular expression engines used by the `pattern` and `patternProperties` keywords.
+use crate::{ecma, paths::Location, ValidationError};
+use ahash::AHashMap;
+use regex_syntax::ast::{parse::Parser, ErrorKind};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+const DEFAULT_CACHE_CAPACITY: usize = 1024;
+
+/// Configuration of the regular expression engine used by `pattern` and `patternProperties`.
+///
+/// Patterns are first translated from ECMA 262 syntax and then compiled by one of the engines:
+///
+/// - [`fancy-regex`](https://docs.rs/fancy-regex) (default) supports look-around assertions and
+///   backreferences. It uses backtracking, which is bounded by the backtrack limit.
+/// - [`regex`](https://docs.rs/regex) guarantees linear-time matching. Patterns with look-around
+///   assertions or backreferences are rejected when the validator is built.
+///
+/// # Example
+///
+/// ```rust
+/// use jsonschema::PatternOptions;
+/// use serde_json::json;
+///
+/// let schema = json!({"pattern": "^(?!internal)"});
+/// let result = jsonschema::options()
+///     .with_pattern_options(PatternOptions::regex())
+///     .build(&schema);
+/// assert!(result.is_err());
+///
+/// let validator = jsonschema::options()
+///     .with_pattern_options(PatternOptions::fancy_regex().backtrack_limit(10_000))
+///     .build(&schema)
+///     .expect("Invalid schema");
+/// assert!(!validator.is_valid(&json!("internal-api")));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PatternOptions {
+    engine: PatternEngine,
+    backtrack_limit: Option<usize>,
+    size_limit: Option<usize>,
+    dfa_size_limit: Option<usize>,
+    cache_capacity: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+enum PatternEngine {
+    FancyRegex,
+    Regex,
+}
+
+impl Default for PatternOptions {
+    fn default() -> Self {
+        PatternOptions::fancy_regex()
+    }
+}
+
+impl PatternOptions {
+    /// Use the backtracking `fancy-regex` engine.
+    #[must_use]
+    pub const fn fancy_regex() -> Self {
+        PatternOptions {
+            engine: PatternEngine::FancyRegex,
+            backtrack_limit: None,
+            size_limit: None,
+            dfa_size_limit: None,
+            cache_capacity: DEFAULT_CACHE_CAPACITY,
+        }
+    }
+    /// Use the linear-time `regex` engine.
+    #[must_use]
+    pub const fn regex() -> Self {
+        PatternOptions {
+            engine: PatternEngine::Regex,
+            backtrack_limit: None,
+            size_limit: None,
+            dfa_size_limit: None,
+            cache_capacity: DEFAULT_CACHE_CAPACITY,
+        }
+    }
+    /// Set the maximum number of backtracking steps for a single match.
+    /// Exceeding it results in a `BacktrackLimitExceeded` validation error.
+    ///
+    /// Only used by the `fancy-regex` engine, as the `regex` engine never backtracks.
+    #[must_use]
+    pub const fn backtrack_limit(mut self, limit: usize) -> Self {
+        self.backtrack_limit = Some(limit);
+        self
+    }
+    /// Set the approximate size limit, in bytes, of a compiled pattern.
+    #[must_use]
+    pub const fn size_limit(mut self, limit: usize) -> Self {
+        self.size_limit = Some(limit);
+        self
+    }
+    /// Set the approximate size limit, in bytes, of the cache used by the lazy DFA.
+    #[must_use]
+    pub const fn dfa_size_limit(mut self, limit: usize) -> Self {
+        self.dfa_size_limit = Some(limit);
+        self
+    }
+
+    /// Set the maximum number of compiled patterns cached by a validator.
+    ///
+    /// Each validator caches patterns used by `pattern`, `patternProperties` and the `regex`
+    /// format, so the same pattern is compiled once. When the cache is full, the least recently used entries are
+    /// evicted. `0` disables caching. Defaults to 1024.
+    #[must_use]
+    pub const fn cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache_capacity = capacity;
+        self
+    }
+
+    /// Compile an ECMA 262 pattern.
+    pub(crate) fn compile(&self, pattern: &str) -> Result<Regex, PatternError> {
+        let translated = ecma::to_rust_regex(pattern).map_err(|()| PatternError::Invalid)?;
+        match self.engine {
+            PatternEngine::FancyRegex => {
+                let mut builder = fancy_regex::RegexBuilder::new(&translated);
+                if let Some(limit) = self.backtrack_limit {
+                    builder.backtrack_limit(limit);
+                }
+                if let Some(limit) = self.size_limit {
+                    builder.delegate_size_limit(limit);
+                }
+                if let Some(limit) = self.dfa_size_limit {
+                    builder.delegate_dfa_size_limit(limit);
+                }
+                builder
+                    .build()
+                    .map(|regex| Regex::FancyRegex(Arc::new(regex)))
+                    .map_err(|error| match error {
+                        fancy_regex::Error::CompileError(
+                            fancy_regex::CompileError::InnerError(error),
+                        ) if error.size_limit().is_some() => PatternError::SizeLimitExceeded(
+                            error.size_limit().expect("Checked above"),
+                        ),
+                        _ => PatternError::Invalid,
+                    })
+            }
+            PatternEngine::Regex => {
+                let mut builder = ::regex::RegexBuilder::new(&translated);
+                if let Some(limit) = self.size_limit {
+                    builder.size_limit(limit);
+                }
+                if let Some(limit) = self.dfa_size_limit {
+                    builder.dfa_size_limit(limit);
+                }
+                builder
+                    .build()
+                    .map(Regex::Regex)
+                    .map_err(|error| match error {
+                        ::regex::Error::CompiledTooBig(limit) => {
+                            PatternError::SizeLimitExceeded(limit)
+                        }
+                        _ => PatternError::Unsupported(unsupported_reason(&translated, &error)),
+                    })
+            }
+        }
+    }
+}
+
+fn unsupported_reason(pattern: &str, error: &::regex::Error) -> String {
+    match Parser::new().parse(pattern) {
+        Err(error) if *error.kind() == ErrorKind::UnsupportedLookAround => {
+            "look-around assertions are not supported by the `regex` engine".to_string()
+        }
+        Err(error) if *error.kind() == ErrorKind::UnsupportedBackreference => {
+            "backreferences are not supported by the `regex` engine".to_string()
+        }
+        _ => error.to_string(),
+    }
+}
+
+/// An error that happened while compiling a pattern.
+pub(crate) enum PatternError {
+    /// The pattern is not a valid ECMA 262 regular expression.
+    Invalid,
+    /// The pattern is valid, but can't be compiled by the configured engine.
+    Unsupported(String),
+    /// The compiled pattern exceeds the configured size limit, in bytes.
+    SizeLimitExceeded(usize),
+}
+
+impl PatternError {
+    /// Convert into an error for the schema value containing the pattern.
+    pub(crate) fn into_validation_error<'a>(
+        self,
+        location: Location,
+        instance: &'a Value,
+        pattern: &str,
+    ) -> ValidationError<'a> {
+        match self {
+            PatternError::Invalid => {
+                ValidationError::format(Location::new(), location, instance, "regex")
+            }
+            PatternError::Unsupported(reason) => ValidationError::unsupported_regex(
+                Location::new(),
+                location,
+                instance,
+                pattern.to_string(),
+                reason,
+            ),
+            PatternError::SizeLimitExceeded(limit) => ValidationError::unsupported_regex(
+                Location::new(),
+                location,
+                instance,
+                pattern.to_string(),
+                format!("compiled pattern exceeds the size limit of {limit} bytes"),
+            ),
+        }
+    }
+}
+
+/// A cache of compiled schema patterns shared by all keywords of a single validator.
+#[derive(Debug)]
+pub(crate) struct PatternCache {
+    options: PatternOptions,
+    entries: Mutex<CacheEntries>,
+}
+
+#[derive(Debug, Default)]
+struct CacheEntries {
+    /// Compiled patterns with the tick of their last use.
+    patterns: AHashMap<String, (Regex, u64)>,
+    tick: u64,
+}
+
+impl PatternCache {
+    pub(crate) fn new(options: PatternOptions) -> Self {
+        PatternCache {
+            options,
+            entries: Mutex::default(),
+        }
+    }
+
+    /// Get a compiled pattern from the cache or compile & cache it.
+    pub(crate) fn get_or_compile(&self, pattern: &str) -> Result<Regex, PatternError> {
+        let capacity = self.options.cache_capacity;
+        if capacity == 0 {
+            return self.options.compile(pattern);
+        }
+        {
+            let mut entries = self.entries.lock().expect("Lock is poisoned");
+            entries.tick += 1;
+            let tick = entries.tick;
+            if let Some((regex, last_used)) = entries.patterns.get_mut(pattern) {
+                *last_used = tick;
+                return Ok(regex.clone());
+            }
+        }
+        // Compile without holding the lock, patterns may be expensive to compile
+        let regex = self.options.compile(pattern)?;
+        let mut entries = self.entries.lock().expect("Lock is poisoned");
+        entries.tick += 1;
+        let tick = entries.tick;
+        if !entries.patterns.contains_key(pattern) && entries.patterns.len() >= capacity {
+            let least_recently_used = entries
+                .patterns
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(pattern, _)| pattern.clone());
+            if let Some(least_recently_used) = least_recently_used {
+                entries.patterns.remove(&least_recently_used);
+            }
+        }
+        entries
+            .patterns
+            .insert(pattern.to_string(), (regex.clone(), tick));
+        Ok(regex)
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.entries
+            .lock()
+            .expect("Lock is poisoned")
+            .patterns
+            .len()
+    }
+}
+
+/// A compiled pattern. Cloning is cheap.
+#[derive(Debug, Clone)]
+pub(crate) enum Regex {
+    FancyRegex(Arc<fancy_regex::Regex>),
+    Regex(::regex::Regex),
+}
+
+impl Regex {
+    /// Check whether the pattern matches the given text.
+    ///
+    /// Only the `fancy-regex` engine may fail, when the backtrack limit is exceeded.
+    #[inline]
+    pub(crate) fn is_match(&self, text: &str) -> Result<bool, fancy_regex::Error> {
+        match self {
+            Regex::FancyRegex(regex) => regex.is_match(text),
+            Regex::Regex(regex) => Ok(regex.is_match(text)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PatternCache, PatternError, PatternOptions};
+    use test_case::test_case;
+
+    #[test_case("^\\d+$", "123", true)]
+    #[test_case("^\\d+$", "12a", false)]
+    #[test_case("^\\w+$", "é", false)]
+    #[test_case("^[a-z]{2,3}$", "abc", true)]
+    fn engines_agree(pattern: &str, text: &str, expected: bool) {
+        for options in [PatternOptions::fancy_regex(), PatternOptions::regex()] {
+            let Ok(regex) = options.compile(pattern) else {
+                panic!("Failed to compile {pattern}");
+            };
+            assert_eq!(regex.is_match(text).expect("No backtracking"), expected);
+        }
+    }
+
+    #[test_case(
+        "^(?!eo:)",
+        "look-around assertions are not supported by the `regex` engine"
+    )]
+    #[test_case(
+        "(?<=a)b",
+        "look-around assertions are not supported by the `regex` engine"
+    )]
+    #[test_case("(a)\\1", "backreferences are not supported by the `regex` engine")]
+    fn regex_engine_rejects(pattern: &str, expected: &str) {
+        match PatternOptions::regex().compile(pattern) {
+            Err(PatternError::Unsupported(reason)) => assert_eq!(reason, expected),
+            _ => panic!("{pattern} should not be supported"),
+        }
+        assert!(PatternOptions::fancy_regex().compile(pattern).is_ok());
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        for options in [PatternOptions::fancy_regex(), PatternOptions::regex()] {
+            assert!(matches!(options.compile("["), Err(PatternError::Invalid)));
+        }
+    }
+
+    #[test]
+    fn size_limit() {
+        for options in [PatternOptions::fancy_regex(), PatternOptions::regex()] {
+            assert!(matches!(
+                options.size_limit(10).compile("a{100}"),
+                Err(PatternError::SizeLimitExceeded(10))
+            ));
+        }
+    }
+
+    #[test]
+    fn backtrack_limit() {
+        let regex = PatternOptions::fancy_regex()
+            .backtrack_limit(10)
+            .compile("^(a|a)*(?=b)")
+            .unwrap_or_else(|_| panic!("Valid pattern"));
+        assert!(regex.is_match("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaac").is_err());
+    }
+
+    #[test]
+    fn cache_evicts_least_recently_used() {
+        let cache = PatternCache::new(PatternOptions::default().cache_capacity(2));
+        for pattern in ["a", "b", "a", "c"] {
+            assert!(cache.get_or_compile(pattern).is_ok());
+        }
+        assert_eq!(cache.len(), 2);
+        let entries = cache.entries.lock().expect("Lock is poisoned");
+        assert!(entries.patterns.contains_key("a"));
+        assert!(!entries.patterns.contains_key("b"));
+        assert!(entries.patterns.contains_key("c"));
+    }
+
+    #[test]
+    fn cache_disabled() {
+        let cache = PatternCache::new(PatternOptions::regex().cache_capacity(0));
+        assert!(cache.get_or_compile("a").is_ok());
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn cache_skips_invalid() {
+        let cache = PatternCache::new(PatternOptions::default());
+        assert!(matches!(
+            cache.get_or_compile("["),
+            Err(PatternError::Invalid)
+        ));
+        assert_eq!(cache.len(), 0);
+    }
+}
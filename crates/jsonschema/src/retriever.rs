@@ -1,8 +1,14 @@
 //! Logic for retrieving external resources.
+#[cfg(feature = "resolve-async")]
+use referencing::AsyncRetrieve;
 use referencing::{Retrieve, Uri};
 use serde_json::Value;
 
-pub(crate) struct DefaultRetriever;
+/// The retriever used unless another one is configured.
+///
+/// It fetches `http(s)` URIs with the `resolve-http` feature and reads `file` URIs with the
+/// `resolve-file` feature. Custom retrievers may delegate to it for URIs they don't handle.
+pub struct DefaultRetriever;
 
 impl Retrieve for DefaultRetriever {
     #[allow(unused)]
@@ -24,20 +30,7 @@ impl Retrieve for DefaultRetriever {
             "file" => {
                 #[cfg(any(feature = "resolve-file", test))]
                 {
-                    let path = uri.path().as_str();
-                    let path = {
-                        #[cfg(windows)]
-                        {
-                            // Remove the leading slash and replace forward slashes with backslashes
-                            let path = path.trim_start_matches('/').replace('/', "\\");
-                            std::path::PathBuf::from(path)
-                        }
-                        #[cfg(not(windows))]
-                        {
-                            std::path::PathBuf::from(path)
-                        }
-                    };
-                    let file = std::fs::File::open(path)?;
+                    let file = std::fs::File::open(path_from_uri(uri))?;
                     Ok(serde_json::from_reader(file)?)
                 }
                 #[cfg(not(any(feature = "resolve-file", test)))]
@@ -50,6 +43,59 @@ impl Retrieve for DefaultRetriever {
     }
 }
 
+#[cfg(feature = "resolve-async")]
+#[async_trait::async_trait]
+impl AsyncRetrieve for DefaultRetriever {
+    #[allow(unused)]
+    async fn retrieve(
+        &self,
+        uri: &Uri<&str>,
+    ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            Err("External references are not supported in WASM".into())
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        match uri.scheme().as_str() {
+            "http" | "https" => {
+                #[cfg(any(feature = "resolve-http", test))]
+                {
+                    Ok(reqwest::get(uri.as_str()).await?.json().await?)
+                }
+                #[cfg(not(any(feature = "resolve-http", test)))]
+                Err("`resolve-http` feature or a custom resolver is required to resolve external schemas via HTTP".into())
+            }
+            "file" => {
+                #[cfg(any(feature = "resolve-file", test))]
+                {
+                    let contents = tokio::fs::read(path_from_uri(uri)).await?;
+                    Ok(serde_json::from_slice(&contents)?)
+                }
+                #[cfg(not(any(feature = "resolve-file", test)))]
+                {
+                    Err("`resolve-file` feature or a custom resolver is required to resolve external schemas via files".into())
+                }
+            }
+            scheme => Err(format!("Unknown scheme {scheme}").into()),
+        }
+    }
+}
+
+#[cfg(all(not(target_arch = "wasm32"), any(feature = "resolve-file", test)))]
+fn path_from_uri(uri: &Uri<&str>) -> std::path::PathBuf {
+    let path = uri.path().as_str();
+    #[cfg(windows)]
+    {
+        // Remove the leading slash and replace forward slashes with backslashes
+        let path = path.trim_start_matches('/').replace('/', "\\");
+        std::path::PathBuf::from(path)
+    }
+    #[cfg(not(windows))]
+    {
+        std::path::PathBuf::from(path)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -167,4 +213,118 @@ mod tests {
         #[cfg(target_arch = "wasm32")]
         assert!(error.contains("External references are not supported in WASM"));
     }
+
+    #[cfg(feature = "resolve-async")]
+    mod async_retrieve {
+        use crate::{AsyncRetrieve, Retrieve, Uri};
+        use serde_json::{json, Value};
+        #[cfg(not(target_arch = "wasm32"))]
+        use std::io::Write;
+
+        struct PanickingRetriever;
+
+        impl Retrieve for PanickingRetriever {
+            fn retrieve(
+                &self,
+                _: &Uri<&str>,
+            ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+                panic!("The synchronous retriever should not be used")
+            }
+        }
+
+        struct InMemoryRetriever;
+
+        #[async_trait::async_trait]
+        impl AsyncRetrieve for InMemoryRetriever {
+            async fn retrieve(
+                &self,
+                uri: &Uri<&str>,
+            ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+                match uri.as_str() {
+                    "https://example.com/person.json" => Ok(json!({
+                        "type": "object",
+                        "properties": {
+                            "name": {"$ref": "https://example.com/name.json"}
+                        },
+                        "required": ["name"]
+                    })),
+                    "https://example.com/name.json" => Ok(json!({"type": "string"})),
+                    _ => Err(format!("Schema not found: {uri}").into()),
+                }
+            }
+        }
+
+        #[tokio::test]
+        async fn test_build_async() {
+            let schema = json!({"$ref": "https://example.com/person.json"});
+            let validator = crate::options()
+                .with_retriever(PanickingRetriever)
+                .with_async_retriever(InMemoryRetriever)
+                .build_async(&schema)
+                .await
+                .expect("Schema compilation failed");
+            assert!(validator.is_valid(&json!({"name": "Alice"})));
+            assert!(!validator.is_valid(&json!({"name": 42})));
+            assert!(!validator.is_valid(&json!({})));
+        }
+
+        #[tokio::test]
+        async fn test_build_async_unretrievable() {
+            let schema = json!({"$ref": "https://example.com/unknown.json"});
+            let error = crate::options()
+                .with_async_retriever(InMemoryRetriever)
+                .build_async(&schema)
+                .await
+                .expect_err("Should fail");
+            assert_eq!(error.to_string(), "Resource 'https://example.com/unknown.json' is not present in a registry and retrieving it failed: Schema not found: https://example.com/unknown.json");
+        }
+
+        #[tokio::test]
+        async fn test_build_async_dialect() {
+            let dialect = crate::Dialect::new(
+                "https://example.com/dialect",
+                json!({
+                    "$schema": "https://json-schema.org/draft/2020-12/schema",
+                    "$id": "https://example.com/dialect",
+                    "properties": {"title": {"$ref": "https://example.com/name.json"}}
+                }),
+            );
+            let build = |title: Value| {
+                let dialect = dialect.clone();
+                async move {
+                    let schema = json!({"$schema": "https://example.com/dialect", "title": title});
+                    crate::options()
+                        .with_dialect(dialect)
+                        .with_retriever(PanickingRetriever)
+                        .with_async_retriever(InMemoryRetriever)
+                        .build_async(&schema)
+                        .await
+                }
+            };
+            assert!(build(json!("Person")).await.is_ok());
+            assert!(build(json!(42)).await.is_err());
+        }
+
+        #[test]
+        fn test_build_async_is_send() {
+            fn assert_send<T: Send>(_: T) {}
+            let schema = json!({});
+            assert_send(crate::options().build_async(&schema));
+        }
+
+        #[tokio::test]
+        #[cfg(not(target_arch = "wasm32"))]
+        async fn test_retrieve_from_file_async() {
+            let mut temp_file = tempfile::NamedTempFile::new().expect("Failed to create temp file");
+            write!(temp_file, "{}", json!({"type": "integer"}))
+                .expect("Failed to write to temp file");
+            let schema = json!({"$ref": super::path_to_uri(temp_file.path())});
+            let validator = crate::options()
+                .build_async(&schema)
+                .await
+                .expect("Schema compilation failed");
+            assert!(validator.is_valid(&json!(1)));
+            assert!(!validator.is_valid(&json!("1")));
+        }
+    }
 }
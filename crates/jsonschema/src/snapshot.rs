@@ -0,0 +1,499 @@
+//! A versioned binary format for storing validators and loading them without retrieving external
+//! resources or validating the schema against its meta-schema.
+//!
+//! The format consists of a magic header, a little-endian `u32` format version and a JSON payload
+//! with the resolved resources, the root schema and the options that can be serialized. The
+//! compiled validators are not stored, they are built again from the schema when loading.
+use crate::{
+    compiler, ConditionalDefaults, PatternOptions, ValidationError, ValidationOptions, Validator,
+};
+use ahash::AHashSet;
+use referencing::{uri, Draft, Uri, SPECIFICATIONS};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{borrow::Cow, error, fmt};
+
+const MAGIC: &[u8; 10] = b"JSONSCHEMA";
+/// Current version of the format. Bump it on any incompatible change to the payload.
+const FORMAT_VERSION: u32 = 1;
+const HEADER_LENGTH: usize = MAGIC.len() + 4;
+
+/// An error that can occur when loading a validator with [`Validator::from_bytes`].
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The input does not start with the expected header.
+    InvalidHeader,
+    /// The input was produced by an incompatible version of the format.
+    UnsupportedVersion {
+        /// The version supported by this build.
+        expected: u32,
+        /// The version found in the input.
+        found: u32,
+    },
+    /// The payload is malformed.
+    Malformed(serde_json::Error),
+    /// The stored schema can not be compiled with the given options.
+    Build(Box<ValidationError<'static>>),
+    /// The validator uses a draft that the format can't store.
+    UnsupportedDraft(Draft),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::InvalidHeader => f.write_str("Input is not a serialized validator"),
+            SnapshotError::UnsupportedVersion { expected, found } => write!(
+                f,
+                "Unsupported serialized validator version: {found} (expected {expected})"
+            ),
+            SnapshotError::Malformed(err) => write!(f, "Malformed serialized validator: {err}"),
+            SnapshotError::Build(err) => write!(f, "Failed to build validator: {err}"),
+            SnapshotError::UnsupportedDraft(draft) => {
+                write!(f, "Unsupported draft for a serialized validator: {draft:?}")
+            }
+        }
+    }
+}
+
+impl error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            SnapshotError::InvalidHeader
+            | SnapshotError::UnsupportedVersion { .. }
+            | SnapshotError::UnsupportedDraft(_) => None,
+            SnapshotError::Malformed(err) => Some(err),
+            SnapshotError::Build(err) => Some(&**err),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Snapshot<'a> {
+    pub(crate) draft: StoredDraft,
+    pub(crate) base_uri: Cow<'a, str>,
+    pub(crate) schema: Cow<'a, Value>,
+    pub(crate) resources: Vec<SnapshotResource<'a>>,
+    pub(crate) options: SnapshotOptions,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SnapshotResource<'a> {
+    pub(crate) uri: Cow<'a, str>,
+    pub(crate) draft: StoredDraft,
+    pub(crate) contents: Cow<'a, Value>,
+}
+
+/// A draft together with its code in the payload.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub(crate) struct StoredDraft {
+    draft: Draft,
+    code: u8,
+}
+
+impl StoredDraft {
+    fn new(draft: Draft) -> Result<StoredDraft, SnapshotError> {
+        let code = match draft {
+            Draft::Draft4 => 4,
+            Draft::Draft6 => 6,
+            Draft::Draft7 => 7,
+            Draft::Draft201909 => 19,
+            Draft::Draft202012 => 20,
+            _ => return Err(SnapshotError::UnsupportedDraft(draft)),
+        };
+        Ok(StoredDraft { draft, code })
+    }
+    pub(crate) const fn draft(self) -> Draft {
+        self.draft
+    }
+}
+
+impl TryFrom<u8> for StoredDraft {
+    type Error = String;
+
+    fn try_from(code: u8) -> Result<Self, Self::Error> {
+        let draft = match code {
+            4 => Draft::Draft4,
+            6 => Draft::Draft6,
+            7 => Draft::Draft7,
+            19 => Draft::Draft201909,
+            20 => Draft::Draft202012,
+            other => return Err(format!("unknown draft: {other}")),
+        };
+        Ok(StoredDraft { draft, code })
+    }
+}
+
+impl From<StoredDraft> for u8 {
+    fn from(draft: StoredDraft) -> u8 {
+        draft.code
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SnapshotOptions {
+    pub(crate) validate_formats: Option<bool>,
+    pub(crate) ignore_unknown_formats: bool,
+    pub(crate) conditional_defaults: ConditionalDefaults,
+    pub(crate) pattern_options: PatternOptions,
+    #[serde(default)]
+    pub(crate) error_messages: bool,
+    #[serde(default)]
+    pub(crate) content_schema: bool,
+}
+
+impl SnapshotOptions {
+    fn new(config: &ValidationOptions) -> Self {
+        SnapshotOptions {
+            validate_formats: config.validate_formats(),
+            ignore_unknown_formats: config.are_unknown_formats_ignored(),
+            conditional_defaults: config.conditional_defaults(),
+            pattern_options: config.pattern_options(),
+            error_messages: config.are_error_messages_used(),
+            content_schema: config.validates_content_schema(),
+        }
+    }
+    pub(crate) fn apply(&self, config: &mut ValidationOptions) {
+        if let Some(yes) = self.validate_formats {
+            config.should_validate_formats(yes);
+        }
+        config
+            .should_ignore_unknown_formats(self.ignore_unknown_formats)
+            .with_conditional_defaults(self.conditional_defaults)
+            .with_pattern_options(self.pattern_options)
+            .should_use_error_messages(self.error_messages)
+            .should_validate_content_schema(self.content_schema);
+    }
+}
+
+pub(crate) fn to_bytes(validator: &Validator) -> Result<Vec<u8>, SnapshotError> {
+    let schema = validator
+        .registry
+        .try_resolver(&validator.base_uri)
+        .and_then(|resolver| resolver.lookup("#"))
+        .expect("The root schema is always registered")
+        .contents();
+    let specifications: Vec<_> = SPECIFICATIONS
+        .resources()
+        .map(|(_, resource)| resource)
+        .collect();
+    let stored: Vec<_> = validator
+        .registry
+        .resources()
+        .filter(|(_, resource)| {
+            // Meta-schemas are available in every registry, and the root schema is stored separately
+            !std::ptr::eq(resource.contents(), schema)
+                && !specifications
+                    .iter()
+                    .any(|specification| std::ptr::eq(*specification, *resource))
+        })
+        .collect();
+    // Resources embedded in other documents are registered again when these are loaded
+    let mut embedded = AHashSet::new();
+    if let Ok(base_uri) = uri::from_str(&validator.base_uri) {
+        collect_embedded(&base_uri, validator.draft, schema, &mut embedded);
+    }
+    for (uri, resource) in &stored {
+        collect_embedded(uri, resource.draft(), resource.contents(), &mut embedded);
+    }
+    let mut resources = stored
+        .into_iter()
+        .filter(|(uri, _)| !embedded.contains(*uri))
+        .map(|(uri, resource)| {
+            Ok(SnapshotResource {
+                uri: Cow::Borrowed(uri.as_str()),
+                draft: StoredDraft::new(resource.draft())?,
+                contents: Cow::Borrowed(resource.contents()),
+            })
+        })
+        .collect::<Result<Vec<_>, SnapshotError>>()?;
+    // Registry iteration order is unspecified, sorting keeps the output deterministic
+    resources.sort_unstable_by(|left, right| left.uri.cmp(&right.uri));
+    let snapshot = Snapshot {
+        draft: StoredDraft::new(validator.draft)?,
+        base_uri: Cow::Borrowed(&validator.base_uri),
+        schema: Cow::Borrowed(schema),
+        resources,
+        options: SnapshotOptions::new(&validator.config),
+    };
+    let mut bytes = Vec::with_capacity(HEADER_LENGTH);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    serde_json::to_writer(&mut bytes, &snapshot).expect("Serializing JSON values never fails");
+    Ok(bytes)
+}
+
+/// Collect the URIs of resources with their own identifiers nested in `contents`.
+fn collect_embedded(
+    base_uri: &Uri<String>,
+    draft: Draft,
+    contents: &Value,
+    embedded: &mut AHashSet<Uri<String>>,
+) {
+    for subresource in draft.subresources_of(contents) {
+        let draft = draft.detect(subresource).unwrap_or(draft);
+        match draft
+            .create_resource_ref(subresource)
+            .id()
+            .and_then(|id| uri::resolve_against(&base_uri.borrow(), id).ok())
+        {
+            Some(uri) => {
+                collect_embedded(&uri, draft, subresource, embedded);
+                embedded.insert(uri);
+            }
+            None => collect_embedded(base_uri, draft, subresource, embedded),
+        }
+    }
+}
+
+pub(crate) fn from_bytes(
+    config: ValidationOptions,
+    bytes: &[u8],
+) -> Result<Validator, SnapshotError> {
+    if bytes.len() < HEADER_LENGTH || !bytes.starts_with(MAGIC) {
+        return Err(SnapshotError::InvalidHeader);
+    }
+    let mut version = [0; 4];
+    version.copy_from_slice(&bytes[MAGIC.len()..HEADER_LENGTH]);
+    let version = u32::from_le_bytes(version);
+    if version != FORMAT_VERSION {
+        return Err(SnapshotError::UnsupportedVersion {
+            expected: FORMAT_VERSION,
+            found: version,
+        });
+    }
+    let snapshot: Snapshot<'_> =
+        serde_json::from_slice(&bytes[HEADER_LENGTH..]).map_err(SnapshotError::Malformed)?;
+    compiler::build_from_snapshot(config, snapshot)
+        .map_err(|error| SnapshotError::Build(Box::new(error)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SnapshotError, FORMAT_VERSION, HEADER_LENGTH, MAGIC};
+    use crate::{Draft, PatternOptions, Retrieve, Uri, Validator};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    struct PanickingRetriever;
+
+    impl Retrieve for PanickingRetriever {
+        fn retrieve(
+            &self,
+            _: &Uri<&str>,
+        ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+            panic!("Loaded validators should not retrieve resources")
+        }
+    }
+
+    struct InMemoryRetriever;
+
+    impl Retrieve for InMemoryRetriever {
+        fn retrieve(
+            &self,
+            uri: &Uri<&str>,
+        ) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+            match uri.path().as_str() {
+                "/person.json" => Ok(json!({
+                    "type": "object",
+                    "properties": {
+                        "name": {"$ref": "#/$defs/name"},
+                        "address": {"$ref": "address.json"}
+                    },
+                    "$defs": {"name": {"type": "string"}}
+                })),
+                "/address.json" => Ok(json!({
+                    "$id": "https://example.com/address.json",
+                    "required": ["city"],
+                    "properties": {"city": {"$ref": "#city"}},
+                    "$defs": {"city": {"$anchor": "city", "minLength": 1}}
+                })),
+                _ => Err("Unknown resource".into()),
+            }
+        }
+    }
+
+    fn roundtrip(validator: &Validator) -> Validator {
+        Validator::from_bytes(&validator.to_bytes().expect("Unsupported draft"))
+            .expect("Invalid snapshot")
+    }
+
+    #[test_case(&json!({"type": "string", "minLength": 2}), &json!("ab"), &json!("a"); "keywords")]
+    #[test_case(&json!({"$id": "https://example.com/root.json", "items": {"$ref": "#item"}, "$defs": {"item": {"$anchor": "item", "type": "integer"}}}), &json!([1, 2]), &json!([1, "2"]); "anchors")]
+    #[test_case(&json!({"$ref": "#/$defs/node", "$defs": {"node": {"properties": {"next": {"$ref": "#/$defs/node"}}, "required": ["value"]}}}), &json!({"value": 1, "next": {"value": 2}}), &json!({"value": 1, "next": {}}); "recursive references")]
+    #[test_case(&json!(false), &json!(null), &json!(1); "boolean schema")]
+    fn roundtrip_preserves_behavior(schema: &Value, valid: &Value, invalid: &Value) {
+        let validator = crate::validator_for(schema).expect("Invalid schema");
+        let loaded = roundtrip(&validator);
+        assert_eq!(loaded.is_valid(valid), validator.is_valid(valid));
+        assert_eq!(loaded.is_valid(invalid), validator.is_valid(invalid));
+        let expected: Vec<_> = validator
+            .validate(invalid)
+            .expect_err("Should fail")
+            .map(|error| (error.to_string(), error.schema_path.to_string()))
+            .collect();
+        let actual: Vec<_> = loaded
+            .validate(invalid)
+            .expect_err("Should fail")
+            .map(|error| (error.to_string(), error.schema_path.to_string()))
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn external_resources_are_not_retrieved() {
+        let schema = json!({"$ref": "https://example.com/person.json"});
+        let validator = crate::options()
+            .with_retriever(InMemoryRetriever)
+            .build(&schema)
+            .expect("Invalid schema");
+        let bytes = validator.to_bytes().expect("Unsupported draft");
+        let loaded = crate::options()
+            .with_retriever(PanickingRetriever)
+            .from_bytes(&bytes)
+            .expect("Invalid snapshot");
+        assert!(loaded.is_valid(&json!({"name": "Alice", "address": {"city": "Paris"}})));
+        assert!(!loaded.is_valid(&json!({"name": 1})));
+        assert!(!loaded.is_valid(&json!({"address": {}})));
+        assert!(!loaded.is_valid(&json!({"address": {"city": ""}})));
+    }
+
+    #[test]
+    fn output_is_deterministic() {
+        let schema = json!({"$ref": "https://example.com/person.json"});
+        let validator = crate::options()
+            .with_retriever(InMemoryRetriever)
+            .build(&schema)
+            .expect("Invalid schema");
+        let bytes = validator.to_bytes().expect("Unsupported draft");
+        assert_eq!(
+            roundtrip(&validator).to_bytes().expect("Unsupported draft"),
+            bytes
+        );
+    }
+
+    #[test]
+    fn meta_schemas_are_not_stored() {
+        let validator = crate::validator_for(&json!({"type": "string"})).expect("Invalid schema");
+        let payload: Value = serde_json::from_slice(
+            &validator.to_bytes().expect("Unsupported draft")[HEADER_LENGTH..],
+        )
+        .expect("Invalid JSON");
+        assert_eq!(payload["resources"], json!([]));
+    }
+
+    #[test]
+    fn embedded_resources_are_stored_once() {
+        let schema = json!({
+            "$id": "https://example.com/root.json",
+            "properties": {
+                "name": {"$ref": "name.json"},
+                "address": {"$ref": "https://example.com/address.json"}
+            },
+            "$defs": {"name": {"$id": "name.json", "type": "string"}}
+        });
+        let validator = crate::options()
+            .with_retriever(InMemoryRetriever)
+            .build(&schema)
+            .expect("Invalid schema");
+        let bytes = validator.to_bytes().expect("Unsupported draft");
+        let payload: Value = serde_json::from_slice(&bytes[HEADER_LENGTH..]).expect("Invalid JSON");
+        let uris: Vec<_> = payload["resources"]
+            .as_array()
+            .expect("Resources are stored as an array")
+            .iter()
+            .map(|resource| resource["uri"].as_str().expect("URIs are strings"))
+            .collect();
+        assert_eq!(uris, ["https://example.com/address.json"]);
+        let loaded = Validator::from_bytes(&bytes).expect("Invalid snapshot");
+        assert!(loaded.is_valid(&json!({"name": "Alice", "address": {"city": "Paris"}})));
+        assert!(!loaded.is_valid(&json!({"name": 1})));
+        assert!(!loaded.is_valid(&json!({"address": {"city": ""}})));
+    }
+
+    #[test]
+    fn draft_is_preserved() {
+        let schema = json!({"maximum": 5, "exclusiveMaximum": true});
+        let validator = crate::options()
+            .with_draft(Draft::Draft4)
+            .build(&schema)
+            .expect("Invalid schema");
+        let loaded = roundtrip(&validator);
+        assert!(loaded.is_valid(&json!(4)));
+        assert!(!loaded.is_valid(&json!(5)));
+    }
+
+    #[test]
+    fn options_are_preserved() {
+        let schema = json!({"format": "email", "pattern": "^a+$"});
+        let validator = crate::options()
+            .should_validate_formats(true)
+            .with_pattern_options(PatternOptions::regex())
+            .build(&schema)
+            .expect("Invalid schema");
+        let loaded = roundtrip(&validator);
+        assert!(!loaded.is_valid(&json!("aaa")));
+        assert_eq!(loaded.config.pattern_options(), PatternOptions::regex());
+    }
+
+    #[test]
+    fn custom_formats_are_registered_again() {
+        let schema = json!({"format": "even"});
+        let bytes = crate::options()
+            .with_format("even", |value: &str| value.len() % 2 == 0)
+            .should_validate_formats(true)
+            .build(&schema)
+            .expect("Invalid schema")
+            .to_bytes()
+            .expect("Unsupported draft");
+        let loaded = crate::options()
+            .with_format("even", |value: &str| value.len() % 2 == 0)
+            .from_bytes(&bytes)
+            .expect("Invalid snapshot");
+        assert!(loaded.is_valid(&json!("ab")));
+        assert!(!loaded.is_valid(&json!("abc")));
+    }
+
+    #[test_case(b""; "empty")]
+    #[test_case(b"JSONSCHEMA"; "truncated")]
+    #[test_case(b"{\"type\": \"string\"}"; "plain json")]
+    fn invalid_header(bytes: &[u8]) {
+        assert!(matches!(
+            Validator::from_bytes(bytes),
+            Err(SnapshotError::InvalidHeader)
+        ));
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let validator = crate::validator_for(&json!({})).expect("Invalid schema");
+        let mut bytes = validator.to_bytes().expect("Unsupported draft");
+        bytes[MAGIC.len()..HEADER_LENGTH].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        let error = Validator::from_bytes(&bytes).expect_err("Should fail");
+        assert!(matches!(
+            error,
+            SnapshotError::UnsupportedVersion { expected, found }
+                if expected == FORMAT_VERSION && found == FORMAT_VERSION + 1
+        ));
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Unsupported serialized validator version: {} (expected {FORMAT_VERSION})",
+                FORMAT_VERSION + 1
+            )
+        );
+    }
+
+    #[test_case(b"{}"; "missing fields")]
+    #[test_case(b"{\"draft\": 20"; "truncated")]
+    #[test_case(b"{\"draft\": 3, \"base_uri\": \"json-schema:///\", \"schema\": {}, \"resources\": [], \"options\": {}}"; "unknown draft")]
+    fn malformed(payload: &[u8]) {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(payload);
+        assert!(matches!(
+            Validator::from_bytes(&bytes),
+            Err(SnapshotError::Malformed(_))
+        ));
+    }
+}
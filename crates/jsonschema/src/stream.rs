@@ -0,0 +1,587 @@
+//! Validation of instances read from a byte stream or given as another document model.
+//!
+//! The compiled schema tree is driven by the events of a streaming parser. Arrays and objects are
+//! consumed item by item when every keyword that applies to them knows which subschemas apply to
+//! which children, so only children with subschemas are parsed into a `Value`. Subtrees where a
+//! keyword needs the whole value, e.g. `uniqueItems` or `unevaluatedProperties`, are buffered and
+//! validated as usual.
+use crate::{
+    keywords::BoxedValidator,
+    node::SchemaNode,
+    paths::LazyLocation,
+    validator::Validate,
+    value::{Json, JsonValue},
+    ValidationError,
+};
+use serde::de::{
+    self, Deserialize, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor,
+};
+use serde_json::{value::RawValue, Map, Value};
+use std::fmt;
+
+/// How a validator consumes arrays and objects during streaming validation.
+pub(crate) enum Stream<'a> {
+    /// Needs the whole instance, which is then buffered.
+    Buffered,
+    /// Does not apply to arrays and objects.
+    Scalar,
+    /// Depends only on the type of the instance.
+    Type,
+    /// Does not look at array items or property values. Checked against the instance with every
+    /// item or property value replaced by `null`; errors are reported for the actual instance.
+    Shallow,
+    /// Applies these subschemas to the instance itself.
+    InPlace(&'a [SchemaNode]),
+    /// Applies `prefix` to the leading array items, and the node after the given index to the
+    /// remaining ones.
+    Items {
+        prefix: &'a [SchemaNode],
+        rest: Option<(usize, &'a SchemaNode)>,
+    },
+    /// Applies subschemas to the values of these properties.
+    Properties(&'a [(String, SchemaNode)]),
+}
+
+/// Validate a single JSON document from `deserializer` against `root`, collecting errors into
+/// `errors`.
+pub(crate) fn validate<'de, D: Deserializer<'de>>(
+    root: &SchemaNode,
+    deserializer: D,
+    errors: &mut Vec<ValidationError<'static>>,
+) -> Result<(), D::Error> {
+    Instance {
+        nodes: vec![root],
+        location: &LazyLocation::new(),
+        errors,
+    }
+    .deserialize(deserializer)
+}
+
+/// Validate `instance` against `root`, collecting errors into `errors`.
+pub(crate) fn validate_value<V: JsonValue>(
+    root: &SchemaNode,
+    instance: V,
+    errors: &mut Vec<ValidationError<'static>>,
+) {
+    walk(vec![root], instance, &LazyLocation::new(), errors);
+}
+
+/// Subschemas for leading array items and for the remaining ones, starting at an index.
+type ItemNodes<'a> = (&'a [SchemaNode], Option<(usize, &'a SchemaNode)>);
+
+/// Subschemas that apply to the same instance, collected from the nodes applying to it.
+#[derive(Default)]
+struct Plan<'a> {
+    types: Vec<&'a BoxedValidator>,
+    shallow: Vec<&'a BoxedValidator>,
+    items: Vec<ItemNodes<'a>>,
+    properties: Vec<&'a [(String, SchemaNode)]>,
+}
+
+impl<'a> Plan<'a> {
+    /// A plan for these nodes, if none of their keywords needs the instance to be buffered.
+    fn new(nodes: &[&'a SchemaNode]) -> Option<Plan<'a>> {
+        let mut plan = Plan::default();
+        for node in nodes {
+            plan.add(node)?;
+        }
+        Some(plan)
+    }
+
+    fn add(&mut self, node: &'a SchemaNode) -> Option<()> {
+        for validator in node.streamed_validators()? {
+            match validator.stream() {
+                Stream::Buffered => return None,
+                Stream::Scalar => {}
+                Stream::Type => self.types.push(validator),
+                Stream::Shallow => self.shallow.push(validator),
+                Stream::InPlace(nodes) => {
+                    for node in nodes {
+                        self.add(node)?;
+                    }
+                }
+                Stream::Items { prefix, rest } => self.items.push((prefix, rest)),
+                Stream::Properties(properties) => self.properties.push(properties),
+            }
+        }
+        Some(())
+    }
+
+    fn item_nodes(&self, idx: usize) -> Vec<&'a SchemaNode> {
+        let mut nodes = Vec::new();
+        for (prefix, rest) in &self.items {
+            if let Some(node) = prefix.get(idx) {
+                nodes.push(node);
+            } else if let Some((start, node)) = rest {
+                if idx >= *start {
+                    nodes.push(*node);
+                }
+            }
+        }
+        nodes
+    }
+
+    fn property_nodes(&self, name: &str) -> Vec<&'a SchemaNode> {
+        self.properties
+            .iter()
+            .flat_map(|properties| properties.iter())
+            .filter(|(property, _)| property == name)
+            .map(|(_, node)| node)
+            .collect()
+    }
+
+    /// Whether type keywords reject instances of the same type as `empty`. Such instances are
+    /// validated as a whole, so errors report them as they are.
+    fn rejects_type(&self, empty: &Value) -> bool {
+        !self.types.iter().all(|validator| validator.is_valid(empty))
+    }
+
+    /// Whether the property names of an object are needed once all properties are visited.
+    fn needs_keys(&self) -> bool {
+        !self.shallow.is_empty()
+    }
+
+    /// Validate keywords that don't look at children against `skeleton`, the instance with its
+    /// children replaced by `null`. If any of them fails, they are validated against the actual
+    /// instance built by `instance`, so errors report it rather than the skeleton.
+    fn validate_shallow(
+        &self,
+        skeleton: Value,
+        instance: impl FnOnce() -> Option<Value>,
+        location: &LazyLocation,
+        errors: &mut Vec<ValidationError<'static>>,
+    ) {
+        if self
+            .shallow
+            .iter()
+            .all(|validator| validator.is_valid(&skeleton))
+        {
+            return;
+        }
+        let instance = instance().unwrap_or(skeleton);
+        for validator in &self.shallow {
+            errors.extend(
+                validator
+                    .validate(&instance, location)
+                    .map(ValidationError::into_owned),
+            );
+        }
+    }
+}
+
+fn validate_nodes(
+    nodes: Vec<&SchemaNode>,
+    instance: &Value,
+    location: &LazyLocation,
+    errors: &mut Vec<ValidationError<'static>>,
+) {
+    for node in nodes {
+        errors.extend(
+            node.validate(instance, location)
+                .map(ValidationError::into_owned),
+        );
+    }
+}
+
+/// Validate `instance` against `nodes`, converting to `Value` only the parts that keywords
+/// need as a whole.
+fn walk<V: JsonValue>(
+    nodes: Vec<&SchemaNode>,
+    instance: V,
+    location: &LazyLocation,
+    errors: &mut Vec<ValidationError<'static>>,
+) {
+    if nodes.is_empty() {
+        return;
+    }
+    match (Plan::new(&nodes), instance.as_json()) {
+        (Some(plan), Json::Array(items)) if !plan.rejects_type(&Value::Array(Vec::new())) => {
+            let mut len = 0;
+            for (idx, item) in items.enumerate() {
+                walk(plan.item_nodes(idx), item, &location.push(idx), errors);
+                len += 1;
+            }
+            if !plan.shallow.is_empty() {
+                plan.validate_shallow(
+                    Value::Array(vec![Value::Null; len]),
+                    || instance.as_json().into_value().ok(),
+                    location,
+                    errors,
+                );
+            }
+        }
+        (Some(plan), Json::Object(properties))
+            if !plan.rejects_type(&Value::Object(Map::new())) =>
+        {
+            let needs_keys = plan.needs_keys();
+            let mut skeleton = Map::new();
+            for (key, value) in properties {
+                walk(
+                    plan.property_nodes(&key),
+                    value,
+                    &location.push(key.as_ref()),
+                    errors,
+                );
+                if needs_keys {
+                    skeleton.insert(key.into_owned(), Value::Null);
+                }
+            }
+            if needs_keys {
+                plan.validate_shallow(
+                    Value::Object(skeleton),
+                    || instance.as_json().into_value().ok(),
+                    location,
+                    errors,
+                );
+            }
+        }
+        (_, json) => match json.into_value() {
+            Ok(instance) => validate_nodes(nodes, &instance, location, errors),
+            Err(number) => errors.push(ValidationError::non_finite_number(location.into(), number)),
+        },
+    }
+}
+
+/// An instance that is validated against `nodes` while it is deserialized.
+struct Instance<'a, 'l, 'e> {
+    nodes: Vec<&'a SchemaNode>,
+    location: &'l LazyLocation<'l, 'l>,
+    errors: &'e mut Vec<ValidationError<'static>>,
+}
+
+impl Instance<'_, '_, '_> {
+    fn validate(self, instance: &Value) {
+        validate_nodes(self.nodes, instance, self.location, self.errors);
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for Instance<'_, '_, '_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        if self.nodes.is_empty() {
+            IgnoredAny::deserialize(deserializer)?;
+            return Ok(());
+        }
+        if let Some(plan) = Plan::new(&self.nodes) {
+            if plan.shallow.is_empty() {
+                return deserializer.deserialize_any(InstanceVisitor {
+                    instance: self,
+                    plan,
+                    raw: None,
+                });
+            }
+            // Keywords like `required` report the whole instance if they fail, so its text is
+            // kept until they are validated
+            let raw = Box::<RawValue>::deserialize(deserializer)?;
+            serde_json::Deserializer::from_str(raw.get())
+                .deserialize_any(InstanceVisitor {
+                    instance: self,
+                    plan,
+                    raw: Some(&raw),
+                })
+                .map_err(de::Error::custom)
+        } else {
+            let instance = Value::deserialize(deserializer)?;
+            self.validate(&instance);
+            Ok(())
+        }
+    }
+}
+
+struct InstanceVisitor<'a, 'l, 'e, 'r> {
+    instance: Instance<'a, 'l, 'e>,
+    plan: Plan<'a>,
+    /// The text of the instance, if keywords may need it as a whole.
+    raw: Option<&'r RawValue>,
+}
+
+impl InstanceVisitor<'_, '_, '_, '_> {
+    fn scalar<E: de::Error>(self, value: Value) -> Result<(), E> {
+        self.instance.validate(&value);
+        Ok(())
+    }
+
+    /// Parse the instance from its text.
+    fn parse(raw: Option<&RawValue>) -> Option<Value> {
+        raw.and_then(|raw| serde_json::from_str(raw.get()).ok())
+    }
+}
+
+impl<'de> Visitor<'de> for InstanceVisitor<'_, '_, '_, '_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("any valid JSON value")
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<(), E> {
+        self.scalar(Value::Bool(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<(), E> {
+        self.scalar(Value::from(value))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<(), E> {
+        self.scalar(Value::from(value))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<(), E> {
+        self.scalar(Value::from(value))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<(), E> {
+        self.scalar(Value::String(value.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<(), E> {
+        self.scalar(Value::String(value))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<(), E> {
+        self.scalar(Value::Null)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<(), E> {
+        self.scalar(Value::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let InstanceVisitor {
+            instance,
+            plan,
+            raw,
+        } = self;
+        if plan.rejects_type(&Value::Array(Vec::new())) {
+            let mut items = Vec::new();
+            while let Some(item) = seq.next_element()? {
+                items.push(item);
+            }
+            instance.validate(&Value::Array(items));
+            return Ok(());
+        }
+        let mut idx = 0;
+        loop {
+            let location = instance.location.push(idx);
+            let item = Instance {
+                nodes: plan.item_nodes(idx),
+                location: &location,
+                errors: &mut *instance.errors,
+            };
+            if seq.next_element_seed(item)?.is_none() {
+                break;
+            }
+            idx += 1;
+        }
+        if !plan.shallow.is_empty() {
+            plan.validate_shallow(
+                Value::Array(vec![Value::Null; idx]),
+                || Self::parse(raw),
+                instance.location,
+                instance.errors,
+            );
+        }
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let InstanceVisitor {
+            instance,
+            plan,
+            raw,
+        } = self;
+        if plan.rejects_type(&Value::Object(Map::new())) {
+            let mut properties = Map::new();
+            while let Some((key, value)) = map.next_entry()? {
+                properties.insert(key, value);
+            }
+            instance.validate(&Value::Object(properties));
+            return Ok(());
+        }
+        let needs_keys = plan.needs_keys();
+        let mut skeleton = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let location = instance.location.push(key.as_str());
+            map.next_value_seed(Instance {
+                nodes: plan.property_nodes(&key),
+                location: &location,
+                errors: &mut *instance.errors,
+            })?;
+            if needs_keys {
+                skeleton.insert(key, Value::Null);
+            }
+        }
+        if needs_keys {
+            plan.validate_shallow(
+                Value::Object(skeleton),
+                || Self::parse(raw),
+                instance.location,
+                instance.errors,
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn locations<'a>(errors: impl Iterator<Item = crate::ValidationError<'a>>) -> Vec<String> {
+        let mut locations: Vec<_> = errors
+            .map(|error| {
+                format!(
+                    "{} {} {}",
+                    error.instance_path,
+                    error.schema_path,
+                    error.kind.code()
+                )
+            })
+            .collect();
+        locations.sort();
+        locations
+    }
+
+    #[test_case(&json!({"items": {"type": "integer"}}), &json!([1, "a", 2, null]))]
+    #[test_case(&json!({"prefixItems": [{"type": "string"}], "items": {"minimum": 5}}), &json!([1, 2, 7]))]
+    #[test_case(&json!({"$schema": "http://json-schema.org/draft-07/schema#", "items": [{"type": "string"}, {"const": 1}]}), &json!([1, 2, 3]))]
+    #[test_case(&json!({"properties": {"a": {"type": "string"}, "b": {"items": {"maxLength": 1}}}, "required": ["c"]}), &json!({"a": 1, "b": ["x", "yy"], "d": [1]}))]
+    #[test_case(&json!({"type": "array", "minItems": 5, "maxItems": 1}), &json!([1, 2]))]
+    #[test_case(&json!({"type": "array", "items": {"type": "object"}}), &json!({"a": 1}))]
+    #[test_case(&json!({"type": ["object", "string"], "minProperties": 2}), &json!({"a": 1}))]
+    #[test_case(&json!({"allOf": [{"items": {"type": "string"}}, {"items": {"maxLength": 2}}]}), &json!(["abc", 1]))]
+    #[test_case(&json!({"$ref": "#/$defs/a", "$defs": {"a": {"items": {"$ref": "#/$defs/b"}}, "b": {"required": ["id"]}}}), &json!([{}, {"id": 1}, {}]))]
+    #[test_case(&json!({"properties": {"children": {"items": {"$ref": "#"}}, "name": {"type": "string"}}}), &json!({"name": 1, "children": [{"name": "a", "children": [{"name": 2}]}]}))]
+    #[test_case(&json!({"items": {"uniqueItems": true}}), &json!([[1, 1], [1, 2]]))]
+    #[test_case(&json!({"items": {"properties": {"a": true}, "unevaluatedProperties": false}}), &json!([{"a": 1, "b": 2}]))]
+    #[test_case(&json!({"items": {"additionalProperties": {"type": "string"}}}), &json!([{"a": 1}]))]
+    #[test_case(&json!({"items": false}), &json!([1, 2]))]
+    #[test_case(&json!({"propertyNames": {"maxLength": 1}}), &json!({"a": 1, "bb": 2}))]
+    #[test_case(&json!({"minimum": 5}), &json!(3))]
+    #[test_case(&json!({"type": "string"}), &json!(null))]
+    #[test_case(&json!({"items": {"type": "integer"}}), &json!([1, 2]))]
+    fn matches_validate(schema: &Value, instance: &Value) {
+        let validator = crate::validator_for(schema).expect("Invalid schema");
+        let expected = match validator.validate(instance) {
+            Ok(()) => Vec::new(),
+            Err(errors) => locations(errors),
+        };
+        let bytes = serde_json::to_vec(instance).expect("Invalid JSON");
+        let streamed = match validator.validate_slice(&bytes) {
+            Ok(()) => Vec::new(),
+            Err(errors) => locations(errors),
+        };
+        assert_eq!(streamed, expected);
+        let read = match validator.validate_reader(bytes.as_slice()) {
+            Ok(()) => Vec::new(),
+            Err(errors) => locations(errors),
+        };
+        assert_eq!(read, expected);
+        let walked = match validator.validate_value(instance) {
+            Ok(()) => Vec::new(),
+            Err(errors) => locations(errors),
+        };
+        assert_eq!(walked, expected);
+        #[cfg(feature = "simd-json")]
+        {
+            let mut buffer = bytes.clone();
+            let borrowed = simd_json::to_borrowed_value(&mut buffer).expect("Invalid JSON");
+            let walked = match validator.validate_value(&borrowed) {
+                Ok(()) => Vec::new(),
+                Err(errors) => locations(errors),
+            };
+            assert_eq!(walked, expected);
+            let mut buffer = bytes.clone();
+            let owned = simd_json::to_owned_value(&mut buffer).expect("Invalid JSON");
+            let walked = match validator.validate_value(&owned) {
+                Ok(()) => Vec::new(),
+                Err(errors) => locations(errors),
+            };
+            assert_eq!(walked, expected);
+        }
+    }
+
+    #[test_case(&json!({"items": {"required": ["b"]}}), &json!([{"a": [1, 2]}]), &json!({"a": [1, 2]}))]
+    #[test_case(&json!({"items": {"uniqueItems": true}}), &json!([[1, 1]]), &json!([1, 1]))]
+    #[test_case(&json!({"properties": {"a": {"maximum": 1}}}), &json!({"a": 1.5}), &json!(1.5))]
+    fn converted_instance(schema: &Value, instance: &Value, expected: &Value) {
+        let validator = crate::validator_for(schema).expect("Invalid schema");
+        let errors: Vec<_> = validator
+            .validate_value(instance)
+            .expect_err("Should fail")
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(&*errors[0].instance, expected);
+    }
+
+    #[cfg(feature = "simd-json")]
+    #[test_case(&json!({"items": {"items": {"type": "number"}}}), "/1/0")]
+    #[test_case(&json!({"items": {"uniqueItems": true}}), "/1")]
+    fn non_finite_number(schema: &Value, expected: &str) {
+        use simd_json::OwnedValue;
+
+        let validator = crate::validator_for(schema).expect("Invalid schema");
+        let instance = OwnedValue::Array(Box::new(vec![
+            OwnedValue::from(1.0),
+            OwnedValue::Array(Box::new(vec![OwnedValue::from(f64::NAN)])),
+        ]));
+        let errors: Vec<_> = validator
+            .validate_value(&instance)
+            .expect_err("Should fail")
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].instance_path.as_str(), expected);
+        assert_eq!(errors[0].to_string(), "NaN is not a valid JSON number");
+    }
+
+    #[test_case(&json!({"items": {"required": ["b"]}}), &json!([{"a": [1, 2]}]))]
+    #[test_case(&json!({"items": {"type": "array"}}), &json!([{"a": [1, 2]}]))]
+    #[test_case(&json!({"maxItems": 1}), &json!([[1], 2]))]
+    #[test_case(&json!({"type": "object", "properties": {"a": {"type": "string"}}}), &json!([{"a": 1}]))]
+    #[test_case(&json!({"properties": {"a": {"minProperties": 2, "properties": {"b": {"type": "string"}}}}}), &json!({"a": {"b": 1}}))]
+    #[test_case(&json!({"items": {"propertyNames": {"maxLength": 1}, "maxProperties": 1}}), &json!([{"a": [1], "bb": {"c": 2}}]))]
+    fn shallow_instance(schema: &Value, instance: &Value) {
+        fn reports<'a>(
+            errors: impl Iterator<Item = crate::ValidationError<'a>>,
+        ) -> Vec<(String, Value, String)> {
+            let mut reports: Vec<_> = errors
+                .map(|error| {
+                    (
+                        error.instance_path.to_string(),
+                        error.instance.clone().into_owned(),
+                        error.to_string(),
+                    )
+                })
+                .collect();
+            reports.sort_by(|left, right| (&left.0, &left.2).cmp(&(&right.0, &right.2)));
+            reports
+        }
+
+        let validator = crate::validator_for(schema).expect("Invalid schema");
+        let expected = reports(validator.validate(instance).expect_err("Should fail"));
+        let bytes = serde_json::to_vec(instance).expect("Invalid JSON");
+        for errors in [
+            validator.validate_slice(&bytes),
+            validator.validate_reader(bytes.as_slice()),
+            validator.validate_value(instance),
+        ] {
+            assert_eq!(reports(errors.expect_err("Should fail")), expected);
+        }
+    }
+
+    #[test_case(br#"[1, "a", 2"#)]
+    #[test_case(br#"[1, "a"] 2"#)]
+    fn invalid_json(input: &[u8]) {
+        let validator =
+            crate::validator_for(&json!({"items": {"type": "integer"}})).expect("Invalid schema");
+        let errors: Vec<_> = validator
+            .validate_slice(input)
+            .expect_err("Should fail")
+            .collect();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].instance_path.as_str(), "/1");
+        assert_eq!(errors[1].kind.code(), "jsonParse");
+    }
+}
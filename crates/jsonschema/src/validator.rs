@@ -2,14 +2,18 @@
 //! The main idea is to create a tree from the input JSON Schema. This tree will contain
 //! everything needed to perform such validation in runtime.
 use crate::{
+    defaults::Defaults,
     error::ErrorIterator,
     node::SchemaNode,
-    output::{Annotations, ErrorDescription, Output, OutputUnit},
+    output::{Annotations, ErrorDescription, Output, OutputUnit, Trace},
     paths::LazyLocation,
-    Draft, ValidationError, ValidationOptions,
+    snapshot::{self, SnapshotError},
+    stream::{self, Stream},
+    Draft, JsonValue, ValidationError, ValidationOptions,
 };
+use referencing::Registry;
 use serde_json::Value;
-use std::{collections::VecDeque, sync::Arc};
+use std::{collections::VecDeque, io, sync::Arc};
 
 /// The Validate trait represents a predicate over some JSON value. Some validators are very simple
 /// predicates such as "a value which is a string", whereas others may be much more complex,
@@ -58,24 +62,33 @@ pub(crate) trait Validate: Send + Sync {
     ///
     /// ```rust,ignore
     /// // Note that self.schema is a `SchemaNode` and we use `apply_rooted` to return a `BasicOutput`
-    /// let mut if_result = self.schema.apply_rooted(instance, instance_path);
+    /// let mut if_result = self.schema.apply_rooted(instance, instance_path, trace);
     /// if if_result.is_valid() {
     ///     // here we use the `AddAssign` implementation to combine the results of subschemas
     ///     if_result += self
     ///         .then_schema
-    ///         .apply_rooted(instance, instance_path);
+    ///         .apply_rooted(instance, instance_path, trace);
     ///     // Here we use the `From<BasicOutput> for PartialApplication impl
     ///     if_result.into()
     /// } else {
     ///     self.else_schema
-    ///         .apply_rooted(instance, instance_path)
+    ///         .apply_rooted(instance, instance_path, trace)
     ///         .into()
     /// }
     /// ```
     ///
     /// `BasicOutput` also implements `Sum<BasicOutput>` and `FromIterator<BasicOutput<'a>> for PartialApplication<'a>`
     /// so you can use `sum()` and `collect()` in simple cases.
-    fn apply<'a>(&'a self, instance: &Value, location: &LazyLocation) -> PartialApplication<'a> {
+    ///
+    /// The `trace` is only present when the "detailed" or "verbose" output is requested. It must
+    /// be passed to every `apply_rooted` call as is, so each applied subschema records its own
+    /// output unit in the hierarchy.
+    fn apply<'a>(
+        &'a self,
+        instance: &Value,
+        location: &LazyLocation,
+        _trace: Option<&Trace<'a>>,
+    ) -> PartialApplication<'a> {
         let errors: Vec<ErrorDescription> = self
             .validate(instance, location)
             .map(ErrorDescription::from)
@@ -86,6 +99,27 @@ pub(crate) trait Validate: Send + Sync {
             PartialApplication::invalid_empty(errors)
         }
     }
+
+    /// Collect the `default` values of subschemas that apply to `instance` into `defaults`.
+    ///
+    /// Only validators which are composed of other validators need to implement this method.
+    /// They decide which subschemas contribute defaults, e.g. `properties` adds defaults for
+    /// missing properties and recurses into present ones, while `not` contributes nothing.
+    fn collect_defaults(
+        &self,
+        _instance: &Value,
+        _location: &LazyLocation,
+        _defaults: &mut Defaults,
+    ) {
+    }
+
+    /// How this validator consumes arrays and objects in [`Validator::validate_reader`].
+    ///
+    /// By default the whole instance is buffered. Validators that can check arrays and objects
+    /// without seeing all of their contents at once should override this method.
+    fn stream(&self) -> Stream<'_> {
+        Stream::Buffered
+    }
 }
 
 /// The result of applying a validator to an instance. As explained in the documentation for
@@ -158,6 +192,9 @@ impl<'a> PartialApplication<'a> {
 pub struct Validator {
     pub(crate) root: SchemaNode,
     pub(crate) config: Arc<ValidationOptions>,
+    pub(crate) registry: Arc<Registry>,
+    pub(crate) draft: Draft,
+    pub(crate) base_uri: String,
 }
 
 impl Validator {
@@ -182,6 +219,51 @@ impl Validator {
     pub fn new(schema: &Value) -> Result<Validator, ValidationError<'static>> {
         Self::options().build(schema)
     }
+    /// Serialize the schema and resolved resources of the validator to bytes that can be loaded
+    /// with [`Validator::from_bytes`].
+    ///
+    /// The output contains the root schema, all resolved external resources and the options that
+    /// affect validation. The compiled validators are not part of the output and are built again
+    /// from the schema when loading. Custom formats, keywords, content media types, encodings and
+    /// error formatters are not serialized and should be registered again via
+    /// [`ValidationOptions::from_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SnapshotError::UnsupportedDraft`] if the validator uses a draft that the format
+    /// can't store.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use jsonschema::Validator;
+    /// use serde_json::json;
+    ///
+    /// let validator = jsonschema::validator_for(&json!({"type": "string"}))
+    ///     .expect("Invalid schema");
+    /// let bytes = validator.to_bytes().expect("Unsupported draft");
+    ///
+    /// let loaded = Validator::from_bytes(&bytes).expect("Invalid input");
+    /// assert!(loaded.is_valid(&json!("Hello")));
+    /// assert!(!loaded.is_valid(&json!(42)));
+    /// ```
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SnapshotError> {
+        snapshot::to_bytes(self)
+    }
+    /// Load a validator serialized with [`Validator::to_bytes`] using the default options.
+    ///
+    /// External resources are not retrieved and the schema is not validated against its
+    /// meta-schema, as both happened when the original validator was built. The schema is
+    /// compiled again, so loading saves the retrieval and meta-schema validation time, but not
+    /// the compilation time. For self-contained schemas it may be no faster than building.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is not a serialized validator, was produced by an
+    /// incompatible version of the format, or is malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Validator, SnapshotError> {
+        Self::options().from_bytes(bytes)
+    }
     /// Run validation against `instance` and return an iterator over [`ValidationError`] in the error case.
     #[inline]
     pub fn validate<'i>(&'i self, instance: &'i Value) -> Result<(), ErrorIterator<'i>> {
@@ -189,6 +271,8 @@ impl Validator {
         let mut errors = self.root.validate(instance, &instance_path).peekable();
         if errors.peek().is_none() {
             Ok(())
+        } else if let Some(formatter) = self.config.error_formatter() {
+            Err(Box::new(errors.map(|error| formatter.apply(error))))
         } else {
             Err(Box::new(errors))
         }
@@ -201,6 +285,151 @@ impl Validator {
     pub fn is_valid(&self, instance: &Value) -> bool {
         self.root.is_valid(instance)
     }
+    /// Validate a JSON document read from `reader` without loading all of it into memory.
+    ///
+    /// Arrays and objects are validated item by item as they are read, as long as every keyword
+    /// that applies to them can be checked this way, e.g. `items`, `prefixItems`, `properties`,
+    /// `required`, `minItems` or `type`. Subtrees where other keywords, such as `uniqueItems`,
+    /// `contains` or `unevaluatedProperties`, apply are read into memory and validated as usual.
+    /// Values that no subschema applies to are skipped without being stored.
+    ///
+    /// Errors carry the same `instance_path` as with [`Validator::validate`], though they may
+    /// come in a different order. Errors of keywords that only look at the shape of an array or
+    /// object, like `required`, point to the instance with its items or property values replaced
+    /// by `null`. If the input is not valid JSON, the last error is a
+    /// [`ValidationErrorKind::JSONParse`](crate::error::ValidationErrorKind::JSONParse) error.
+    ///
+    /// The reader is read one byte at a time, so wrap unbuffered readers, like files, in a
+    /// [`std::io::BufReader`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"items": {"properties": {"id": {"type": "integer"}}}});
+    /// let validator = jsonschema::validator_for(&schema).expect("Invalid schema");
+    ///
+    /// let input = br#"[{"id": 1}, {"id": "two"}, {"id": 3}]"#;
+    /// let errors: Vec<_> = validator
+    ///     .validate_reader(&input[..])
+    ///     .expect_err("Should fail")
+    ///     .collect();
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!(errors[0].instance_path.as_str(), "/1/id");
+    /// ```
+    pub fn validate_reader<R: io::Read>(&self, reader: R) -> Result<(), ErrorIterator<'static>> {
+        self.validate_stream(serde_json::Deserializer::from_reader(reader))
+    }
+    /// Validate a JSON document from `bytes` without building a [`Value`] for all of it.
+    ///
+    /// See [`Validator::validate_reader`] for details.
+    pub fn validate_slice(&self, bytes: &[u8]) -> Result<(), ErrorIterator<'static>> {
+        self.validate_stream(serde_json::Deserializer::from_slice(bytes))
+    }
+    /// Validate a document of another document model.
+    ///
+    /// Arrays and objects are walked through [`JsonValue`] in the same way as with
+    /// [`Validator::validate_reader`], and only subtrees that keywords need as a whole, as well
+    /// as scalars, are converted to [`Value`]. NaN and infinite numbers in converted values are
+    /// reported as errors. With the `simd-json` feature, [`JsonValue`] is implemented for
+    /// `simd_json`'s `BorrowedValue` and `OwnedValue`. Keywords themselves only validate
+    /// [`Value`]s, so documents with many scalars gain little over converting them upfront.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    ///
+    /// let validator = jsonschema::validator_for(&json!({"items": {"type": "integer"}}))
+    ///     .expect("Invalid schema");
+    /// let instance = json!([1, "a"]);
+    /// assert!(validator.validate_value(&instance).is_err());
+    /// ```
+    pub fn validate_value<V: JsonValue>(&self, instance: V) -> Result<(), ErrorIterator<'static>> {
+        let mut errors = Vec::new();
+        stream::validate_value(&self.root, instance, &mut errors);
+        self.to_result(errors)
+    }
+    fn validate_stream<'de, R: serde_json::de::Read<'de>>(
+        &self,
+        mut deserializer: serde_json::Deserializer<R>,
+    ) -> Result<(), ErrorIterator<'static>> {
+        let mut errors = Vec::new();
+        if let Err(error) = stream::validate(&self.root, &mut deserializer, &mut errors)
+            .and_then(|()| deserializer.end())
+        {
+            errors.push(ValidationError::json_parse(error));
+        }
+        self.to_result(errors)
+    }
+    fn to_result(
+        &self,
+        mut errors: Vec<ValidationError<'static>>,
+    ) -> Result<(), ErrorIterator<'static>> {
+        if errors.is_empty() {
+            return Ok(());
+        }
+        if let Some(formatter) = self.config.error_formatter() {
+            errors = errors
+                .into_iter()
+                .map(|error| formatter.apply(error))
+                .collect();
+        }
+        Err(Box::new(errors.into_iter()))
+    }
+    /// Insert `default` values for missing properties and array items into `instance`, then
+    /// validate it.
+    ///
+    /// Defaults are taken from subschemas under `properties`, `prefixItems` and array-valued
+    /// `items`, and nested defaults inside inserted values are expanded too. Whether defaults
+    /// inside `if` / `then` / `else`, `anyOf` and `oneOf` are applied is controlled by
+    /// [`ValidationOptions::with_conditional_defaults`]. Conditional branches are chosen based on
+    /// the instance before any defaults are inserted.
+    ///
+    /// Defaults behind `$ref`, `$recursiveRef` and `$dynamicRef` are applied as well, with
+    /// dynamic references resolved in the dynamic scope of the instance location. Inside an
+    /// inserted default value, recursive references are expanded only once, so a recursive schema
+    /// with defaults does not produce an infinitely nested value.
+    ///
+    /// If different defaults apply to the same location, the instance is left unchanged and a
+    /// [`ValidationErrorKind::ConflictingDefaults`](crate::error::ValidationErrorKind::ConflictingDefaults)
+    /// error is returned for each such location.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({
+    ///     "properties": {
+    ///         "host": {"type": "string", "default": "localhost"},
+    ///         "port": {"type": "integer", "default": 8080}
+    ///     }
+    /// });
+    /// let validator = jsonschema::validator_for(&schema).expect("Invalid schema");
+    ///
+    /// let mut instance = json!({"port": 80});
+    /// assert!(validator.validate_and_apply_defaults(&mut instance).is_ok());
+    /// assert_eq!(instance, json!({"host": "localhost", "port": 80}));
+    /// ```
+    pub fn validate_and_apply_defaults<'i>(
+        &'i self,
+        instance: &'i mut Value,
+    ) -> Result<(), ErrorIterator<'i>> {
+        let mut defaults = Defaults::new(self.config.conditional_defaults());
+        self.root
+            .collect_defaults(instance, &LazyLocation::new(), &mut defaults);
+        if let Err(errors) = defaults.apply(instance) {
+            if let Some(formatter) = self.config.error_formatter() {
+                return Err(Box::new(
+                    errors.into_iter().map(|error| formatter.apply(error)),
+                ));
+            }
+            return Err(Box::new(errors.into_iter()));
+        }
+        self.validate(instance)
+    }
     /// Apply the schema and return an [`Output`]. No actual work is done at this point, the
     /// evaluation of the schema is deferred until a method is called on the `Output`. This is
     /// because different output formats will have different performance characteristics.
@@ -263,15 +492,20 @@ impl Validator {
 mod tests {
     use crate::{
         error::{self, no_error, ValidationError},
-        keywords::custom::Keyword,
+        keywords::custom::{Keyword, KeywordAnnotations},
         paths::{LazyLocation, Location},
         primitive_type::PrimitiveType,
-        ErrorIterator, Validator,
+        Draft, ErrorIterator, Validator,
     };
     use fancy_regex::Regex;
     use num_cmp::NumCmp;
     use once_cell::sync::Lazy;
     use serde_json::{json, Map, Value};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use test_case::test_case;
 
     #[cfg(not(target_arch = "wasm32"))]
     fn load(path: &str, idx: usize) -> Value {
@@ -568,6 +802,299 @@ mod tests {
         assert_eq!(error.to_string(), "\"foo\" is not of type \"number\"");
     }
 
+    /// Marks the listed properties as deprecated.
+    struct DeprecatedKeyword {
+        properties: Vec<String>,
+    }
+
+    impl Keyword for DeprecatedKeyword {
+        fn validate<'i>(&self, _: &'i Value, _: &LazyLocation) -> ErrorIterator<'i> {
+            no_error()
+        }
+
+        fn is_valid(&self, _: &Value) -> bool {
+            true
+        }
+
+        fn apply(&self, instance: &Value) -> Option<KeywordAnnotations> {
+            let Value::Object(object) = instance else {
+                return None;
+            };
+            let present: Vec<&String> = self
+                .properties
+                .iter()
+                .filter(|property| object.contains_key(property.as_str()))
+                .collect();
+            if present.is_empty() {
+                None
+            } else {
+                Some(KeywordAnnotations::new(json!(present)).with_evaluated_properties(present))
+            }
+        }
+    }
+
+    fn deprecated_factory<'a>(
+        _: &'a Map<String, Value>,
+        schema: &'a Value,
+        _: Location,
+    ) -> Result<Box<dyn Keyword>, ValidationError<'a>> {
+        let properties = serde_json::from_value(schema.clone()).expect("Invalid keyword value");
+        Ok(Box::new(DeprecatedKeyword { properties }))
+    }
+
+    /// Marks the first `n` items as evaluated.
+    struct HeaderItemsKeyword {
+        count: usize,
+    }
+
+    impl Keyword for HeaderItemsKeyword {
+        fn validate<'i>(&self, _: &'i Value, _: &LazyLocation) -> ErrorIterator<'i> {
+            no_error()
+        }
+
+        fn is_valid(&self, _: &Value) -> bool {
+            true
+        }
+
+        fn apply(&self, _: &Value) -> Option<KeywordAnnotations> {
+            Some(KeywordAnnotations::default().with_evaluated_items(0..self.count))
+        }
+    }
+
+    fn header_items_factory<'a>(
+        _: &'a Map<String, Value>,
+        schema: &'a Value,
+        _: Location,
+    ) -> Result<Box<dyn Keyword>, ValidationError<'a>> {
+        let count = schema.as_u64().expect("Invalid keyword value") as usize;
+        Ok(Box::new(HeaderItemsKeyword { count }))
+    }
+
+    #[test]
+    fn custom_keyword_annotations_in_output() {
+        let schema = json!({"x-deprecated": ["old"]});
+        let validator = crate::options()
+            .with_keyword("x-deprecated", deprecated_factory)
+            .build(&schema)
+            .expect("Invalid schema");
+        let output = validator.apply(&json!({"old": 1, "new": 2})).basic();
+        assert_eq!(
+            serde_json::to_value(output).expect("Failed to serialize"),
+            json!({
+                "valid": true,
+                "annotations": [
+                    {
+                        "keywordLocation": "/x-deprecated",
+                        "instanceLocation": "",
+                        "annotations": ["old"]
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test_case(&json!({"x-deprecated": ["old"], "unevaluatedProperties": false}); "same schema")]
+    #[test_case(&json!({"allOf": [{"x-deprecated": ["old"]}], "unevaluatedProperties": false}); "in subschema")]
+    #[test_case(&json!({"$ref": "#/$defs/a", "$defs": {"a": {"x-deprecated": ["old"]}}, "unevaluatedProperties": false}); "via ref")]
+    fn custom_keyword_evaluated_properties(schema: &Value) {
+        for draft in [Draft::Draft201909, Draft::Draft202012] {
+            let validator = crate::options()
+                .with_draft(draft)
+                .with_keyword("x-deprecated", deprecated_factory)
+                .build(schema)
+                .expect("Invalid schema");
+            assert!(validator.is_valid(&json!({"old": 1})));
+            assert!(validator.validate(&json!({"old": 1})).is_ok());
+            assert!(!validator.is_valid(&json!({"old": 1, "new": 2})));
+        }
+    }
+
+    #[test_case(&json!({"x-header": 2, "unevaluatedItems": false}); "same schema")]
+    #[test_case(&json!({"anyOf": [{"x-header": 2}], "unevaluatedItems": false}); "in subschema")]
+    fn custom_keyword_evaluated_items(schema: &Value) {
+        for draft in [Draft::Draft201909, Draft::Draft202012] {
+            let validator = crate::options()
+                .with_draft(draft)
+                .with_keyword("x-header", header_items_factory)
+                .build(schema)
+                .expect("Invalid schema");
+            assert!(validator.is_valid(&json!(["a", "b"])));
+            assert!(validator.validate(&json!(["a", "b"])).is_ok());
+            assert!(!validator.is_valid(&json!(["a", "b", "c"])));
+        }
+    }
+
+    #[test]
+    fn custom_keyword_initialized_once_with_unevaluated() {
+        let schema = json!({
+            "x-deprecated": ["old"],
+            "allOf": [{"x-deprecated": ["new"]}],
+            "properties": {"nested": {"x-deprecated": ["inner"], "unevaluatedProperties": false}},
+            "unevaluatedProperties": false
+        });
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let validator = crate::options()
+            .with_draft(Draft::Draft202012)
+            .with_keyword("x-deprecated", move |parent, value, location| {
+                counter.fetch_add(1, Ordering::Relaxed);
+                deprecated_factory(parent, value, location)
+            })
+            .build(&schema)
+            .expect("Invalid schema");
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+        assert!(validator.is_valid(&json!({"old": 1, "new": 2, "nested": {"inner": 3}})));
+        assert!(!validator.is_valid(&json!({"old": 1, "other": 2})));
+    }
+
+    #[test]
+    fn error_formatter() {
+        let schema = json!({
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"minimum": 0}
+            }
+        });
+        let validator = crate::options()
+            .with_error_formatter(|error| match &error.kind {
+                error::ValidationErrorKind::Type { .. } => {
+                    Some(format!("{} ({})", error.instance_path, error.schema_path))
+                }
+                _ => None,
+            })
+            .build(&schema)
+            .expect("Invalid schema");
+        let instance = json!({"name": 1, "age": -1});
+        let mut errors: Vec<_> = validator
+            .validate(&instance)
+            .expect_err("Should fail")
+            .collect();
+        errors.sort_by_key(|error| error.instance_path.to_string());
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].to_string(), "-1 is less than the minimum of 0");
+        assert_eq!(errors[1].to_string(), "/name (/properties/name/type)");
+        // Formatted errors keep their kind and locations
+        assert!(matches!(
+            &errors[1].kind,
+            error::ValidationErrorKind::Type { .. }
+        ));
+        assert_eq!(
+            errors[1].message.as_deref(),
+            Some("/name (/properties/name/type)")
+        );
+        assert_eq!(errors[1].instance_path.as_str(), "/name");
+        assert_eq!(errors[1].schema_path.as_str(), "/properties/name/type");
+        // The message is kept when the error is converted to an owned one
+        let error = errors.pop().expect("Two errors").into_owned();
+        assert_eq!(error.to_string(), "/name (/properties/name/type)");
+    }
+
+    #[test]
+    fn error_formatter_with_streamed_documents() {
+        let validator = crate::options()
+            .with_error_formatter(|error| Some(format!("Invalid at {}", error.instance_path)))
+            .build(&json!({"items": {"type": "integer"}}))
+            .expect("Invalid schema");
+        let instance = json!([1, "a"]);
+        let bytes = instance.to_string();
+        for errors in [
+            validator.validate_slice(bytes.as_bytes()),
+            validator.validate_reader(bytes.as_bytes()),
+            validator.validate_value(&instance),
+        ] {
+            let messages: Vec<_> = errors
+                .expect_err("Should fail")
+                .map(|error| error.to_string())
+                .collect();
+            assert_eq!(messages, ["Invalid at /1"]);
+        }
+    }
+
+    #[test]
+    fn error_formatter_with_error_messages() {
+        let schema = json!({"type": "string", "errorMessage": "Enter a text"});
+        let validator = crate::options()
+            .should_use_error_messages(true)
+            .with_error_formatter(|error| {
+                error
+                    .message
+                    .as_ref()
+                    .map(|message| format!("Translated: {message}"))
+            })
+            .build(&schema)
+            .expect("Invalid schema");
+        let instance = json!(1);
+        let messages: Vec<_> = validator
+            .validate(&instance)
+            .expect_err("Should fail")
+            .map(|error| error.to_string())
+            .collect();
+        assert_eq!(messages, ["Translated: Enter a text"]);
+    }
+
+    #[test]
+    fn error_formatter_with_output() {
+        let schema = json!({
+            "properties": {"name": {"type": "string"}},
+            "required": ["age"],
+            "errorMessage": {"required": {"age": "Age is required"}}
+        });
+        let validator = crate::options()
+            .should_use_error_messages(true)
+            .with_error_formatter(|error| match &error.message {
+                Some(message) => Some(format!("Translated: {message}")),
+                None => Some(format!("{} at {}", error.kind.code(), error.instance_path)),
+            })
+            .build(&schema)
+            .expect("Invalid schema");
+        let instance = json!({"name": 1});
+        let output = validator.apply(&instance);
+        let crate::BasicOutput::Invalid(units) = output.basic() else {
+            panic!("Should fail");
+        };
+        let messages: Vec<_> = units
+            .iter()
+            .map(|unit| unit.error_description().to_string())
+            .collect();
+        assert_eq!(messages, ["type at /name", "Translated: Age is required"]);
+        let detailed = serde_json::to_value(output.detailed()).expect("Serializable");
+        let messages: Vec<_> = detailed["errors"]
+            .as_array()
+            .expect("Errors")
+            .iter()
+            .map(|unit| unit["error"].as_str().expect("Message"))
+            .collect();
+        assert_eq!(messages, ["type at /name", "Translated: Age is required"]);
+        fn collect(unit: &crate::output::HierarchicalOutput<'_>, messages: &mut Vec<String>) {
+            messages.extend(unit.error_description().map(ToString::to_string));
+            for child in unit.children() {
+                collect(child, messages);
+            }
+        }
+        let mut messages = Vec::new();
+        collect(&output.verbose(), &mut messages);
+        assert_eq!(messages, ["type at /name", "Translated: Age is required"]);
+    }
+
+    #[test]
+    fn error_formatter_with_defaults() {
+        let schema = json!({
+            "properties": {"a": {"default": 1}},
+            "allOf": [{"properties": {"a": {"default": 2}}}]
+        });
+        let validator = crate::options()
+            .with_error_formatter(|error| Some(format!("Conflict at {}", error.instance_path)))
+            .build(&schema)
+            .expect("Invalid schema");
+        let mut instance = json!({});
+        let messages: Vec<_> = validator
+            .validate_and_apply_defaults(&mut instance)
+            .expect_err("Should fail")
+            .map(|error| error.to_string())
+            .collect();
+        assert_eq!(messages, ["Conflict at /a"]);
+    }
+
     #[test]
     fn test_validator_is_send_and_sync() {
         fn assert_send_sync<T: Send + Sync>() {}
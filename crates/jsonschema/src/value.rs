@@ -0,0 +1,162 @@
+//! Read-only access to JSON documents that are not `serde_json::Value`.
+use serde_json::{Map, Number, Value};
+use std::borrow::Cow;
+
+/// A read-only JSON value of another document model.
+///
+/// Implement it for references to nodes of another document model to validate them with
+/// [`Validator::validate_value`](crate::Validator::validate_value). Keywords still validate
+/// [`Value`]s: arrays and objects are walked item by item, while scalars and the parts of the
+/// document that keywords need as a whole, e.g. for `uniqueItems`, are converted.
+///
+/// # Example
+///
+/// ```rust
+/// use jsonschema::{Json, JsonValue};
+/// use serde_json::json;
+///
+/// enum Node {
+///     Number(u64),
+///     List(Vec<Node>),
+/// }
+///
+/// impl JsonValue for &Node {
+///     fn as_json(&self) -> Json<'_, Self> {
+///         match *self {
+///             Node::Number(value) => Json::Number((*value).into()),
+///             Node::List(items) => Json::Array(Box::new(items.iter())),
+///         }
+///     }
+/// }
+///
+/// let validator = jsonschema::validator_for(&json!({"items": {"maximum": 5}}))
+///     .expect("Invalid schema");
+/// let document = Node::List(vec![Node::Number(1), Node::Number(7)]);
+/// let errors: Vec<_> = validator
+///     .validate_value(&document)
+///     .expect_err("Should fail")
+///     .collect();
+/// assert_eq!(errors[0].instance_path.as_str(), "/1");
+/// ```
+pub trait JsonValue: Sized {
+    /// The type and contents of this value.
+    fn as_json(&self) -> Json<'_, Self>;
+}
+
+/// The type and contents of a [`JsonValue`].
+pub enum Json<'a, V> {
+    /// `null`.
+    Null,
+    /// `true` or `false`.
+    Bool(bool),
+    /// A number.
+    Number(Number),
+    /// A NaN or infinite number, which has no JSON representation and is reported as an error.
+    NonFinite(f64),
+    /// A string.
+    String(Cow<'a, str>),
+    /// An array with its items.
+    Array(Box<dyn Iterator<Item = V> + 'a>),
+    /// An object with its properties.
+    Object(Box<dyn Iterator<Item = (Cow<'a, str>, V)> + 'a>),
+}
+
+impl<V: JsonValue> Json<'_, V> {
+    /// Convert this value with all of its contents to a [`Value`].
+    ///
+    /// Fails with the first number that has no JSON representation.
+    pub(crate) fn into_value(self) -> Result<Value, f64> {
+        Ok(match self {
+            Json::Null => Value::Null,
+            Json::Bool(value) => Value::Bool(value),
+            Json::Number(value) => Value::Number(value),
+            Json::NonFinite(value) => return Err(value),
+            Json::String(value) => Value::String(value.into_owned()),
+            Json::Array(items) => Value::Array(
+                items
+                    .map(|item| item.as_json().into_value())
+                    .collect::<Result<_, _>>()?,
+            ),
+            Json::Object(properties) => Value::Object(
+                properties
+                    .map(|(key, value)| {
+                        value
+                            .as_json()
+                            .into_value()
+                            .map(|value| (key.into_owned(), value))
+                    })
+                    .collect::<Result<Map<_, _>, _>>()?,
+            ),
+        })
+    }
+}
+
+impl JsonValue for &Value {
+    fn as_json(&self) -> Json<'_, Self> {
+        match *self {
+            Value::Null => Json::Null,
+            Value::Bool(value) => Json::Bool(*value),
+            Value::Number(value) => Json::Number(value.clone()),
+            Value::String(value) => Json::String(Cow::Borrowed(value)),
+            Value::Array(items) => Json::Array(Box::new(items.iter())),
+            Value::Object(properties) => Json::Object(Box::new(
+                properties
+                    .iter()
+                    .map(|(key, value)| (Cow::Borrowed(key.as_str()), value)),
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "simd-json")]
+mod simd {
+    use super::{Json, JsonValue};
+    use serde_json::Number;
+    use simd_json::{prelude::*, BorrowedValue, OwnedValue, StaticNode};
+    use std::borrow::Cow;
+
+    fn static_node<'a, V>(node: &StaticNode) -> Json<'a, V> {
+        if node.is_null() {
+            Json::Null
+        } else if let Some(value) = node.as_bool() {
+            Json::Bool(value)
+        } else if let Some(value) = node.as_i64() {
+            Json::Number(value.into())
+        } else if let Some(value) = node.as_u64() {
+            Json::Number(value.into())
+        } else {
+            let value = node.as_f64().unwrap_or(f64::NAN);
+            Number::from_f64(value).map_or(Json::NonFinite(value), Json::Number)
+        }
+    }
+
+    impl JsonValue for &BorrowedValue<'_> {
+        fn as_json(&self) -> Json<'_, Self> {
+            match *self {
+                BorrowedValue::Static(node) => static_node(node),
+                BorrowedValue::String(value) => Json::String(Cow::Borrowed(value)),
+                BorrowedValue::Array(items) => Json::Array(Box::new(items.iter())),
+                BorrowedValue::Object(properties) => Json::Object(Box::new(
+                    properties
+                        .iter()
+                        .map(|(key, value)| (Cow::Borrowed(key.as_ref()), value)),
+                )),
+            }
+        }
+    }
+
+    impl JsonValue for &OwnedValue {
+        fn as_json(&self) -> Json<'_, Self> {
+            match *self {
+                OwnedValue::Static(node) => static_node(node),
+                OwnedValue::String(value) => Json::String(Cow::Borrowed(value)),
+                OwnedValue::Array(items) => Json::Array(Box::new(items.iter())),
+                OwnedValue::Object(properties) => Json::Object(Box::new(
+                    properties
+                        .iter()
+                        .map(|(key, value)| (Cow::Borrowed(key.as_str()), value)),
+                )),
+            }
+        }
+    }
+}
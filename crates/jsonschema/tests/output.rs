@@ -1017,3 +1017,441 @@ fn test_additional_properties_basic_output(
         panic!("\nExpected:\n{}\n\nGot:\n{}\n", expected_str, actual_str);
     }
 }
+
+#[test_case{
+    &json!({"type": "string"}),
+    &json!("some string"),
+    &json!({
+        "valid": true,
+        "keywordLocation": "",
+        "instanceLocation": ""
+    }); "valid without annotations"
+}]
+#[test_case{
+    &json!({"title": "string value", "type": "string"}),
+    &json!("some string"),
+    &json!({
+        "valid": true,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "annotation": {"title": "string value"}
+    }); "valid with annotations"
+}]
+#[test_case{
+    &json!({
+        "anyOf": [
+            {"type": "string"},
+            {"type": "integer", "minimum": 5}
+        ]
+    }),
+    &json!(1),
+    &json!({
+        "valid": false,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "errors": [
+            {
+                "valid": false,
+                "keywordLocation": "/anyOf",
+                "instanceLocation": "",
+                "errors": [
+                    {
+                        "valid": false,
+                        "keywordLocation": "/anyOf/0/type",
+                        "instanceLocation": "",
+                        "error": "1 is not of type \"string\""
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/anyOf/1/minimum",
+                        "instanceLocation": "",
+                        "error": "1 is less than the minimum of 5"
+                    }
+                ]
+            }
+        ]
+    }); "invalid anyOf"
+}]
+#[test_case{
+    &json!({
+        "$id": "https://example.com/polygon",
+        "$defs": {
+            "point": {
+                "type": "object",
+                "properties": {
+                    "x": {"type": "number"},
+                    "y": {"type": "number"}
+                },
+                "required": ["x", "y"]
+            }
+        },
+        "type": "array",
+        "items": {"$ref": "#/$defs/point"},
+        "minItems": 3
+    }),
+    &json!([{"x": 2.5, "y": 1.3}, {"x": 1, "z": 6.7}]),
+    &json!({
+        "valid": false,
+        "keywordLocation": "",
+        "absoluteKeywordLocation": "https://example.com/polygon#",
+        "instanceLocation": "",
+        "errors": [
+            {
+                "valid": false,
+                "keywordLocation": "/items/$ref/required",
+                "absoluteKeywordLocation": "https://example.com/polygon#/items/$ref/required",
+                "instanceLocation": "/1",
+                "error": "\"y\" is a required property"
+            },
+            {
+                "valid": false,
+                "keywordLocation": "/minItems",
+                "absoluteKeywordLocation": "https://example.com/polygon#/minItems",
+                "instanceLocation": "",
+                "error": "[{\"x\":2.5,\"y\":1.3},{\"x\":1,\"z\":6.7}] has less than 3 items"
+            }
+        ]
+    }); "invalid with references"
+}]
+#[test_case{
+    &json!({
+        "properties": {
+            "foo": {"type": "integer"},
+            "bar": {
+                "properties": {
+                    "baz": {"type": "string"},
+                    "spam": {"type": "string"}
+                }
+            }
+        }
+    }),
+    &json!({"foo": "a", "bar": {"baz": 1, "spam": 2}}),
+    &json!({
+        "valid": false,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "errors": [
+            {
+                "valid": false,
+                "keywordLocation": "/properties",
+                "instanceLocation": "",
+                "errors": [
+                    {
+                        "valid": false,
+                        "keywordLocation": "/properties/bar/properties",
+                        "instanceLocation": "/bar",
+                        "errors": [
+                            {
+                                "valid": false,
+                                "keywordLocation": "/properties/bar/properties/baz/type",
+                                "instanceLocation": "/bar/baz",
+                                "error": "1 is not of type \"string\""
+                            },
+                            {
+                                "valid": false,
+                                "keywordLocation": "/properties/bar/properties/spam/type",
+                                "instanceLocation": "/bar/spam",
+                                "error": "2 is not of type \"string\""
+                            }
+                        ]
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/properties/foo/type",
+                        "instanceLocation": "/foo",
+                        "error": "\"a\" is not of type \"integer\""
+                    }
+                ]
+            }
+        ]
+    }); "invalid nested properties"
+}]
+#[test_case{
+    &json!({"properties": {"name": {"minLength": 3}}}),
+    &json!({"name": "Jo"}),
+    &json!({
+        "valid": false,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "errors": [
+            {
+                "valid": false,
+                "keywordLocation": "/properties/name/minLength",
+                "instanceLocation": "/name",
+                "error": "\"Jo\" is shorter than 3 characters"
+            }
+        ]
+    }); "root applies to the whole instance"
+}]
+#[test_case{
+    &json!({
+        "properties": {
+            "name": {"title": "Name"},
+            "tags": {"items": {"title": "Tag"}}
+        }
+    }),
+    &json!({"name": "x", "tags": ["a"]}),
+    &json!({
+        "valid": true,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "annotations": [
+            {
+                "valid": true,
+                "keywordLocation": "/properties",
+                "instanceLocation": "",
+                "annotation": ["name", "tags"],
+                "annotations": [
+                    {
+                        "valid": true,
+                        "keywordLocation": "/properties/name",
+                        "instanceLocation": "/name",
+                        "annotation": {"title": "Name"}
+                    },
+                    {
+                        "valid": true,
+                        "keywordLocation": "/properties/tags/items",
+                        "instanceLocation": "/tags",
+                        "annotation": true,
+                        "annotations": [
+                            {
+                                "valid": true,
+                                "keywordLocation": "/properties/tags/items",
+                                "instanceLocation": "/tags/0",
+                                "annotation": {"title": "Tag"}
+                            }
+                        ]
+                    }
+                ]
+            }
+        ]
+    }); "valid nested annotations"
+}]
+#[test_case{
+    &json!({
+        "oneOf": [
+            {"type": "integer"},
+            {"minimum": 0}
+        ]
+    }),
+    &json!(1),
+    &json!({
+        "valid": false,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "errors": [
+            {
+                "valid": false,
+                "keywordLocation": "/oneOf",
+                "instanceLocation": "",
+                "error": "more than one subschema succeeded"
+            }
+        ]
+    }); "valid subschemas of a failed keyword are left out"
+}]
+fn test_detailed_output(
+    schema: &serde_json::Value,
+    instance: &serde_json::Value,
+    expected: &serde_json::Value,
+) {
+    let validator = jsonschema::validator_for(schema).unwrap();
+    let output = serde_json::to_value(validator.apply(instance).detailed()).unwrap();
+    if &output != expected {
+        let expected_str = serde_json::to_string_pretty(expected).unwrap();
+        let actual_str = serde_json::to_string_pretty(&output).unwrap();
+        panic!("\nExpected:\n{}\n\nGot:\n{}\n", expected_str, actual_str);
+    }
+}
+
+#[test_case{
+    &json!({"title": "string value", "type": "string"}),
+    &json!("some string"),
+    &json!({
+        "valid": true,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "annotation": {"title": "string value"},
+        "annotations": [
+            {
+                "valid": true,
+                "keywordLocation": "/type",
+                "instanceLocation": ""
+            }
+        ]
+    }); "valid with annotations"
+}]
+#[test_case{
+    &json!({
+        "anyOf": [
+            {"type": "string"},
+            {"type": "integer", "minimum": 5}
+        ]
+    }),
+    &json!(1),
+    &json!({
+        "valid": false,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "errors": [
+            {
+                "valid": false,
+                "keywordLocation": "/anyOf",
+                "instanceLocation": "",
+                "errors": [
+                    {
+                        "valid": false,
+                        "keywordLocation": "/anyOf/0",
+                        "instanceLocation": "",
+                        "errors": [
+                            {
+                                "valid": false,
+                                "keywordLocation": "/anyOf/0/type",
+                                "instanceLocation": "",
+                                "error": "1 is not of type \"string\""
+                            }
+                        ]
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/anyOf/1",
+                        "instanceLocation": "",
+                        "errors": [
+                            {
+                                "valid": false,
+                                "keywordLocation": "/anyOf/1/minimum",
+                                "instanceLocation": "",
+                                "error": "1 is less than the minimum of 5"
+                            },
+                            {
+                                "valid": true,
+                                "keywordLocation": "/anyOf/1/type",
+                                "instanceLocation": ""
+                            }
+                        ]
+                    }
+                ]
+            }
+        ]
+    }); "invalid anyOf"
+}]
+#[test_case{
+    &json!({
+        "items": {"type": "integer"},
+        "title": "numbers"
+    }),
+    &json!([1, "a", "b"]),
+    &json!({
+        "valid": false,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "errors": [
+            {
+                "valid": false,
+                "keywordLocation": "/items",
+                "instanceLocation": "",
+                "errors": [
+                    {
+                        "valid": true,
+                        "keywordLocation": "/items",
+                        "instanceLocation": "/0",
+                        "annotations": [
+                            {
+                                "valid": true,
+                                "keywordLocation": "/items/type",
+                                "instanceLocation": "/0"
+                            }
+                        ]
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/items",
+                        "instanceLocation": "/1",
+                        "errors": [
+                            {
+                                "valid": false,
+                                "keywordLocation": "/items/type",
+                                "instanceLocation": "/1",
+                                "error": "\"a\" is not of type \"integer\""
+                            }
+                        ]
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/items",
+                        "instanceLocation": "/2",
+                        "errors": [
+                            {
+                                "valid": false,
+                                "keywordLocation": "/items/type",
+                                "instanceLocation": "/2",
+                                "error": "\"b\" is not of type \"integer\""
+                            }
+                        ]
+                    }
+                ]
+            }
+        ]
+    }); "invalid items"
+}]
+#[test_case{
+    &json!({
+        "properties": {
+            "name": {"minLength": 3},
+            "age": {"title": "Age", "minimum": 0}
+        }
+    }),
+    &json!({"name": "Jo", "age": 1}),
+    &json!({
+        "valid": false,
+        "keywordLocation": "",
+        "instanceLocation": "",
+        "errors": [
+            {
+                "valid": false,
+                "keywordLocation": "/properties",
+                "instanceLocation": "",
+                "errors": [
+                    {
+                        "valid": true,
+                        "keywordLocation": "/properties/age",
+                        "instanceLocation": "/age",
+                        "annotation": {"title": "Age"},
+                        "annotations": [
+                            {
+                                "valid": true,
+                                "keywordLocation": "/properties/age/minimum",
+                                "instanceLocation": "/age"
+                            }
+                        ]
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/properties/name",
+                        "instanceLocation": "/name",
+                        "errors": [
+                            {
+                                "valid": false,
+                                "keywordLocation": "/properties/name/minLength",
+                                "instanceLocation": "/name",
+                                "error": "\"Jo\" is shorter than 3 characters"
+                            }
+                        ]
+                    }
+                ]
+            }
+        ]
+    }); "valid and invalid properties"
+}]
+fn test_verbose_output(
+    schema: &serde_json::Value,
+    instance: &serde_json::Value,
+    expected: &serde_json::Value,
+) {
+    let validator = jsonschema::validator_for(schema).unwrap();
+    let output = serde_json::to_value(validator.apply(instance).verbose()).unwrap();
+    if &output != expected {
+        let expected_str = serde_json::to_string_pretty(expected).unwrap();
+        let actual_str = serde_json::to_string_pretty(&output).unwrap();
+        panic!("\nExpected:\n{}\n\nGot:\n{}\n", expected_str, actual_str);
+    }
+}
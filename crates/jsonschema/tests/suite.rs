@@ -1,7 +1,7 @@
 #[cfg(not(target_arch = "wasm32"))]
 mod tests {
-    use jsonschema::Draft;
-    use std::fs;
+    use jsonschema::{Draft, Resource};
+    use std::{fs, path::Path};
     use testsuite::{suite, Test};
 
     #[suite(
@@ -175,4 +175,63 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_output_suite() {
+        for draft in ["draft2019-09", "draft2020-12"] {
+            let root = Path::new("tests/suite/output-tests").join(draft);
+            let output_schema: serde_json::Value = serde_json::from_str(
+                &fs::read_to_string(root.join("output-schema.json")).expect("Valid file"),
+            )
+            .expect("Valid JSON");
+            let output_schema_id = output_schema["$id"]
+                .as_str()
+                .expect("Output schema has an ID")
+                .to_string();
+            for entry in fs::read_dir(root.join("content")).expect("Valid directory") {
+                let path = entry.expect("Valid entry").path();
+                let cases: serde_json::Value =
+                    serde_json::from_str(&fs::read_to_string(&path).expect("Valid file"))
+                        .expect("Valid JSON");
+                for case in cases.as_array().expect("Is array") {
+                    let validator = jsonschema::validator_for(&case["schema"])
+                        .unwrap_or_else(|_| panic!("Valid schema. File: {}", path.display()));
+                    for test in case["tests"].as_array().expect("Is array") {
+                        let instance = &test["data"];
+                        for (format, expected) in test["output"].as_object().expect("Is object") {
+                            let output = match format.as_str() {
+                                "detailed" => {
+                                    serde_json::to_value(validator.apply(instance).detailed())
+                                }
+                                "verbose" => {
+                                    serde_json::to_value(validator.apply(instance).verbose())
+                                }
+                                // The "basic" format emits annotations as they were produced by
+                                // keywords instead of nested output units
+                                _ => continue,
+                            }
+                            .expect("Output is serializable");
+                            let output_validator = jsonschema::options()
+                                .with_resource(
+                                    output_schema_id.as_str(),
+                                    Resource::from_contents(output_schema.clone())
+                                        .expect("Valid resource"),
+                                )
+                                .build(expected)
+                                .expect("Valid output schema");
+                            assert!(
+                                output_validator.is_valid(&output),
+                                "\nFile: {}\nCase: {}\nTest: {}\nFormat: {}\nOutput: {}",
+                                path.display(),
+                                case["description"],
+                                test["description"],
+                                format,
+                                pretty_json(&output),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
 }